@@ -0,0 +1,12 @@
+-- Retry bookkeeping for the Omni notification queue
+-- Failed sends are rescheduled with backoff until max_attempts is reached
+
+ALTER TABLE forge_omni_notifications ADD COLUMN attempt_count INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE forge_omni_notifications ADD COLUMN max_attempts INTEGER NOT NULL DEFAULT 5;
+ALTER TABLE forge_omni_notifications ADD COLUMN next_attempt_at DATETIME;
+
+-- JSON array of {attempt, error, at} objects, one per failed send
+ALTER TABLE forge_omni_notifications ADD COLUMN attempt_errors TEXT NOT NULL DEFAULT '[]';
+
+CREATE INDEX IF NOT EXISTS idx_forge_omni_notifications_next_attempt
+    ON forge_omni_notifications(status, next_attempt_at);
@@ -0,0 +1,7 @@
+-- Lease tracking for the Omni notification queue
+-- Rows stuck in 'processing' past the lease timeout are returned to 'pending'
+
+ALTER TABLE forge_omni_notifications ADD COLUMN claimed_at DATETIME;
+
+CREATE INDEX IF NOT EXISTS idx_forge_omni_notifications_claimed_at
+    ON forge_omni_notifications(status, claimed_at);
@@ -0,0 +1,5 @@
+-- Per-channel delivery results for queued notifications
+-- JSON object keyed by channel label: {"omni": {"status": "sent", "at": "..."}, ...}
+-- Retries only resend to channels that have not succeeded yet
+
+ALTER TABLE forge_omni_notifications ADD COLUMN deliveries TEXT NOT NULL DEFAULT '{}';
@@ -0,0 +1,10 @@
+-- Digest mode for the Omni notification queue
+-- Rows waiting for a digest have status 'batched' and are grouped by digest_key (the project id);
+-- next_attempt_at holds the moment the group's window closes.
+-- Every row delivered in the same summary message shares one digest_id.
+
+ALTER TABLE forge_omni_notifications ADD COLUMN digest_key TEXT;
+ALTER TABLE forge_omni_notifications ADD COLUMN digest_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_forge_omni_notifications_digest
+    ON forge_omni_notifications(status, digest_key);
@@ -0,0 +1,4 @@
+-- The notification log is paged newest first and purged by age
+
+CREATE INDEX IF NOT EXISTS idx_forge_omni_notifications_created_at
+    ON forge_omni_notifications(created_at);
@@ -0,0 +1,16 @@
+-- Health history of the configured Omni instance, written by the health monitor.
+-- While the latest check of an instance is unhealthy, notifications to it stay
+-- pending instead of using up their attempts.
+
+CREATE TABLE IF NOT EXISTS forge_omni_health_checks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    instance TEXT NOT NULL,
+    healthy INTEGER NOT NULL,
+    status TEXT,
+    error_message TEXT,
+    latency_ms INTEGER,
+    checked_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_forge_omni_health_checks_instance
+    ON forge_omni_health_checks(instance, checked_at);
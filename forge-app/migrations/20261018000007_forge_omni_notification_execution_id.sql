@@ -0,0 +1,40 @@
+-- Execution process a notification was queued for, so the completion trigger can
+-- skip duplicates through an index instead of scanning metadata.
+-- Holds lower(hex(execution_processes.id)); NULL for events without a process.
+
+ALTER TABLE forge_omni_notifications ADD COLUMN execution_process_id TEXT;
+
+-- Rows queued so far only recorded the attempt, the status and the run reason;
+-- match them to the process of that attempt that finished closest to the row
+UPDATE forge_omni_notifications
+   SET execution_process_id = (
+       SELECT process_id
+         FROM (
+             SELECT lower(hex(ep.id)) AS process_id,
+                    abs(julianday(COALESCE(ep.completed_at, ep.updated_at, ep.started_at))
+                        - julianday(forge_omni_notifications.created_at)) AS distance
+               FROM execution_processes ep
+              WHERE lower(hex(ep.task_attempt_id)) = json_extract(forge_omni_notifications.metadata, '$.task_attempt_id')
+                AND ep.status = json_extract(forge_omni_notifications.metadata, '$.status')
+                AND ep.run_reason IS json_extract(forge_omni_notifications.metadata, '$.run_reason')
+         )
+        ORDER BY distance
+        LIMIT 1
+   )
+ WHERE notification_type = 'execution_completed'
+   AND json_valid(metadata);
+
+-- Keep the oldest row when several matched the same process
+UPDATE forge_omni_notifications
+   SET execution_process_id = NULL
+ WHERE execution_process_id IS NOT NULL
+   AND rowid NOT IN (
+       SELECT MIN(rowid)
+         FROM forge_omni_notifications
+        WHERE execution_process_id IS NOT NULL
+        GROUP BY execution_process_id
+   );
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_forge_omni_notifications_execution_process
+    ON forge_omni_notifications(execution_process_id)
+    WHERE execution_process_id IS NOT NULL;
@@ -0,0 +1,14 @@
+-- Per-task settings, merged field by field over the project and global settings
+
+CREATE TABLE IF NOT EXISTS forge_task_settings (
+    task_id TEXT PRIMARY KEY REFERENCES tasks(id) ON DELETE CASCADE,
+    forge_config TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TRIGGER IF NOT EXISTS update_forge_task_settings_updated_at
+AFTER UPDATE ON forge_task_settings
+BEGIN
+    UPDATE forge_task_settings SET updated_at = CURRENT_TIMESTAMP WHERE task_id = NEW.task_id;
+END;
@@ -0,0 +1,16 @@
+-- Every write of global, project or task settings, so changes can be audited and
+-- rolled back. Secret fields are stored redacted.
+
+CREATE TABLE IF NOT EXISTS forge_settings_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    scope TEXT NOT NULL CHECK (scope IN ('global', 'project', 'task')),
+    -- Project or task id; NULL for the global settings
+    scope_id BLOB,
+    old_value TEXT,
+    new_value TEXT NOT NULL,
+    source TEXT NOT NULL CHECK (source IN ('api', 'mcp', 'import')),
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_forge_settings_history_scope
+    ON forge_settings_history(scope, scope_id, id);
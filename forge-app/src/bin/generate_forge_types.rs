@@ -1,8 +1,23 @@
 use std::{env, fs, path::Path};
 
 use anyhow::{Context, Result, bail};
-use forge_config::{ForgeProjectSettings, ProjectConfig};
-use forge_omni::{OmniConfig, OmniInstance, RecipientType, SendTextRequest, SendTextResponse};
+use forge_config::bundle::{BundledProject, BundledTask, BundledTaskTemplate};
+use forge_config::{
+    ConfigOrigin, CustomExecutors, EffectiveSettings, ExecutorOverlay, ExecutorOverrides,
+    ExecutorVariant, ForgeProjectSettings, ImportConflict, ImportConflictKind, ProjectBundle,
+    ProjectConfig, ProjectImportReport, ProjectImportRequest, SettingsHistoryEntry, SettingsScope,
+    SettingsSource,
+};
+use forge_notifications::{
+    CommandChannelConfig, EmailChannelConfig, NotificationChannelConfig, NotificationRule,
+    NotificationType, SlackChannelConfig, WebhookChannelConfig,
+};
+use forge_omni::{
+    OmniAttachmentMode, OmniConfig, OmniDelivery, OmniDigestConfig, OmniHttpConfig,
+    OmniInboundConfig, OmniInstance, OmniMediaType, OmniMessageConfig, OmniRateLimitConfig,
+    OmniRecipient, QuietHoursAction, QuietHoursConfig, RecipientType, SendDocumentRequest,
+    SendMediaRequest, SendTextRequest, SendTextResponse,
+};
 use ts_rs::TS;
 
 fn main() -> Result<()> {
@@ -12,11 +27,48 @@ fn main() -> Result<()> {
     let declarations = vec![
         ForgeProjectSettings::decl(),
         ProjectConfig::decl(),
+        CustomExecutors::decl(),
+        ExecutorOverlay::decl(),
+        ExecutorVariant::decl(),
+        ExecutorOverrides::decl(),
+        EffectiveSettings::decl(),
+        ConfigOrigin::decl(),
+        SettingsHistoryEntry::decl(),
+        SettingsScope::decl(),
+        SettingsSource::decl(),
+        ProjectBundle::decl(),
+        BundledProject::decl(),
+        BundledTaskTemplate::decl(),
+        BundledTask::decl(),
+        ProjectImportRequest::decl(),
+        ProjectImportReport::decl(),
+        ImportConflict::decl(),
+        ImportConflictKind::decl(),
         OmniConfig::decl(),
+        OmniRecipient::decl(),
+        OmniDigestConfig::decl(),
+        QuietHoursConfig::decl(),
+        QuietHoursAction::decl(),
+        OmniRateLimitConfig::decl(),
+        OmniInboundConfig::decl(),
+        OmniHttpConfig::decl(),
+        OmniMessageConfig::decl(),
+        OmniAttachmentMode::decl(),
         RecipientType::decl(),
         OmniInstance::decl(),
         SendTextRequest::decl(),
         SendTextResponse::decl(),
+        OmniMediaType::decl(),
+        SendMediaRequest::decl(),
+        SendDocumentRequest::decl(),
+        OmniDelivery::decl(),
+        NotificationChannelConfig::decl(),
+        SlackChannelConfig::decl(),
+        EmailChannelConfig::decl(),
+        CommandChannelConfig::decl(),
+        WebhookChannelConfig::decl(),
+        NotificationRule::decl(),
+        NotificationType::decl(),
     ];
 
     let body = declarations
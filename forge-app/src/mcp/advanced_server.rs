@@ -8,6 +8,7 @@ use db::models::{
     task_attempt::TaskAttempt,
 };
 use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use forge_config::{SettingsSource, history::SOURCE_HEADER};
 use rmcp::{
     ErrorData, ServerHandler,
     handler::server::tool::{Parameters, ToolRouter},
@@ -25,9 +26,11 @@ use super::advanced_tools::{
     DeleteDraftRequest, DeleteFileRequest, FollowUpRequest, GetBranchStatusRequest,
     GetChildrenRequest, GetCommitInfoRequest, GetContainerRequest, GetDraftRequest, GetFileRequest,
     GetImageRequest, GetProcessRequest, GetProjectSettingsRequest, ListDraftsRequest,
-    ListProcessesRequest, MergeTaskAttemptRequest, OpenEditorRequest, PushTaskAttemptRequest,
-    RebaseTaskAttemptRequest, ReplaceProcessRequest, SaveDraftRequest, SetDraftQueueRequest,
-    StartDevServerRequest, StopProcessRequest, UpdateConfigRequest, UpdateDraftRequest,
+    ListOmniNotificationsRequest, ListProcessesRequest, MergeTaskAttemptRequest,
+    OmniNotificationRequest, OpenEditorRequest, PurgeOmniNotificationsRequest,
+    PushTaskAttemptRequest, RebaseTaskAttemptRequest, ReplaceProcessRequest,
+    RotateSecretKeyRequest, SaveDraftRequest, SetDraftQueueRequest, StartDevServerRequest,
+    StopProcessRequest, TestOmniNotificationRequest, UpdateConfigRequest, UpdateDraftRequest,
     UpdateProjectSettingsRequest, UploadImageRequest, UploadImageResponse,
     ValidateOmniConfigRequest,
 };
@@ -249,7 +252,9 @@ pub struct StartTaskAttemptRequest {
         description = "The coding agent executor to run ('CLAUDE_CODE', 'CODEX', 'GEMINI', 'CURSOR', 'OPENCODE')"
     )]
     pub executor: String,
-    #[schemars(description = "Optional executor variant, if needed")]
+    #[schemars(
+        description = "Optional executor variant, upstream or one of the project's custom executors; the project's default variant when unset"
+    )]
     pub variant: Option<String>,
     #[schemars(description = "The base branch to use for the attempt")]
     pub base_branch: String,
@@ -687,7 +692,7 @@ impl ForgeAdvancedServer {
             if trimmed.is_empty() {
                 None
             } else {
-                Some(trimmed.to_string())
+                Some(trimmed.replace('-', "_").to_ascii_uppercase())
             }
         });
 
@@ -852,7 +857,7 @@ impl ForgeAdvancedServer {
             if trimmed.is_empty() {
                 None
             } else {
-                Some(trimmed.to_string())
+                Some(trimmed.replace('-', "_").to_ascii_uppercase())
             }
         });
 
@@ -1709,7 +1714,12 @@ impl ForgeAdvancedServer {
     ) -> Result<CallToolResult, ErrorData> {
         let url = self.url("/api/forge/config");
         if let Err(e) = self
-            .send_json::<serde_json::Value>(self.client.put(&url).json(&config))
+            .send_json::<serde_json::Value>(
+                self.client
+                    .put(&url)
+                    .header(SOURCE_HEADER, SettingsSource::Mcp.as_str())
+                    .json(&config),
+            )
             .await
         {
             return Ok(e);
@@ -1745,7 +1755,12 @@ impl ForgeAdvancedServer {
     ) -> Result<CallToolResult, ErrorData> {
         let url = self.url(&format!("/api/forge/projects/{}/settings", project_id));
         if let Err(e) = self
-            .send_json::<serde_json::Value>(self.client.put(&url).json(&settings))
+            .send_json::<serde_json::Value>(
+                self.client
+                    .put(&url)
+                    .header(SOURCE_HEADER, SettingsSource::Mcp.as_str())
+                    .json(&settings),
+            )
             .await
         {
             return Ok(e);
@@ -1794,15 +1809,104 @@ impl ForgeAdvancedServer {
         ForgeAdvancedServer::success(&result)
     }
 
-    #[tool(description = "List all Omni notifications")]
-    async fn list_omni_notifications(&self) -> Result<CallToolResult, ErrorData> {
+    #[tool(
+        description = "Send a test Omni notification and return Omni's response for each recipient"
+    )]
+    async fn test_omni_notification(
+        &self,
+        Parameters(request): Parameters<TestOmniNotificationRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url("/api/forge/omni/test");
+        let result: serde_json::Value =
+            match self.send_json(self.client.post(&url).json(&request)).await {
+                Ok(r) => r,
+                Err(e) => return Ok(e),
+            };
+
+        ForgeAdvancedServer::success(&result)
+    }
+
+    #[tool(description = "List Omni notifications, newest first, with optional filters and paging")]
+    async fn list_omni_notifications(
+        &self,
+        Parameters(request): Parameters<ListOmniNotificationsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
         let url = self.url("/api/forge/omni/notifications");
-        let notifications: serde_json::Value = match self.send_json(self.client.get(&url)).await {
-            Ok(n) => n,
+        let notifications: serde_json::Value =
+            match self.send_json(self.client.get(&url).query(&request)).await {
+                Ok(n) => n,
+                Err(e) => return Ok(e),
+            };
+
+        ForgeAdvancedServer::success(&notifications)
+    }
+
+    #[tool(description = "Queue a failed or skipped Omni notification for delivery again")]
+    async fn resend_omni_notification(
+        &self,
+        Parameters(OmniNotificationRequest { notification_id }): Parameters<
+            OmniNotificationRequest,
+        >,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!(
+            "/api/forge/omni/notifications/{}/resend",
+            urlencoding::encode(&notification_id)
+        ));
+        let result: serde_json::Value = match self.send_json(self.client.post(&url)).await {
+            Ok(r) => r,
             Err(e) => return Ok(e),
         };
 
-        ForgeAdvancedServer::success(&notifications)
+        ForgeAdvancedServer::success(&result)
+    }
+
+    #[tool(description = "Cancel a pending Omni notification")]
+    async fn cancel_omni_notification(
+        &self,
+        Parameters(OmniNotificationRequest { notification_id }): Parameters<
+            OmniNotificationRequest,
+        >,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url(&format!(
+            "/api/forge/omni/notifications/{}/cancel",
+            urlencoding::encode(&notification_id)
+        ));
+        let result: serde_json::Value = match self.send_json(self.client.post(&url)).await {
+            Ok(r) => r,
+            Err(e) => return Ok(e),
+        };
+
+        ForgeAdvancedServer::success(&result)
+    }
+
+    #[tool(description = "Delete finished Omni notifications older than a number of days")]
+    async fn purge_omni_notifications(
+        &self,
+        Parameters(request): Parameters<PurgeOmniNotificationsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url("/api/forge/omni/notifications/purge");
+        let result: serde_json::Value =
+            match self.send_json(self.client.post(&url).json(&request)).await {
+                Ok(r) => r,
+                Err(e) => return Ok(e),
+            };
+
+        ForgeAdvancedServer::success(&result)
+    }
+
+    #[tool(description = "Re-encrypt stored secrets such as the Omni API key with a new key")]
+    async fn rotate_secret_key(
+        &self,
+        Parameters(request): Parameters<RotateSecretKeyRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let url = self.url("/api/forge/secrets/rotate");
+        let result: serde_json::Value =
+            match self.send_json(self.client.post(&url).json(&request)).await {
+                Ok(r) => r,
+                Err(e) => return Ok(e),
+            };
+
+        ForgeAdvancedServer::success(&result)
     }
 }
 
@@ -1820,7 +1924,7 @@ impl ServerHandler for ForgeAdvancedServer {
                 name: "automagik-forge".to_string(),
                 version: env!("CARGO_PKG_VERSION").to_string(),
             },
-            instructions: Some("A task and project management server with full backend API access. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_task_attempt', 'get_task', 'update_task', 'delete_task', plus 49 advanced tools for projects, task attempts, processes, drafts, containers, filesystem, and Omni integration. Make sure to pass `project_id` or `task_id` where required.".to_string()),
+            instructions: Some("A task and project management server with full backend API access. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_task_attempt', 'get_task', 'update_task', 'delete_task', plus 54 advanced tools for projects, task attempts, processes, drafts, containers, filesystem, and Omni integration. Make sure to pass `project_id` or `task_id` where required.".to_string()),
         }
     }
 }
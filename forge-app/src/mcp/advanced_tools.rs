@@ -11,9 +11,9 @@
 //! - Config (2 tools)
 //! - Drafts (5 tools)
 //! - Containers (2 tools)
-//! - Forge-Specific (8 tools)
+//! - Forge-Specific (13 tools)
 //!
-//! Total Advanced Tools: 49 additional tools
+//! Total Advanced Tools: 54 additional tools
 
 use rmcp::schemars;
 use serde::{Deserialize, Serialize};
@@ -296,7 +296,7 @@ pub struct GetContainerRequest {
 }
 
 // ============================================================================
-// FORGE-SPECIFIC (8 tools)
+// FORGE-SPECIFIC (13 tools)
 // ============================================================================
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -327,6 +327,52 @@ pub struct ValidateOmniConfigRequest {
     pub api_key: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct TestOmniNotificationRequest {
+    #[schemars(description = "Omni configuration to test; defaults to the saved configuration")]
+    pub config: Option<serde_json::Value>,
+    #[schemars(description = "Use this project's effective Omni configuration")]
+    pub project_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ListOmniNotificationsRequest {
+    #[schemars(description = "Only notifications with this status (e.g., 'failed', 'pending')")]
+    pub status: Option<String>,
+    #[schemars(description = "Only notifications for this task")]
+    pub task_id: Option<Uuid>,
+    #[schemars(description = "Only notifications for this project")]
+    pub project_id: Option<Uuid>,
+    #[schemars(description = "Only notifications created at or after this RFC 3339 time")]
+    pub since: Option<String>,
+    #[schemars(description = "Only notifications created before this RFC 3339 time")]
+    pub until: Option<String>,
+    #[schemars(description = "Page size (default 50, max 200)")]
+    pub limit: Option<i64>,
+    #[schemars(description = "Number of notifications to skip")]
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+pub struct OmniNotificationRequest {
+    #[schemars(description = "Notification ID")]
+    pub notification_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct PurgeOmniNotificationsRequest {
+    #[schemars(description = "Delete finished notifications older than this many days")]
+    pub older_than_days: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct RotateSecretKeyRequest {
+    #[schemars(
+        description = "Base64 32-byte key to rotate to; required when FORGE_SECRET_KEY is set, generated otherwise"
+    )]
+    pub key: Option<String>,
+}
+
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct CreateTaskAndStartRequest {
     #[schemars(description = "Project ID")]
@@ -337,7 +383,9 @@ pub struct CreateTaskAndStartRequest {
     pub description: Option<String>,
     #[schemars(description = "Executor to use (e.g., 'CLAUDE_CODE', 'CURSOR', 'GEMINI')")]
     pub executor: String,
-    #[schemars(description = "Optional executor variant")]
+    #[schemars(
+        description = "Optional executor variant, upstream or one of the project's custom executors"
+    )]
     pub variant: Option<String>,
     #[schemars(description = "Base branch for the task attempt")]
     pub base_branch: String,
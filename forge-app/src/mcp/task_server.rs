@@ -8,6 +8,7 @@ use db::models::{
     task_attempt::TaskAttempt,
 };
 use executors::{executors::BaseCodingAgent, profile::ExecutorProfileId};
+use forge_config::{SettingsSource, history::SOURCE_HEADER};
 use rmcp::{
     ErrorData, ServerHandler,
     handler::server::tool::{Parameters, ToolRouter},
@@ -25,9 +26,11 @@ use super::advanced_tools::{
     DeleteDraftRequest, DeleteFileRequest, FollowUpRequest, GetBranchStatusRequest,
     GetChildrenRequest, GetCommitInfoRequest, GetContainerRequest, GetDraftRequest, GetFileRequest,
     GetImageRequest, GetProcessRequest, GetProjectSettingsRequest, ListDraftsRequest,
-    ListProcessesRequest, MergeTaskAttemptRequest, OpenEditorRequest, PushTaskAttemptRequest,
-    RebaseTaskAttemptRequest, ReplaceProcessRequest, SaveDraftRequest, SetDraftQueueRequest,
-    StartDevServerRequest, StopProcessRequest, UpdateConfigRequest, UpdateDraftRequest,
+    ListOmniNotificationsRequest, ListProcessesRequest, MergeTaskAttemptRequest,
+    OmniNotificationRequest, OpenEditorRequest, PurgeOmniNotificationsRequest,
+    PushTaskAttemptRequest, RebaseTaskAttemptRequest, ReplaceProcessRequest,
+    RotateSecretKeyRequest, SaveDraftRequest, SetDraftQueueRequest, StartDevServerRequest,
+    StopProcessRequest, TestOmniNotificationRequest, UpdateConfigRequest, UpdateDraftRequest,
     UpdateProjectSettingsRequest, UploadImageRequest, UploadImageResponse,
     ValidateOmniConfigRequest,
 };
@@ -249,7 +252,9 @@ pub struct StartTaskAttemptRequest {
         description = "The coding agent executor to run ('CLAUDE_CODE', 'CODEX', 'GEMINI', 'CURSOR', 'OPENCODE')"
     )]
     pub executor: String,
-    #[schemars(description = "Optional executor variant, if needed")]
+    #[schemars(
+        description = "Optional executor variant, upstream or one of the project's custom executors; the project's default variant when unset"
+    )]
     pub variant: Option<String>,
     #[schemars(description = "The base branch to use for the attempt")]
     pub base_branch: String,
@@ -689,7 +694,7 @@ impl ForgeTaskServer {
             if trimmed.is_empty() {
                 None
             } else {
-                Some(trimmed.to_string())
+                Some(trimmed.replace('-', "_").to_ascii_uppercase())
             }
         });
 
@@ -884,7 +889,7 @@ impl ForgeTaskServer {
             if trimmed.is_empty() {
                 None
             } else {
-                Some(trimmed.to_string())
+                Some(trimmed.replace('-', "_").to_ascii_uppercase())
             }
         });
 
@@ -1983,7 +1988,12 @@ impl ForgeTaskServer {
 
         let url = self.url("/api/forge/config");
         if let Err(e) = self
-            .send_json::<serde_json::Value>(self.client.put(&url).json(&config))
+            .send_json::<serde_json::Value>(
+                self.client
+                    .put(&url)
+                    .header(SOURCE_HEADER, SettingsSource::Mcp.as_str())
+                    .json(&config),
+            )
             .await
         {
             return Ok(e);
@@ -2031,7 +2041,12 @@ impl ForgeTaskServer {
 
         let url = self.url(&format!("/api/forge/projects/{}/settings", project_id));
         if let Err(e) = self
-            .send_json::<serde_json::Value>(self.client.put(&url).json(&settings))
+            .send_json::<serde_json::Value>(
+                self.client
+                    .put(&url)
+                    .header(SOURCE_HEADER, SettingsSource::Mcp.as_str())
+                    .json(&settings),
+            )
             .await
         {
             return Ok(e);
@@ -2100,8 +2115,36 @@ impl ForgeTaskServer {
         ForgeTaskServer::success(&result)
     }
 
-    #[tool(description = "[ADVANCED] List all Omni notifications")]
-    async fn adv_list_omni_notifications(&self) -> Result<CallToolResult, ErrorData> {
+    #[tool(
+        description = "[ADVANCED] Send a test Omni notification and return Omni's response for each recipient"
+    )]
+    async fn adv_test_omni_notification(
+        &self,
+        Parameters(request): Parameters<TestOmniNotificationRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if !self.advanced_mode {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "This tool requires --advanced flag".to_string(),
+            )]));
+        }
+
+        let url = self.url("/api/forge/omni/test");
+        let result: serde_json::Value =
+            match self.send_json(self.client.post(&url).json(&request)).await {
+                Ok(r) => r,
+                Err(e) => return Ok(e),
+            };
+
+        ForgeTaskServer::success(&result)
+    }
+
+    #[tool(
+        description = "[ADVANCED] List Omni notifications, newest first, with optional filters and paging"
+    )]
+    async fn adv_list_omni_notifications(
+        &self,
+        Parameters(request): Parameters<ListOmniNotificationsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
         if !self.advanced_mode {
             return Ok(CallToolResult::error(vec![Content::text(
                 "This tool requires --advanced flag".to_string(),
@@ -2109,12 +2152,111 @@ impl ForgeTaskServer {
         }
 
         let url = self.url("/api/forge/omni/notifications");
-        let notifications: serde_json::Value = match self.send_json(self.client.get(&url)).await {
-            Ok(n) => n,
+        let notifications: serde_json::Value =
+            match self.send_json(self.client.get(&url).query(&request)).await {
+                Ok(n) => n,
+                Err(e) => return Ok(e),
+            };
+
+        ForgeTaskServer::success(&notifications)
+    }
+
+    #[tool(
+        description = "[ADVANCED] Queue a failed or skipped Omni notification for delivery again"
+    )]
+    async fn adv_resend_omni_notification(
+        &self,
+        Parameters(OmniNotificationRequest { notification_id }): Parameters<
+            OmniNotificationRequest,
+        >,
+    ) -> Result<CallToolResult, ErrorData> {
+        if !self.advanced_mode {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "This tool requires --advanced flag".to_string(),
+            )]));
+        }
+
+        let url = self.url(&format!(
+            "/api/forge/omni/notifications/{}/resend",
+            urlencoding::encode(&notification_id)
+        ));
+        let result: serde_json::Value = match self.send_json(self.client.post(&url)).await {
+            Ok(r) => r,
             Err(e) => return Ok(e),
         };
 
-        ForgeTaskServer::success(&notifications)
+        ForgeTaskServer::success(&result)
+    }
+
+    #[tool(description = "[ADVANCED] Cancel a pending Omni notification")]
+    async fn adv_cancel_omni_notification(
+        &self,
+        Parameters(OmniNotificationRequest { notification_id }): Parameters<
+            OmniNotificationRequest,
+        >,
+    ) -> Result<CallToolResult, ErrorData> {
+        if !self.advanced_mode {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "This tool requires --advanced flag".to_string(),
+            )]));
+        }
+
+        let url = self.url(&format!(
+            "/api/forge/omni/notifications/{}/cancel",
+            urlencoding::encode(&notification_id)
+        ));
+        let result: serde_json::Value = match self.send_json(self.client.post(&url)).await {
+            Ok(r) => r,
+            Err(e) => return Ok(e),
+        };
+
+        ForgeTaskServer::success(&result)
+    }
+
+    #[tool(
+        description = "[ADVANCED] Delete finished Omni notifications older than a number of days"
+    )]
+    async fn adv_purge_omni_notifications(
+        &self,
+        Parameters(request): Parameters<PurgeOmniNotificationsRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if !self.advanced_mode {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "This tool requires --advanced flag".to_string(),
+            )]));
+        }
+
+        let url = self.url("/api/forge/omni/notifications/purge");
+        let result: serde_json::Value =
+            match self.send_json(self.client.post(&url).json(&request)).await {
+                Ok(r) => r,
+                Err(e) => return Ok(e),
+            };
+
+        ForgeTaskServer::success(&result)
+    }
+
+    #[tool(
+        description = "[ADVANCED] Re-encrypt stored secrets such as the Omni API key with a new key"
+    )]
+    async fn adv_rotate_secret_key(
+        &self,
+        Parameters(request): Parameters<RotateSecretKeyRequest>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if !self.advanced_mode {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "This tool requires --advanced flag".to_string(),
+            )]));
+        }
+
+        let url = self.url("/api/forge/secrets/rotate");
+        let result: serde_json::Value =
+            match self.send_json(self.client.post(&url).json(&request)).await {
+                Ok(r) => r,
+                Err(e) => return Ok(e),
+            };
+
+        ForgeTaskServer::success(&result)
     }
 }
 
@@ -2124,7 +2266,7 @@ impl ServerHandler for ForgeTaskServer {
         use rmcp::model::{Implementation, ProtocolVersion};
 
         let instructions = if self.advanced_mode {
-            "A task and project management server with full backend API access. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_task_attempt', 'get_task', 'update_task', 'delete_task', plus 49 advanced tools (adv_*). Make sure to pass `project_id` or `task_id` where required.".to_string()
+            "A task and project management server with full backend API access. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_task_attempt', 'get_task', 'update_task', 'delete_task', plus 54 advanced tools (adv_*). Make sure to pass `project_id` or `task_id` where required.".to_string()
         } else {
             "A task and project management server. Core task management tools only. TOOLS: 'list_projects', 'list_tasks', 'create_task', 'start_task_attempt', 'get_task', 'update_task', 'delete_task'. Advanced tools (adv_*) require --advanced flag. Make sure to pass `project_id` or `task_id` where required.".to_string()
         };
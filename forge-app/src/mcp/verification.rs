@@ -145,7 +145,7 @@ pub const ALL_BACKEND_ENDPOINTS: &[(&str, &str, &str)] = &[
     // ========== ADVANCED: CONTAINERS (2 tools) ==========
     ("GET", "/api/containers", "adv_list_containers"),
     ("GET", "/api/containers/{id}", "adv_get_container"),
-    // ========== ADVANCED: FORGE-SPECIFIC (8 tools) ==========
+    // ========== ADVANCED: FORGE-SPECIFIC (13 tools) ==========
     ("GET", "/api/forge/config", "adv_get_forge_config"),
     ("PUT", "/api/forge/config", "adv_update_forge_config"),
     (
@@ -169,11 +169,28 @@ pub const ALL_BACKEND_ENDPOINTS: &[(&str, &str, &str)] = &[
         "/api/forge/omni/validate",
         "adv_validate_omni_config",
     ),
+    ("POST", "/api/forge/omni/test", "adv_test_omni_notification"),
     (
         "GET",
         "/api/forge/omni/notifications",
         "adv_list_omni_notifications",
     ),
+    (
+        "POST",
+        "/api/forge/omni/notifications/{id}/resend",
+        "adv_resend_omni_notification",
+    ),
+    (
+        "POST",
+        "/api/forge/omni/notifications/{id}/cancel",
+        "adv_cancel_omni_notification",
+    ),
+    (
+        "POST",
+        "/api/forge/omni/notifications/purge",
+        "adv_purge_omni_notifications",
+    ),
+    ("POST", "/api/forge/secrets/rotate", "adv_rotate_secret_key"),
     // ========== SKIPPED: EVENTS/SSE (not suitable for MCP) ==========
     // GET /api/events/processes/{id}/logs - Server-Sent Events
     // GET /api/events/task-attempts/{id}/diff - Server-Sent Events
@@ -3,10 +3,12 @@
 //! Routes forge-specific APIs under `/api/forge/*` and upstream APIs under `/api/*`.
 //! Serves single frontend (with overlay architecture) at `/`.
 
+use std::str::FromStr;
+
 use axum::{
-    Json, Router,
+    Extension, Json, Router,
     extract::{FromRef, Path, Query, State},
-    http::{HeaderValue, Method, StatusCode, header},
+    http::{HeaderMap, HeaderValue, Method, StatusCode, header},
     response::{Html, IntoResponse, Response},
     routing::{get, post},
 };
@@ -16,14 +18,29 @@ use serde_json::{Value, json};
 use tower_http::cors::{Any, CorsLayer};
 use uuid::Uuid;
 
-use crate::services::ForgeServices;
+use crate::services::{
+    ForgeServices,
+    notification_log::{self, LogUpdate, NotificationLogQuery},
+    omni_health, omni_inbound,
+    project_bundle::{self, ExportOptions, ImportOutcome},
+};
 use db::models::{
     image::TaskImage,
     task::{Task, TaskWithAttemptStatus},
     task_attempt::{CreateTaskAttempt, TaskAttempt},
 };
 use deployment::Deployment;
-use forge_config::ForgeProjectSettings;
+use executors::{
+    executors::{BaseCodingAgent, CodingAgent},
+    profile::{ExecutorConfigs, ExecutorProfileId},
+};
+use forge_config::{
+    CustomExecutors, EffectiveSettings, ExecutorOverrides, ForgeConfigService,
+    ForgeProjectSettings, InvalidSettings, KeySource, ProjectBundle, ProjectImportReport,
+    ProjectImportRequest, SecretCipher, SettingsHistoryEntry, SettingsScope, SettingsSource,
+    history, schema, secrets,
+};
+use forge_omni::{InboundCommand, OmniInboundEvent, OmniService, inbound as omni_inbound_api};
 use server::routes::{
     self as upstream, auth, config as upstream_config, containers, drafts, events,
     execution_processes, filesystem, images, projects, task_attempts, task_templates, tasks,
@@ -69,7 +86,7 @@ pub fn create_router(services: ForgeServices) -> Router {
     let deployment = services.deployment.as_ref().clone();
     let state = ForgeAppState::new(services, deployment.clone());
 
-    let upstream_api = upstream_api_router(&deployment);
+    let upstream_api = upstream_api_router(&deployment, &state.services);
 
     // Configure CORS for Swagger UI and external API access
     let cors = CorsLayer::new()
@@ -107,13 +124,50 @@ fn forge_api_routes() -> Router<ForgeAppState> {
             "/api/forge/projects/{project_id}/settings",
             get(get_project_settings).put(update_project_settings),
         )
+        .route(
+            "/api/forge/projects/{project_id}/settings/effective",
+            get(get_effective_settings),
+        )
+        .route(
+            "/api/forge/tasks/{task_id}/settings",
+            get(get_task_settings).put(update_task_settings),
+        )
+        .route(
+            "/api/forge/projects/{project_id}/executors",
+            get(get_project_executors).put(update_project_executors),
+        )
+        .route(
+            "/api/forge/projects/{project_id}/export",
+            get(export_project),
+        )
+        .route("/api/forge/projects/import", post(import_project))
+        .route("/api/forge/settings/history", get(list_settings_history))
+        .route(
+            "/api/forge/settings/history/{id}/restore",
+            post(restore_settings),
+        )
         .route("/api/forge/omni/status", get(get_omni_status))
         .route("/api/forge/omni/instances", get(list_omni_instances))
         .route("/api/forge/omni/validate", post(validate_omni_config))
+        .route("/api/forge/omni/test", post(test_omni_notification))
+        .route("/api/forge/omni/inbound", post(omni_inbound_webhook))
         .route(
             "/api/forge/omni/notifications",
             get(list_omni_notifications),
         )
+        .route(
+            "/api/forge/omni/notifications/purge",
+            post(purge_omni_notifications),
+        )
+        .route(
+            "/api/forge/omni/notifications/{id}/resend",
+            post(resend_omni_notification),
+        )
+        .route(
+            "/api/forge/omni/notifications/{id}/cancel",
+            post(cancel_omni_notification),
+        )
+        .route("/api/forge/secrets/rotate", post(rotate_secret_key))
     // Branch-templates extension removed - using simple forge/ prefix
 }
 
@@ -147,6 +201,7 @@ async fn forge_create_task(
 /// Forge override: create task attempt with forge/ branch prefix (vk -> forge only)
 async fn forge_create_task_attempt(
     State(deployment): State<DeploymentImpl>,
+    State(services): State<ForgeServices>,
     Json(payload): Json<task_attempts::CreateTaskAttemptBody>,
 ) -> Result<Json<ApiResponse<TaskAttempt>>, ApiError> {
     let executor_profile_id = payload.get_executor_profile_id();
@@ -154,6 +209,29 @@ async fn forge_create_task_attempt(
         .await?
         .ok_or(ApiError::Database(SqlxError::RowNotFound))?;
 
+    let task_attempt = start_forge_attempt(
+        &deployment,
+        &services.config,
+        &task,
+        executor_profile_id,
+        payload.base_branch,
+    )
+    .await?;
+
+    Ok(Json(ApiResponse::success(task_attempt)))
+}
+
+/// Create an attempt on a `forge/` branch and start its executor, with the
+/// profile resolved through the project's custom executors.
+async fn start_forge_attempt(
+    deployment: &DeploymentImpl,
+    config: &ForgeConfigService,
+    task: &Task,
+    executor_profile_id: ExecutorProfileId,
+    base_branch: String,
+) -> Result<TaskAttempt, ApiError> {
+    let executor_profile_id =
+        resolve_executor_profile(config, task.project_id, executor_profile_id).await?;
     let attempt_id = Uuid::new_v4();
 
     // Use same logic as upstream but replace "vk" with "forge" prefix
@@ -165,11 +243,11 @@ async fn forge_create_task_attempt(
         &deployment.db().pool,
         &CreateTaskAttempt {
             executor: executor_profile_id.executor,
-            base_branch: payload.base_branch.clone(),
+            base_branch,
             branch: git_branch_name.clone(),
         },
         attempt_id,
-        payload.task_id,
+        task.id,
     )
     .await?;
 
@@ -189,12 +267,155 @@ async fn forge_create_task_attempt(
         )
         .await;
 
-    Ok(Json(ApiResponse::success(task_attempt)))
+    Ok(task_attempt)
+}
+
+/// Profile an attempt of `project_id` starts with. A project variant, or the
+/// project's default one, is swapped for the upstream variant it builds on;
+/// when the project also overrides the model, arguments or environment, the
+/// overridden profile is added to the in-memory profiles under a variant of
+/// the project (see `ResolvedExecutor::profile_variant`) and that one is
+/// started. The profiles file is never written.
+async fn resolve_executor_profile(
+    config: &ForgeConfigService,
+    project_id: Uuid,
+    requested: ExecutorProfileId,
+) -> Result<ExecutorProfileId, ApiError> {
+    let custom_executors = config
+        .get_custom_executors(project_id)
+        .await
+        .map_err(|e| ApiError::Conflict(format!("{e:#}")))?;
+    let resolved = custom_executors.resolve(
+        &requested.executor.to_string(),
+        requested.variant.as_deref(),
+    );
+    let base = ExecutorProfileId {
+        executor: requested.executor,
+        variant: resolved.base_variant.clone(),
+    };
+    if resolved.overrides.is_empty() {
+        return Ok(base);
+    }
+
+    let variant = resolved.profile_variant(project_id);
+    cache_profile_variant(&base, &variant, &resolved.overrides).map_err(|e| {
+        ApiError::Conflict(format!(
+            "Custom executor {} of project {project_id} cannot be applied: {e:#}",
+            requested.executor
+        ))
+    })?;
+    Ok(ExecutorProfileId {
+        executor: requested.executor,
+        variant: Some(variant),
+    })
+}
+
+/// Put every variant of `project_id` that has overrides back into the cached
+/// profiles. Follow-ups and retries start under the variant of their attempt,
+/// which a restart or a reload of the profiles drops. Variants that cannot be
+/// applied are logged and skipped, so the others are still restored.
+async fn cache_project_variants(
+    config: &ForgeConfigService,
+    project_id: Uuid,
+) -> anyhow::Result<()> {
+    let custom_executors = config.get_custom_executors(project_id).await?;
+    let profiles = ExecutorConfigs::get_cached();
+    for (executor, overlay) in &custom_executors.0 {
+        let Ok(agent) = BaseCodingAgent::from_str(executor) else {
+            continue;
+        };
+        // Executor overrides apply to the upstream variants as well
+        let upstream: Vec<String> = profiles
+            .executors
+            .get(&agent)
+            .map(|config| {
+                config
+                    .configurations
+                    .keys()
+                    .filter(|variant| !variant.starts_with("FORGE_"))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        let variants = std::iter::once(None).chain(
+            overlay
+                .variants
+                .keys()
+                .chain(&upstream)
+                .map(|variant| Some(variant.as_str())),
+        );
+        for variant in variants {
+            let resolved = custom_executors.resolve(executor, variant);
+            if resolved.overrides.is_empty() {
+                continue;
+            }
+            let base = ExecutorProfileId {
+                executor: agent,
+                variant: resolved.base_variant.clone(),
+            };
+            let variant = resolved.profile_variant(project_id);
+            if let Err(e) = cache_profile_variant(&base, &variant, &resolved.overrides) {
+                tracing::warn!("Failed to restore executor variant {}: {:#}", variant, e);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Serializes updates of project variants in the cached profiles.
+static CACHED_VARIANTS: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Add `base` with `overrides` applied as `variant` of its executor to the
+/// cached profiles only. The variant is replaced on every start, so it follows
+/// the project's overlay; a reload of the profiles drops it until
+/// [`cache_project_variants`] puts it back.
+fn cache_profile_variant(
+    base: &ExecutorProfileId,
+    variant: &str,
+    overrides: &ExecutorOverrides,
+) -> anyhow::Result<()> {
+    // Held over the in-memory copy and its replacement only, so concurrent
+    // starts do not drop each other's variants
+    let _guard = CACHED_VARIANTS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut profiles = ExecutorConfigs::get_cached();
+    let agent = profiles.get_coding_agent(base).ok_or_else(|| {
+        anyhow::anyhow!(
+            "{} has no variant {}",
+            base.executor,
+            base.variant.as_deref().unwrap_or("DEFAULT")
+        )
+    })?;
+
+    let mut profile = serde_json::to_value(&agent)?;
+    overrides.apply(&mut profile)?;
+    let agent: CodingAgent = serde_json::from_value(profile.clone())?;
+    // Fields the executor does not know are dropped when it is read back
+    if serde_json::to_value(&agent)? != profile {
+        anyhow::bail!("{} does not support every override", base.executor);
+    }
+
+    let executor_config = profiles
+        .executors
+        .get_mut(&base.executor)
+        .ok_or_else(|| anyhow::anyhow!("{} has no profiles", base.executor))?;
+    if let Some(current) = executor_config.configurations.get(variant)
+        && serde_json::to_value(current)? == profile
+    {
+        return Ok(());
+    }
+    executor_config
+        .configurations
+        .insert(variant.to_string(), agent);
+    ExecutorConfigs::set_cached(profiles);
+    Ok(())
 }
 
 /// Forge override: create task and start with forge/ branch prefix (vk -> forge only)
 async fn forge_create_task_and_start(
     State(deployment): State<DeploymentImpl>,
+    State(services): State<ForgeServices>,
     Json(payload): Json<CreateAndStartTaskRequest>,
 ) -> Result<Json<ApiResponse<TaskWithAttemptStatus>>, ApiError> {
     let task_id = Uuid::new_v4();
@@ -216,6 +437,12 @@ async fn forge_create_task_and_start(
         )
         .await;
 
+    let executor_profile_id = resolve_executor_profile(
+        &services.config,
+        task.project_id,
+        payload.executor_profile_id,
+    )
+    .await?;
     let task_attempt_id = Uuid::new_v4();
 
     // Use same logic as upstream but replace "vk" with "forge" prefix
@@ -226,7 +453,7 @@ async fn forge_create_task_and_start(
     let task_attempt = TaskAttempt::create(
         &deployment.db().pool,
         &CreateTaskAttempt {
-            executor: payload.executor_profile_id.executor,
+            executor: executor_profile_id.executor,
             base_branch: payload.base_branch.clone(),
             branch: branch_name,
         },
@@ -237,7 +464,7 @@ async fn forge_create_task_and_start(
 
     let execution_process = deployment
         .container()
-        .start_attempt(&task_attempt, payload.executor_profile_id.clone())
+        .start_attempt(&task_attempt, executor_profile_id.clone())
         .await?;
 
     deployment
@@ -245,8 +472,8 @@ async fn forge_create_task_and_start(
             "task_attempt_started",
             serde_json::json!({
                 "task_id": task.id.to_string(),
-                "executor": &payload.executor_profile_id.executor,
-                "variant": &payload.executor_profile_id.variant,
+                "executor": &executor_profile_id.executor,
+                "variant": &executor_profile_id.variant,
                 "attempt_id": task_attempt.id.to_string(),
             }),
         )
@@ -269,7 +496,10 @@ async fn forge_create_task_and_start(
     })))
 }
 
-fn upstream_api_router(deployment: &DeploymentImpl) -> Router<ForgeAppState> {
+fn upstream_api_router(
+    deployment: &DeploymentImpl,
+    services: &ForgeServices,
+) -> Router<ForgeAppState> {
     let mut router = Router::new().route("/health", get(upstream::health::health_check));
 
     let dep_clone = deployment.clone();
@@ -289,7 +519,7 @@ fn upstream_api_router(deployment: &DeploymentImpl) -> Router<ForgeAppState> {
 
     // Build custom task_attempts router with forge override
     let task_attempts_router_with_override =
-        build_task_attempts_router_with_forge_override(deployment);
+        build_task_attempts_router_with_forge_override(deployment, services);
     router = router
         .merge(task_attempts_router_with_override.with_state::<ForgeAppState>(dep_clone.clone()));
     router = router.merge(
@@ -425,13 +655,20 @@ ORDER BY t.created_at DESC"#,
 /// Build task_attempts router with forge override for create endpoint
 fn build_task_attempts_router_with_forge_override(
     deployment: &DeploymentImpl,
+    services: &ForgeServices,
 ) -> Router<DeploymentImpl> {
     use axum::middleware::from_fn_with_state;
     use server::middleware::load_task_attempt_middleware;
 
     let task_attempt_id_router = Router::new()
         .route("/", get(task_attempts::get_task_attempt))
-        .route("/follow-up", post(task_attempts::follow_up))
+        .route(
+            "/follow-up",
+            post(task_attempts::follow_up).layer(from_fn_with_state(
+                services.clone(),
+                restore_project_variants,
+            )),
+        ) // Forge: project executor variants
         .route(
             "/draft",
             get(task_attempts::drafts::get_draft)
@@ -439,7 +676,13 @@ fn build_task_attempts_router_with_forge_override(
                 .delete(task_attempts::drafts::delete_draft),
         )
         .route("/draft/queue", post(task_attempts::drafts::set_draft_queue))
-        .route("/replace-process", post(task_attempts::replace_process))
+        .route(
+            "/replace-process",
+            post(task_attempts::replace_process).layer(from_fn_with_state(
+                services.clone(),
+                restore_project_variants,
+            )),
+        ) // Forge: project executor variants
         .route("/commit-info", get(task_attempts::get_commit_info))
         .route(
             "/commit-compare",
@@ -451,9 +694,17 @@ fn build_task_attempts_router_with_forge_override(
             get(task_attempts::get_task_attempt_branch_status),
         )
         .route("/diff/ws", get(task_attempts::stream_task_attempt_diff_ws))
-        .route("/merge", post(task_attempts::merge_task_attempt))
+        .route(
+            "/merge",
+            post(task_attempts::merge_task_attempt)
+                .layer(from_fn_with_state(services.clone(), notify_on_conflicts)),
+        ) // Forge: merge conflict notifications
         .route("/push", post(task_attempts::push_task_attempt_branch))
-        .route("/rebase", post(task_attempts::rebase_task_attempt))
+        .route(
+            "/rebase",
+            post(task_attempts::rebase_task_attempt)
+                .layer(from_fn_with_state(services.clone(), notify_on_conflicts)),
+        ) // Forge: merge conflict notifications
         .route(
             "/conflicts/abort",
             post(task_attempts::abort_conflicts_task_attempt),
@@ -489,6 +740,85 @@ fn build_task_attempts_router_with_forge_override(
     Router::new().nest("/task-attempts", task_attempts_router)
 }
 
+/// Restore the executor variants of the attempt's project before a follow-up or
+/// retry starts one of them again.
+async fn restore_project_variants(
+    State(services): State<ForgeServices>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> Response {
+    match Task::find_by_id(services.pool(), task_attempt.task_id).await {
+        Ok(Some(task)) => {
+            if let Err(e) = cache_project_variants(&services.config, task.project_id).await {
+                tracing::warn!(
+                    "Failed to restore executor variants of project {}: {:#}",
+                    task.project_id,
+                    e
+                );
+            }
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Failed to load task {}: {}", task_attempt.task_id, e),
+    }
+    next.run(request).await
+}
+
+/// Queue a merge conflict notification when a merge or rebase stops on conflicts.
+/// Upstream only reports them in the response, so the body is inspected and
+/// passed on unchanged.
+async fn notify_on_conflicts(
+    State(services): State<ForgeServices>,
+    Extension(task_attempt): Extension<TaskAttempt>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> Response {
+    let op = if request.uri().path().ends_with("/rebase") {
+        "rebase"
+    } else {
+        "merge"
+    };
+    let (parts, body) = next.run(request).await.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("Failed to read {} response: {}", op, e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    if let Some((op, message)) = merge_conflict(&bytes, op)
+        && let Err(e) = services
+            .queue_merge_conflict(task_attempt.id, &op, message.as_deref())
+            .await
+    {
+        tracing::warn!("Failed to queue merge conflict notification: {}", e);
+    }
+
+    Response::from_parts(parts, axum::body::Body::from(bytes))
+}
+
+/// Operation and message of a `merge_conflicts` error in an upstream response.
+fn merge_conflict(body: &[u8], default_op: &str) -> Option<(String, Option<String>)> {
+    let response: Value = serde_json::from_slice(body).ok()?;
+    let error_data = response.get("error_data")?;
+    if error_data.get("type").and_then(Value::as_str) != Some("merge_conflicts") {
+        return None;
+    }
+
+    let op = error_data
+        .get("op")
+        .and_then(Value::as_str)
+        .unwrap_or(default_op)
+        .to_string();
+    let message = error_data
+        .get("message")
+        .or_else(|| response.get("message"))
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    Some((op, message))
+}
+
 async fn frontend_handler(uri: axum::http::Uri) -> Response {
     let path = uri.path().trim_start_matches('/');
 
@@ -662,10 +992,24 @@ async fn list_routes() -> Json<Value> {
                 "PUT /api/forge/config",
                 "GET /api/forge/projects/{id}/settings",
                 "PUT /api/forge/projects/{id}/settings",
+                "GET /api/forge/projects/{id}/settings/effective",
+                "GET /api/forge/tasks/{id}/settings",
+                "PUT /api/forge/tasks/{id}/settings",
+                "GET /api/forge/projects/{id}/executors",
+                "PUT /api/forge/projects/{id}/executors",
+                "GET /api/forge/projects/{id}/export",
+                "POST /api/forge/projects/import",
+                "GET /api/forge/settings/history",
+                "POST /api/forge/settings/history/{id}/restore",
                 "GET /api/forge/omni/status",
                 "GET /api/forge/omni/instances",
                 "POST /api/forge/omni/validate",
-                "GET /api/forge/omni/notifications"
+                "POST /api/forge/omni/test",
+                "POST /api/forge/omni/inbound",
+                "GET /api/forge/omni/notifications",
+                "POST /api/forge/omni/notifications/{id}/resend",
+                "POST /api/forge/omni/notifications/{id}/cancel",
+                "POST /api/forge/omni/notifications/purge"
             ],
             "filesystem": [
                 "GET /api/filesystem/tree",
@@ -698,32 +1042,73 @@ async fn get_forge_config(
         .config
         .get_global_settings()
         .await
-        .map(|settings| Json(ApiResponse::success(settings)))
+        .map(|settings| Json(ApiResponse::success(settings.redacted())))
         .map_err(|e| {
-            tracing::error!("Failed to load forge config: {}", e);
+            tracing::error!("Failed to load forge config: {:#}", e);
             StatusCode::INTERNAL_SERVER_ERROR
         })
 }
 
 async fn update_forge_config(
     State(services): State<ForgeServices>,
-    Json(settings): Json<ForgeProjectSettings>,
-) -> Result<Json<ApiResponse<ForgeProjectSettings>>, StatusCode> {
+    headers: HeaderMap,
+    Json(payload): Json<Value>,
+) -> Result<Json<ApiResponse<ForgeProjectSettings>>, Response> {
+    let settings = settings_from_request(payload)?;
+
     services
         .config
-        .set_global_settings(&settings)
+        .set_global_settings(&settings, settings_source(&headers))
         .await
-        .map_err(|e| {
-            tracing::error!("Failed to persist forge config: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .map_err(|e| settings_write_error(e, "Failed to persist forge config"))?;
 
     services.apply_global_omni_config().await.map_err(|e| {
         tracing::error!("Failed to refresh Omni config: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
+        StatusCode::INTERNAL_SERVER_ERROR.into_response()
     })?;
 
-    Ok(Json(ApiResponse::success(settings)))
+    Ok(Json(ApiResponse::success(settings.redacted())))
+}
+
+/// Read a settings payload, upgrading it from an older schema version. Payloads
+/// that do not parse or validate are rejected with 400 and the message.
+fn settings_from_request(payload: Value) -> Result<ForgeProjectSettings, Response> {
+    schema::parse_settings(payload)
+        .and_then(|settings| settings.validate().map(|()| settings))
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<ForgeProjectSettings>::error(&format!(
+                    "{e:#}"
+                ))),
+            )
+                .into_response()
+        })
+}
+
+/// Settings the config service rejected before storing them are answered with 400
+/// and the reason; any other failure is logged under `context` and is a 500.
+fn settings_write_error(e: anyhow::Error, context: &str) -> Response {
+    if let Some(invalid) = e.downcast_ref::<InvalidSettings>() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<ForgeProjectSettings>::error(
+                &invalid.to_string(),
+            )),
+        )
+            .into_response();
+    }
+    tracing::error!("{context}: {e:#}");
+    StatusCode::INTERNAL_SERVER_ERROR.into_response()
+}
+
+/// Where a settings write came from, as told by the client; the API by default.
+fn settings_source(headers: &HeaderMap) -> SettingsSource {
+    SettingsSource::from_header(
+        headers
+            .get(history::SOURCE_HEADER)
+            .and_then(|value| value.to_str().ok()),
+    )
 }
 
 async fn get_project_settings(
@@ -734,9 +1119,9 @@ async fn get_project_settings(
         .config
         .get_forge_settings(project_id)
         .await
-        .map(|settings| Json(ApiResponse::success(settings)))
+        .map(|settings| Json(ApiResponse::success(settings.redacted())))
         .map_err(|e| {
-            tracing::error!("Failed to load project settings {}: {}", project_id, e);
+            tracing::error!("Failed to load project settings {}: {:#}", project_id, e);
             StatusCode::INTERNAL_SERVER_ERROR
         })
 }
@@ -744,31 +1129,349 @@ async fn get_project_settings(
 async fn update_project_settings(
     Path(project_id): Path<Uuid>,
     State(services): State<ForgeServices>,
-    Json(settings): Json<ForgeProjectSettings>,
+    headers: HeaderMap,
+    Json(payload): Json<Value>,
+) -> Result<Json<ApiResponse<ForgeProjectSettings>>, Response> {
+    let settings = settings_from_request(payload)?;
+
+    services
+        .config
+        .set_forge_settings(project_id, &settings, settings_source(&headers))
+        .await
+        .map_err(|e| {
+            settings_write_error(
+                e,
+                &format!("Failed to persist project settings {project_id}"),
+            )
+        })?;
+
+    Ok(Json(ApiResponse::success(settings.redacted())))
+}
+
+#[derive(Debug, Deserialize)]
+struct EffectiveSettingsQuery {
+    /// Include the settings of this task on top of the project's
+    task_id: Option<Uuid>,
+}
+
+/// Settings merged over the global, project and optional task levels, with the
+/// level each field came from.
+async fn get_effective_settings(
+    Path(project_id): Path<Uuid>,
+    Query(query): Query<EffectiveSettingsQuery>,
+    State(services): State<ForgeServices>,
+) -> Result<Json<ApiResponse<EffectiveSettings>>, StatusCode> {
+    let mut effective = services
+        .config
+        .effective_settings(Some(project_id), query.task_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                "Failed to resolve effective settings for project {}: {}",
+                project_id,
+                e
+            );
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    effective.settings = effective.settings.redacted();
+
+    Ok(Json(ApiResponse::success(effective)))
+}
+
+async fn get_task_settings(
+    Path(task_id): Path<Uuid>,
+    State(services): State<ForgeServices>,
 ) -> Result<Json<ApiResponse<ForgeProjectSettings>>, StatusCode> {
     services
         .config
-        .set_forge_settings(project_id, &settings)
+        .get_task_settings(task_id)
         .await
+        .map(|settings| {
+            Json(ApiResponse::success(
+                settings.unwrap_or_default().redacted(),
+            ))
+        })
         .map_err(|e| {
-            tracing::error!("Failed to persist project settings {}: {}", project_id, e);
+            tracing::error!("Failed to load task settings {}: {:#}", task_id, e);
             StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+async fn update_task_settings(
+    Path(task_id): Path<Uuid>,
+    State(services): State<ForgeServices>,
+    headers: HeaderMap,
+    Json(payload): Json<Value>,
+) -> Result<Json<ApiResponse<ForgeProjectSettings>>, Response> {
+    let settings = settings_from_request(payload)?;
+
+    services
+        .config
+        .set_task_settings(task_id, &settings, settings_source(&headers))
+        .await
+        .map_err(|e| {
+            settings_write_error(e, &format!("Failed to persist task settings {task_id}"))
         })?;
 
-    Ok(Json(ApiResponse::success(settings)))
+    Ok(Json(ApiResponse::success(settings.redacted())))
+}
+
+/// A project's custom executors; empty when none were saved.
+async fn get_project_executors(
+    Path(project_id): Path<Uuid>,
+    State(services): State<ForgeServices>,
+) -> Result<Json<ApiResponse<CustomExecutors>>, StatusCode> {
+    services
+        .config
+        .get_custom_executors(project_id)
+        .await
+        .map(|custom_executors| Json(ApiResponse::success(custom_executors)))
+        .map_err(|e| {
+            tracing::error!(
+                "Failed to load custom executors of project {}: {:#}",
+                project_id,
+                e
+            );
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+/// Replace a project's custom executors. Overlays of unknown executors, with
+/// invalid names or built on variants the upstream profiles lack are rejected
+/// with 400.
+async fn update_project_executors(
+    Path(project_id): Path<Uuid>,
+    State(services): State<ForgeServices>,
+    Json(payload): Json<Value>,
+) -> Result<Json<ApiResponse<CustomExecutors>>, Response> {
+    let custom_executors = serde_json::from_value::<CustomExecutors>(payload)
+        .map_err(anyhow::Error::from)
+        .and_then(|custom_executors| {
+            custom_executors.validate()?;
+            validate_base_variants(&custom_executors)?;
+            Ok(custom_executors)
+        })
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<CustomExecutors>::error(&format!("{e:#}"))),
+            )
+                .into_response()
+        })?;
+
+    services
+        .config
+        .set_custom_executors(project_id, Some(&custom_executors))
+        .await
+        .map_err(|e| {
+            tracing::error!(
+                "Failed to persist custom executors of project {}: {:#}",
+                project_id,
+                e
+            );
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    Ok(Json(ApiResponse::success(custom_executors)))
+}
+
+/// Check the upstream variants `custom_executors` build on against the current
+/// executor profiles.
+fn validate_base_variants(custom_executors: &CustomExecutors) -> anyhow::Result<()> {
+    let profiles = ExecutorConfigs::get_cached();
+    custom_executors.validate_base_variants(|executor, variant| {
+        profiles
+            .get_coding_agent(&ExecutorProfileId {
+                executor,
+                variant: Some(variant.to_string()),
+            })
+            .is_some()
+    })
+}
+
+/// Portable copy of a project's setup; secrets and tasks only when asked for.
+async fn export_project(
+    Path(project_id): Path<Uuid>,
+    Query(options): Query<ExportOptions>,
+    State(services): State<ForgeServices>,
+) -> Result<Json<ApiResponse<ProjectBundle>>, Response> {
+    project_bundle::export_project(services.pool(), &services.config, project_id, &options)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to export project {}: {:#}", project_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?
+        .map(|bundle| Json(ApiResponse::success(bundle)))
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error(&format!(
+                    "Project {project_id} not found"
+                ))),
+            )
+                .into_response()
+        })
+}
+
+/// Create a project from a bundle. A blocking conflict answers 409 with the
+/// report, so the client can show every conflict at once.
+async fn import_project(
+    State(services): State<ForgeServices>,
+    Json(request): Json<ProjectImportRequest>,
+) -> Result<Json<ApiResponse<ProjectImportReport>>, Response> {
+    if let Some(custom_executors) = &request.bundle.custom_executors
+        && let Err(e) = validate_base_variants(custom_executors)
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(&format!(
+                "Custom executors are invalid: {e:#}"
+            ))),
+        )
+            .into_response());
+    }
+
+    let outcome = project_bundle::import_project(services.pool(), &services.config, request)
+        .await
+        .map_err(|e| settings_write_error(e, "Failed to import project"))?;
+
+    match outcome {
+        ImportOutcome::Completed(report) => Ok(Json(ApiResponse::success(report))),
+        ImportOutcome::Blocked(report) => Err((
+            StatusCode::CONFLICT,
+            Json(json!({
+                "success": false,
+                "data": report,
+                "error_data": null,
+                "message": "The bundle conflicts with existing projects",
+            })),
+        )
+            .into_response()),
+        ImportOutcome::Invalid(message) => Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(&message)),
+        )
+            .into_response()),
+    }
+}
+
+const SETTINGS_HISTORY_PAGE_SIZE: i64 = 50;
+const SETTINGS_HISTORY_MAX_PAGE_SIZE: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+struct SettingsHistoryQuery {
+    /// Only entries of this level
+    scope: Option<SettingsScope>,
+    /// Project or task the entries belong to, with a `project` or `task` scope
+    scope_id: Option<Uuid>,
+    limit: Option<i64>,
+}
+
+/// Settings writes, newest first, with secrets redacted.
+async fn list_settings_history(
+    State(services): State<ForgeServices>,
+    Query(query): Query<SettingsHistoryQuery>,
+) -> Result<Json<ApiResponse<Vec<SettingsHistoryEntry>>>, Response> {
+    let scope = match (query.scope, query.scope_id) {
+        (Some(SettingsScope::Global), _) => Some((SettingsScope::Global, None)),
+        (Some(scope), Some(scope_id)) => Some((scope, Some(scope_id))),
+        (Some(_), None) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<()>::error(
+                    "scope_id is required for project and task history",
+                )),
+            )
+                .into_response());
+        }
+        (None, _) => None,
+    };
+    let limit = query
+        .limit
+        .unwrap_or(SETTINGS_HISTORY_PAGE_SIZE)
+        .clamp(1, SETTINGS_HISTORY_MAX_PAGE_SIZE);
+
+    services
+        .config
+        .settings_history(scope, limit)
+        .await
+        .map(|entries| Json(ApiResponse::success(entries)))
+        .map_err(|e| {
+            tracing::error!("Failed to fetch settings history: {:#}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })
+}
+
+/// Store the settings recorded by a history entry again. The restore is itself
+/// recorded as a new entry.
+async fn restore_settings(
+    Path(id): Path<i64>,
+    State(services): State<ForgeServices>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<SettingsHistoryEntry>>, Response> {
+    let entry = services
+        .config
+        .restore_settings(id, settings_source(&headers))
+        .await
+        .map_err(|e| {
+            settings_write_error(
+                e,
+                &format!("Failed to restore settings from history entry {id}"),
+            )
+        })?
+        .ok_or_else(|| {
+            (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()>::error(&format!(
+                    "Settings history entry {id} not found"
+                ))),
+            )
+                .into_response()
+        })?;
+
+    if entry.scope == SettingsScope::Global {
+        services.apply_global_omni_config().await.map_err(|e| {
+            tracing::error!("Failed to refresh Omni config: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+    }
+
+    Ok(Json(ApiResponse::success(entry)))
 }
 
 async fn get_omni_status(State(services): State<ForgeServices>) -> Result<Json<Value>, StatusCode> {
+    let history = omni_health::health_history(services.pool(), omni_health::HEALTH_HISTORY_LIMIT)
+        .await
+        .map_err(|error| {
+            tracing::error!("Failed to fetch Omni health history: {}", error);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
     let omni = services.omni.read().await;
     let config = omni.config();
+    let current = config.instance.as_deref().and_then(|instance| {
+        history
+            .iter()
+            .find(|check| check["instance"] == instance)
+            .cloned()
+    });
 
     Ok(Json(json!({
         "enabled": config.enabled,
         "version": env!("CARGO_PKG_VERSION"),
         "config": if config.enabled {
-            serde_json::to_value(config).ok()
+            Some(secrets::redacted_omni_config(config))
         } else {
             None
+        },
+        "health": {
+            "healthy": current.as_ref().map(|check| check["healthy"].clone()),
+            "last_check": current,
+            "interval_secs": omni_health::HEALTH_CHECK_INTERVAL.as_secs(),
+            "history": history,
+        },
+        "metrics": {
+            "leases_reclaimed": services.notification_metrics.leases_reclaimed(),
         }
     })))
 }
@@ -791,67 +1494,154 @@ async fn list_omni_instances(
 
 async fn list_omni_notifications(
     State(services): State<ForgeServices>,
+    Query(query): Query<NotificationLogQuery>,
 ) -> Result<Json<Value>, StatusCode> {
-    let rows = sqlx::query(
-        r#"SELECT
-                id,
-                task_id,
-                notification_type,
-                status,
-                message,
-                error_message,
-                sent_at,
-                created_at,
-                metadata
-           FROM forge_omni_notifications
-          ORDER BY created_at DESC
-          LIMIT 50"#,
-    )
-    .fetch_all(services.pool())
-    .await
-    .map_err(|error| {
-        tracing::error!("Failed to fetch Omni notifications: {}", error);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    let (notifications, total) = notification_log::list_notifications(services.pool(), &query)
+        .await
+        .map_err(|error| {
+            tracing::error!("Failed to fetch Omni notifications: {}", error);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
 
-    let mut notifications = Vec::with_capacity(rows.len());
+    Ok(Json(json!({
+        "notifications": notifications,
+        "total": total,
+        "limit": query.limit(),
+        "offset": query.offset(),
+    })))
+}
 
-    for row in rows {
-        let metadata = match row.try_get::<Option<String>, _>("metadata") {
-            Ok(Some(raw)) => serde_json::from_str::<Value>(&raw).ok(),
-            _ => None,
-        };
+async fn resend_omni_notification(
+    Path(id): Path<String>,
+    State(services): State<ForgeServices>,
+) -> Result<Json<Value>, Response> {
+    let outcome = notification_log::resend_notification(services.pool(), &id)
+        .await
+        .map_err(|error| {
+            tracing::error!("Failed to resend Omni notification {}: {}", id, error);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+    log_update_response(outcome, "resend", &id)?;
 
-        let record = json!({
-            "id": row.try_get::<String, _>("id").unwrap_or_default(),
-            "task_id": row.try_get::<Option<String>, _>("task_id").unwrap_or(None),
-            "notification_type": row
-                .try_get::<String, _>("notification_type")
-                .unwrap_or_else(|_| "unknown".to_string()),
-            "status": row
-                .try_get::<String, _>("status")
-                .unwrap_or_else(|_| "pending".to_string()),
-            "message": row.try_get::<Option<String>, _>("message").unwrap_or(None),
-            "error_message": row
-                .try_get::<Option<String>, _>("error_message")
-                .unwrap_or(None),
-            "sent_at": row.try_get::<Option<String>, _>("sent_at").unwrap_or(None),
-            "created_at": row
-                .try_get::<String, _>("created_at")
-                .unwrap_or_else(|_| chrono::Utc::now().to_rfc3339()),
-            "metadata": metadata,
-        });
+    services.omni_wakeup.notify_one();
+    Ok(Json(json!({ "id": id, "status": "pending" })))
+}
 
-        notifications.push(record);
+async fn cancel_omni_notification(
+    Path(id): Path<String>,
+    State(services): State<ForgeServices>,
+) -> Result<Json<Value>, Response> {
+    let outcome = notification_log::cancel_notification(services.pool(), &id)
+        .await
+        .map_err(|error| {
+            tracing::error!("Failed to cancel Omni notification {}: {}", id, error);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+    log_update_response(outcome, "cancel", &id)?;
+
+    Ok(Json(json!({ "id": id, "status": "cancelled" })))
+}
+
+/// 404 for unknown rows, 409 when the row's status does not allow `operation`.
+fn log_update_response(outcome: LogUpdate, operation: &str, id: &str) -> Result<(), Response> {
+    match outcome {
+        LogUpdate::Updated => Ok(()),
+        LogUpdate::NotFound => Err((
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<()>::error(&format!(
+                "Notification {id} not found"
+            ))),
+        )
+            .into_response()),
+        LogUpdate::Conflict(status) => Err((
+            StatusCode::CONFLICT,
+            Json(ApiResponse::<()>::error(&format!(
+                "Cannot {operation} a notification with status '{status}'"
+            ))),
+        )
+            .into_response()),
     }
+}
 
-    Ok(Json(json!({ "notifications": notifications })))
+#[derive(Debug, Deserialize)]
+struct PurgeOmniNotificationsRequest {
+    older_than_days: u32,
+}
+
+async fn purge_omni_notifications(
+    State(services): State<ForgeServices>,
+    Json(request): Json<PurgeOmniNotificationsRequest>,
+) -> Result<Json<Value>, Response> {
+    if request.older_than_days == 0 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(
+                "older_than_days must be at least 1",
+            )),
+        )
+            .into_response());
+    }
+
+    let purged = notification_log::purge_notifications(services.pool(), request.older_than_days)
+        .await
+        .map_err(|error| {
+            tracing::error!("Failed to purge Omni notifications: {}", error);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+
+    Ok(Json(json!({ "purged": purged })))
+}
+
+#[derive(Debug, Deserialize)]
+struct RotateSecretKeyRequest {
+    /// Base64 key to rotate to; generated when omitted (key file only)
+    #[serde(default)]
+    key: Option<String>,
+}
+
+/// Re-encrypt stored secrets with a new key.
+async fn rotate_secret_key(
+    State(services): State<ForgeServices>,
+    Json(request): Json<RotateSecretKeyRequest>,
+) -> Result<Json<Value>, Response> {
+    let bad_request = |message: &str| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<()>::error(message)),
+        )
+            .into_response()
+    };
+
+    let new_cipher = request
+        .key
+        .as_deref()
+        .map(SecretCipher::from_base64)
+        .transpose()
+        .map_err(|e| bad_request(&e.to_string()))?;
+    if new_cipher.is_none() && services.secret_key_source == KeySource::Env {
+        return Err(bad_request(
+            "The secret key is set by FORGE_SECRET_KEY; pass the new key in `key`",
+        ));
+    }
+
+    let rotated = services.rotate_secret_key(new_cipher).await.map_err(|e| {
+        tracing::error!("Failed to rotate the forge secret key: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+    })?;
+
+    Ok(Json(json!({
+        "rotated": rotated,
+        "key_source": services.secret_key_source,
+    })))
 }
 
 #[derive(Debug, Deserialize)]
 struct ValidateOmniRequest {
     host: String,
     api_key: String,
+    /// HTTP client settings to validate with; defaults to the saved ones
+    #[serde(default)]
+    http: Option<forge_omni::OmniHttpConfig>,
 }
 
 #[derive(Debug, Serialize)]
@@ -862,19 +1652,39 @@ struct ValidateOmniResponse {
 }
 
 async fn validate_omni_config(
-    State(_services): State<ForgeServices>,
+    State(services): State<ForgeServices>,
     Json(req): Json<ValidateOmniRequest>,
-) -> Result<Json<ValidateOmniResponse>, StatusCode> {
+) -> Result<Json<ValidateOmniResponse>, Response> {
+    // Settings loaded from the API carry a redacted key; validate the saved one
+    let stored = services.omni.read().await.config().clone();
+    let api_key = secrets::restore_api_key(Some(req.api_key), Some(&req.host), &stored)
+        .map_err(redacted_key_rejected)?;
+
     // Create temporary OmniService with provided credentials
     let temp_config = forge_omni::OmniConfig {
         enabled: false,
         host: Some(req.host),
-        api_key: Some(req.api_key),
+        api_key,
         instance: None,
         recipient: None,
         recipient_type: None,
+        recipients: vec![],
+        digest: None,
+        quiet_hours: None,
+        rate_limit: None,
+        inbound: None,
+        http: req.http.or_else(|| stored.http.clone()),
+        message: None,
     };
 
+    if let Some(Err(e)) = temp_config.http.as_ref().map(|http| http.validate()) {
+        return Ok(Json(ValidateOmniResponse {
+            valid: false,
+            instances: vec![],
+            error: Some(format!("Invalid HTTP settings: {}", e)),
+        }));
+    }
+
     let temp_service = forge_omni::OmniService::new(temp_config);
     match temp_service.list_instances().await {
         Ok(instances) => Ok(Json(ValidateOmniResponse {
@@ -890,6 +1700,209 @@ async fn validate_omni_config(
     }
 }
 
+/// Chat commands sent to Forge through Omni. Requests must carry the shared secret
+/// and come from an allowed sender; the reply is sent back through Omni and also
+/// returned in the response body.
+async fn omni_inbound_webhook(
+    State(state): State<ForgeAppState>,
+    headers: HeaderMap,
+    Json(event): Json<OmniInboundEvent>,
+) -> Result<Json<Value>, StatusCode> {
+    let config = state.services.omni.read().await.config().clone();
+    let Some(inbound) = config.inbound.clone().filter(|inbound| inbound.enabled) else {
+        return Err(StatusCode::NOT_FOUND);
+    };
+
+    let provided = headers
+        .get(omni_inbound_api::SECRET_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    if !inbound.verify_secret(provided) {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let Some(mut reply_to) = inbound.allowed_sender(&config, &event.sender) else {
+        tracing::warn!("Ignoring Omni command from unknown sender {}", event.sender);
+        return Err(StatusCode::FORBIDDEN);
+    };
+    if event.instance.is_some() {
+        reply_to.instance = event.instance.clone();
+    }
+
+    let reply = match InboundCommand::parse(&event.text) {
+        Ok(command) => {
+            tracing::info!("Omni command from {}: {:?}", event.sender, command);
+            run_inbound_command(&state.deployment, &state.services.config, command)
+                .await
+                .unwrap_or_else(|e| format!("⚠️ {e}"))
+        }
+        Err(e) => e.to_string(),
+    };
+
+    let replier = OmniService::new(config.for_recipient(&reply_to));
+    if let Err(e) = replier.send_text_notification(&reply).await {
+        tracing::warn!(
+            "Failed to reply to Omni command from {}: {}",
+            event.sender,
+            e
+        );
+    }
+
+    Ok(Json(json!({ "reply": reply })))
+}
+
+async fn run_inbound_command(
+    deployment: &DeploymentImpl,
+    config: &ForgeConfigService,
+    command: InboundCommand,
+) -> anyhow::Result<String> {
+    let pool = &deployment.db().pool;
+
+    match command {
+        InboundCommand::Help => Ok(omni_inbound_api::HELP_TEXT.to_string()),
+        InboundCommand::Status => omni_inbound::status_report(pool).await,
+        InboundCommand::Retry { task } => {
+            let task_id = omni_inbound::resolve_task(pool, &task).await?;
+            let target = omni_inbound::retry_target(pool, task_id).await?;
+            let task = Task::find_by_id(pool, task_id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Task {task} no longer exists"))?;
+            let executor = BaseCodingAgent::from_str(&target.executor)
+                .map_err(|_| anyhow::anyhow!("Unknown executor '{}'", target.executor))?;
+            let executor_profile_id = ExecutorProfileId {
+                executor,
+                variant: target.variant,
+            };
+
+            let attempt = start_forge_attempt(
+                deployment,
+                config,
+                &task,
+                executor_profile_id,
+                target.base_branch,
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to start attempt: {e}"))?;
+            Ok(format!(
+                "🔁 Retrying {} as attempt {} on {}",
+                target.task_title,
+                omni_inbound::short_id(&attempt.id),
+                attempt.branch
+            ))
+        }
+        InboundCommand::Stop { attempt } => {
+            let attempt_id = omni_inbound::resolve_attempt(pool, &attempt).await?;
+            let task_attempt = TaskAttempt::find_by_id(pool, attempt_id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Attempt {attempt} no longer exists"))?;
+            deployment.container().try_stop(&task_attempt).await;
+            // try_stop only logs the processes it fails to stop
+            let running = omni_inbound::running_processes(pool, attempt_id).await?;
+            if running > 0 {
+                anyhow::bail!(
+                    "Failed to stop attempt {}: {running} process(es) still running",
+                    omni_inbound::short_id(&attempt_id)
+                );
+            }
+            Ok(format!(
+                "⏹ Stopped attempt {}",
+                omni_inbound::short_id(&attempt_id)
+            ))
+        }
+        InboundCommand::FollowUp { attempt, prompt } => {
+            let attempt_id = omni_inbound::resolve_attempt(pool, &attempt).await?;
+            let task_attempt = TaskAttempt::find_by_id(pool, attempt_id)
+                .await?
+                .ok_or_else(|| anyhow::anyhow!("Attempt {attempt} no longer exists"))?;
+            // Same payload the advanced MCP server posts to /follow-up
+            let payload = serde_json::from_value(json!({ "prompt": prompt }))?;
+            task_attempts::follow_up(
+                Extension(task_attempt),
+                State(deployment.clone()),
+                Json(payload),
+            )
+            .await
+            .map_err(|e| anyhow::anyhow!("Follow-up failed: {e}"))?;
+            Ok(format!(
+                "💬 Follow-up sent to attempt {}",
+                omni_inbound::short_id(&attempt_id)
+            ))
+        }
+    }
+}
+
+/// 400 for a redacted API key sent with a host other than the saved one.
+fn redacted_key_rejected(e: anyhow::Error) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(ApiResponse::<()>::error(&format!("{e:#}"))),
+    )
+        .into_response()
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct TestOmniRequest {
+    /// Unsaved settings to test; defaults to the effective config of `project_id`
+    #[serde(default)]
+    config: Option<forge_omni::OmniConfig>,
+    #[serde(default)]
+    project_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize)]
+struct TestOmniResponse {
+    success: bool,
+    deliveries: Vec<forge_omni::OmniDelivery>,
+    error: Option<String>,
+}
+
+async fn test_omni_notification(
+    State(services): State<ForgeServices>,
+    Json(req): Json<TestOmniRequest>,
+) -> Result<Json<TestOmniResponse>, Response> {
+    let stored = services
+        .effective_omni_config(req.project_id)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to load Omni config: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+    let config = match req.config {
+        Some(mut config) => {
+            config.api_key =
+                secrets::restore_api_key(config.api_key, config.host.as_deref(), &stored)
+                    .map_err(redacted_key_rejected)?;
+            config
+        }
+        None => stored,
+    };
+
+    if config
+        .host
+        .as_deref()
+        .is_none_or(|host| host.trim().is_empty())
+    {
+        return Ok(Json(TestOmniResponse {
+            success: false,
+            deliveries: vec![],
+            error: Some("Omni host not configured".into()),
+        }));
+    }
+
+    match OmniService::new(config).send_test_message().await {
+        Ok(deliveries) => Ok(Json(TestOmniResponse {
+            success: deliveries.iter().all(|delivery| delivery.error.is_none()),
+            deliveries,
+            error: None,
+        })),
+        Err(e) => Ok(Json(TestOmniResponse {
+            success: false,
+            deliveries: vec![],
+            error: Some(format!("Test notification failed: {}", e)),
+        })),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -928,6 +1941,34 @@ mod tests {
         assert!(branch_2.starts_with("forge/"));
     }
 
+    #[test]
+    fn detects_merge_conflicts_in_upstream_responses() {
+        let conflict = json!({
+            "success": false,
+            "data": null,
+            "error_data": {
+                "type": "merge_conflicts",
+                "message": "Rebase stopped on conflicts in src/main.rs",
+                "op": "rebase"
+            },
+            "message": null
+        });
+        assert_eq!(
+            merge_conflict(conflict.to_string().as_bytes(), "merge"),
+            Some((
+                "rebase".to_string(),
+                Some("Rebase stopped on conflicts in src/main.rs".to_string())
+            ))
+        );
+
+        let success = json!({ "success": true, "data": null, "message": null });
+        assert_eq!(
+            merge_conflict(success.to_string().as_bytes(), "merge"),
+            None
+        );
+        assert_eq!(merge_conflict(b"not json", "merge"), None);
+    }
+
     #[test]
     fn test_forge_branch_format_matches_upstream() {
         // Verify format is identical to upstream except for "forge" vs "vk" prefix
@@ -0,0 +1,120 @@
+//! Diff Stats
+//!
+//! Summarises how far an attempt branch has moved from its base branch, for use in
+//! notification messages, and exports the full patch for attachments. Failures are
+//! treated as "no stats" since notifications must not depend on the repository
+//! still being present.
+
+use std::path::Path;
+
+use forge_notifications::DiffStats;
+use tokio::process::Command;
+
+/// `git diff --shortstat base...branch` in the project repository.
+pub async fn branch_diff_stats(repo_path: &Path, base: &str, branch: &str) -> Option<DiffStats> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args(["diff", "--shortstat", &format!("{base}...{branch}")])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .ok()?;
+
+    if !output.status.success() {
+        tracing::debug!(
+            "git diff --shortstat failed for {}: {}",
+            repo_path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        return None;
+    }
+
+    Some(parse_shortstat(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// `git diff base...branch`, or `None` when it is empty or larger than `max_bytes`.
+pub async fn branch_patch(
+    repo_path: &Path,
+    base: &str,
+    branch: &str,
+    max_bytes: usize,
+) -> Option<Vec<u8>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args(["diff", "--no-color", &format!("{base}...{branch}")])
+        .kill_on_drop(true)
+        .output()
+        .await
+        .ok()?;
+
+    if !output.status.success() {
+        tracing::debug!(
+            "git diff failed for {}: {}",
+            repo_path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+        return None;
+    }
+    if output.stdout.len() > max_bytes {
+        tracing::warn!(
+            "Patch of {} is {} bytes, over the {} byte attachment limit",
+            branch,
+            output.stdout.len(),
+            max_bytes
+        );
+        return None;
+    }
+
+    (!output.stdout.is_empty()).then_some(output.stdout)
+}
+
+/// Parse ` 3 files changed, 42 insertions(+), 7 deletions(-)`. Empty output means
+/// the branches do not differ.
+fn parse_shortstat(line: &str) -> DiffStats {
+    let mut stats = DiffStats::default();
+    for part in line.trim().split(',') {
+        let mut words = part.split_whitespace();
+        let (Some(count), Some(kind)) = (words.next(), words.next()) else {
+            continue;
+        };
+        let Ok(count) = count.parse::<u64>() else {
+            continue;
+        };
+        if kind.starts_with("file") {
+            stats.files_changed = count;
+        } else if kind.starts_with("insertion") {
+            stats.insertions = count;
+        } else if kind.starts_with("deletion") {
+            stats.deletions = count;
+        }
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_shortstat_output() {
+        assert_eq!(
+            parse_shortstat(" 3 files changed, 42 insertions(+), 7 deletions(-)\n"),
+            DiffStats {
+                files_changed: 3,
+                insertions: 42,
+                deletions: 7,
+            }
+        );
+        assert_eq!(
+            parse_shortstat(" 1 file changed, 1 deletion(-)"),
+            DiffStats {
+                files_changed: 1,
+                insertions: 0,
+                deletions: 1,
+            }
+        );
+        assert_eq!(parse_shortstat(""), DiffStats::default());
+    }
+}
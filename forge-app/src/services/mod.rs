@@ -3,24 +3,37 @@
 //! Service composition layer that wraps upstream services with forge extensions.
 //! Provides unified access to both upstream functionality and forge-specific features.
 
+mod diff_stats;
 mod notification_hook;
+pub mod notification_log;
+pub mod omni_health;
+pub mod omni_inbound;
+pub mod project_bundle;
 
 use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, NaiveDateTime, Utc};
 use deployment::Deployment;
 use serde::Deserialize;
-use serde_json::json;
+use serde_json::{Value, json};
 use server::DeploymentImpl;
 use sqlx::{ConnectOptions, Row, SqlitePool, sqlite::SqliteConnectOptions};
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::{Notify, RwLock};
 use tokio::time::{Duration, sleep};
 use uuid::Uuid;
 
 // Import forge extension services
-use forge_config::ForgeConfigService;
-use forge_omni::{OmniConfig, OmniService};
+use forge_config::secrets::KEY_FILE_NAME;
+use forge_config::{ConfigOrigin, ForgeConfigService, KeySource, SecretCipher};
+use forge_notifications::{
+    NotificationAttachment, NotificationChannelConfig, NotificationEvent, NotificationMessage,
+    NotificationType, TemplateContext, build_channel, channel_labels, rejecting_rule,
+    render_template,
+};
+use forge_omni::{OmniAttachmentMode, OmniConfig, OmniService, QuietHoursAction};
 
 /// Main forge services container
 #[derive(Clone)]
@@ -30,6 +43,27 @@ pub struct ForgeServices {
     pub omni: Arc<RwLock<OmniService>>,
     pub config: Arc<ForgeConfigService>,
     pub pool: SqlitePool,
+    /// Wakes the Omni notification worker when new rows are queued
+    pub omni_wakeup: Arc<Notify>,
+    pub notification_metrics: Arc<NotificationMetrics>,
+    /// Where the key encrypting stored secrets came from
+    pub secret_key_source: KeySource,
+}
+
+/// Counters exposed by the Omni notification worker
+#[derive(Debug, Default)]
+pub struct NotificationMetrics {
+    leases_reclaimed: AtomicU64,
+}
+
+impl NotificationMetrics {
+    pub fn record_leases_reclaimed(&self, count: u64) {
+        self.leases_reclaimed.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn leases_reclaimed(&self) -> u64 {
+        self.leases_reclaimed.load(Ordering::Relaxed)
+    }
 }
 
 impl ForgeServices {
@@ -69,27 +103,74 @@ impl ForgeServices {
         apply_forge_migrations(&pool).await?;
 
         // Initialize forge extension services
-        let config = Arc::new(ForgeConfigService::new(pool.clone()));
-        let global_settings = config.get_global_settings().await?;
-        let omni_config = config.effective_omni_config(None).await?;
+        let (cipher, secret_key_source) = SecretCipher::load(&secret_key_path())?;
+        let config = Arc::new(ForgeConfigService::with_secret_cipher(pool.clone(), cipher));
+        let encrypted = config.encrypt_stored_secrets().await?;
+        if encrypted > 0 {
+            tracing::info!(encrypted, "Encrypted forge settings stored as plain text");
+        }
+        let settings_check = config.upgrade_stored_settings().await?;
+        if settings_check.upgraded > 0 {
+            tracing::info!(
+                upgraded = settings_check.upgraded,
+                "Upgraded forge settings stored with an older schema version"
+            );
+        }
+        for invalid in &settings_check.invalid {
+            tracing::error!("{}", invalid);
+        }
+        // Broken global settings were reported above; run with defaults until fixed
+        let global_settings = config.get_global_settings().await.unwrap_or_default();
+        let omni_config = config.effective_omni_config(None).await.unwrap_or_default();
         let omni = Arc::new(RwLock::new(OmniService::new(omni_config)));
 
         tracing::info!(
-            forge_omni_enabled = global_settings.omni_enabled,
+            forge_omni_enabled = global_settings.omni_enabled.unwrap_or_default(),
             "Loaded forge extension settings from auxiliary schema"
         );
 
         // Install SQLite trigger for Omni notifications when tasks complete
         notification_hook::install_notification_trigger(&pool).await?;
 
+        // Return notifications orphaned by a crash or restart to the queue
+        let notification_metrics = Arc::new(NotificationMetrics::default());
+        let reclaimed = reclaim_expired_leases(&pool, OMNI_LEASE_TIMEOUT).await?;
+        notification_metrics.record_leases_reclaimed(reclaimed);
+        if reclaimed > 0 {
+            tracing::warn!(
+                reclaimed,
+                "Returned Omni notifications with expired leases to the queue"
+            );
+        }
+
+        // Wake the worker as soon as an execution process exits instead of polling
+        let omni_wakeup = Arc::new(Notify::new());
+        notification_hook::spawn_completion_listener(deployment.as_ref(), omni_wakeup.clone());
+
         // Spawn background worker that processes queued Omni notifications
-        spawn_omni_notification_worker(pool.clone(), config.clone());
+        spawn_omni_notification_worker(
+            pool.clone(),
+            config.clone(),
+            omni_wakeup.clone(),
+            notification_metrics.clone(),
+        );
+
+        // Check the Omni instance on a schedule; notifications wait while it is down
+        omni_health::spawn_health_monitor(
+            pool.clone(),
+            omni.clone(),
+            deployment.events().msg_store().clone(),
+            omni_wakeup.clone(),
+        );
 
         Ok(Self {
             deployment,
             omni,
             config,
             pool,
+            omni_wakeup,
+            notification_metrics,
+            secret_key_source,
         })
     }
 
@@ -106,10 +187,66 @@ impl ForgeServices {
         Ok(())
     }
 
-    #[allow(dead_code)]
     pub async fn effective_omni_config(&self, project_id: Option<Uuid>) -> Result<OmniConfig> {
         self.config.effective_omni_config(project_id).await
     }
+
+    /// Queue a notification for a merge or rebase of `attempt_id` that stopped on
+    /// conflicts and wake the worker.
+    pub async fn queue_merge_conflict(
+        &self,
+        attempt_id: Uuid,
+        op: &str,
+        message: Option<&str>,
+    ) -> Result<()> {
+        notification_hook::queue_conflict_notification(&self.pool, attempt_id, op, message).await?;
+        self.omni_wakeup.notify_one();
+        Ok(())
+    }
+
+    /// Re-encrypt stored secrets with a new key and return the number of rows
+    /// rewritten. A key must be given when it comes from `FORGE_SECRET_KEY`, which
+    /// then has to be updated before the next start; with a key file a new key is
+    /// generated when none is given and the file is replaced.
+    pub async fn rotate_secret_key(&self, new_cipher: Option<SecretCipher>) -> Result<u64> {
+        let new_cipher = match new_cipher {
+            Some(cipher) => cipher,
+            None if self.secret_key_source == KeySource::Env => {
+                return Err(anyhow!(
+                    "The secret key is set by FORGE_SECRET_KEY; pass the new key to rotate to"
+                ));
+            }
+            None => SecretCipher::generate(),
+        };
+
+        if self.secret_key_source == KeySource::Env {
+            return self.config.rotate_secret_key(new_cipher).await;
+        }
+
+        // Keep the previous key next to the new one until the rows are rewritten, so
+        // an interrupted rotation can be recovered by hand
+        let key_file = secret_key_path();
+        let backup = key_file.with_extension("key.old");
+        std::fs::copy(&key_file, &backup)
+            .with_context(|| format!("Failed to back up {}", key_file.display()))?;
+        new_cipher.write_key_file(&key_file)?;
+
+        match self.config.rotate_secret_key(new_cipher).await {
+            Ok(rows) => {
+                std::fs::remove_file(&backup).ok();
+                Ok(rows)
+            }
+            Err(e) => {
+                std::fs::rename(&backup, &key_file)
+                    .with_context(|| format!("Failed to restore {}", key_file.display()))?;
+                Err(e)
+            }
+        }
+    }
+}
+
+fn secret_key_path() -> PathBuf {
+    utils::assets::asset_dir().join(KEY_FILE_NAME)
 }
 
 /// Ensure forge-specific migrations do not pollute upstream tracking table.
@@ -254,6 +391,53 @@ const FORGE_MIGRATIONS: &[ForgeMigration] = &[
         description: "add_agent_task_status",
         sql: include_str!("../../migrations/20251020000001_add_agent_task_status.sql"),
     },
+    ForgeMigration {
+        version: "20261018000001",
+        description: "forge_omni_notification_retries",
+        sql: include_str!("../../migrations/20261018000001_forge_omni_notification_retries.sql"),
+    },
+    ForgeMigration {
+        version: "20261018000002",
+        description: "forge_omni_notification_leases",
+        sql: include_str!("../../migrations/20261018000002_forge_omni_notification_leases.sql"),
+    },
+    ForgeMigration {
+        version: "20261018000003",
+        description: "forge_notification_deliveries",
+        sql: include_str!("../../migrations/20261018000003_forge_notification_deliveries.sql"),
+    },
+    ForgeMigration {
+        version: "20261018000004",
+        description: "forge_omni_notification_digests",
+        sql: include_str!("../../migrations/20261018000004_forge_omni_notification_digests.sql"),
+    },
+    ForgeMigration {
+        version: "20261018000005",
+        description: "forge_omni_notification_log_index",
+        sql: include_str!("../../migrations/20261018000005_forge_omni_notification_log_index.sql"),
+    },
+    ForgeMigration {
+        version: "20261018000006",
+        description: "forge_omni_health_checks",
+        sql: include_str!("../../migrations/20261018000006_forge_omni_health_checks.sql"),
+    },
+    ForgeMigration {
+        version: "20261018000007",
+        description: "forge_omni_notification_execution_id",
+        sql: include_str!(
+            "../../migrations/20261018000007_forge_omni_notification_execution_id.sql"
+        ),
+    },
+    ForgeMigration {
+        version: "20261018000008",
+        description: "forge_task_settings",
+        sql: include_str!("../../migrations/20261018000008_forge_task_settings.sql"),
+    },
+    ForgeMigration {
+        version: "20261018000009",
+        description: "forge_settings_history",
+        sql: include_str!("../../migrations/20261018000009_forge_settings_history.sql"),
+    },
 ];
 
 async fn apply_forge_migrations(pool: &SqlitePool) -> Result<()> {
@@ -362,7 +546,22 @@ fn should_ignore_migration_error(_version: &str, _statement: &str, _err: &sqlx::
     false
 }
 
-fn spawn_omni_notification_worker(pool: SqlitePool, config: Arc<ForgeConfigService>) {
+/// Safety-net poll interval for the notification worker. The worker is normally
+/// woken through `ForgeServices::omni_wakeup` as soon as an execution finishes.
+const OMNI_WORKER_SAFETY_POLL: Duration = Duration::from_secs(60);
+
+/// How long a worker may hold a row in `processing` before it is considered abandoned.
+const OMNI_LEASE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Larger patches are not attached; the message still carries the diff summary.
+const MAX_PATCH_ATTACHMENT_BYTES: usize = 1024 * 1024;
+
+fn spawn_omni_notification_worker(
+    pool: SqlitePool,
+    config: Arc<ForgeConfigService>,
+    wakeup: Arc<Notify>,
+    metrics: Arc<NotificationMetrics>,
+) {
     tokio::spawn(async move {
         loop {
             match process_next_omni_notification(&pool, &config).await {
@@ -371,8 +570,38 @@ fn spawn_omni_notification_worker(pool: SqlitePool, config: Arc<ForgeConfigServi
                     continue;
                 }
                 Ok(false) => {
-                    // Queue empty → short backoff
-                    sleep(Duration::from_secs(10)).await;
+                    match flush_due_digests(&pool, &config).await {
+                        Ok(true) => continue,
+                        Ok(false) => {}
+                        Err(err) => {
+                            tracing::error!("Failed to flush Omni notification digests: {err:?}");
+                        }
+                    }
+
+                    match reclaim_expired_leases(&pool, OMNI_LEASE_TIMEOUT).await {
+                        Ok(0) => {}
+                        Ok(reclaimed) => {
+                            metrics.record_leases_reclaimed(reclaimed);
+                            tracing::warn!(reclaimed, "Reclaimed expired Omni notification leases");
+                            continue;
+                        }
+                        Err(err) => {
+                            tracing::error!("Failed to reclaim Omni notification leases: {err:?}");
+                        }
+                    }
+
+                    // Queue empty → wait for a wakeup, the next scheduled retry or the safety poll
+                    let idle = next_retry_delay(&pool)
+                        .await
+                        .unwrap_or(None)
+                        .map_or(OMNI_WORKER_SAFETY_POLL, |delay| {
+                            delay.min(OMNI_WORKER_SAFETY_POLL)
+                        });
+
+                    tokio::select! {
+                        _ = wakeup.notified() => {}
+                        _ = sleep(idle) => {}
+                    }
                 }
                 Err(err) => {
                     tracing::error!("Omni notification worker error: {err:?}");
@@ -383,15 +612,52 @@ fn spawn_omni_notification_worker(pool: SqlitePool, config: Arc<ForgeConfigServi
     });
 }
 
+/// Return rows whose `processing` lease is older than `lease` (or was never stamped)
+/// to `pending`. Returns the number of reclaimed rows.
+async fn reclaim_expired_leases(pool: &SqlitePool, lease: Duration) -> Result<u64> {
+    let result = sqlx::query(
+        r#"UPDATE forge_omni_notifications
+              SET status = 'pending',
+                  claimed_at = NULL
+            WHERE status = 'processing'
+              AND (claimed_at IS NULL OR claimed_at <= datetime('now', '-' || ? || ' seconds'))"#,
+    )
+    .bind(lease.as_secs() as i64)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Time until the earliest rescheduled notification or digest window becomes due, if any.
+async fn next_retry_delay(pool: &SqlitePool) -> Result<Option<Duration>> {
+    let seconds: Option<i64> = sqlx::query_scalar(
+        r#"SELECT CAST((julianday(MIN(next_attempt_at)) - julianday('now')) * 86400 AS INTEGER) + 1
+             FROM forge_omni_notifications
+            WHERE status IN ('pending', 'batched')
+              AND next_attempt_at IS NOT NULL"#,
+    )
+    .fetch_one(pool)
+    .await?;
+
+    Ok(seconds.map(|secs| Duration::from_secs(secs.max(0) as u64)))
+}
+
 async fn process_next_omni_notification(
     pool: &SqlitePool,
     config: &ForgeConfigService,
 ) -> Result<bool> {
     let pending_row = sqlx::query(
         r#"SELECT id,
-                  metadata
+                  notification_type,
+                  execution_process_id,
+                  metadata,
+                  attempt_count,
+                  max_attempts,
+                  deliveries
              FROM forge_omni_notifications
             WHERE status = 'pending'
+              AND (next_attempt_at IS NULL OR next_attempt_at <= datetime('now'))
             ORDER BY created_at
             LIMIT 1"#,
     )
@@ -404,12 +670,17 @@ async fn process_next_omni_notification(
 
     let row = PendingNotification {
         id: row.try_get::<String, _>("id")?,
+        notification_type: stored_notification_type(&row)?,
+        execution_process_id: row.try_get::<Option<String>, _>("execution_process_id")?,
         metadata: row.try_get::<Option<String>, _>("metadata")?,
+        attempt_count: row.try_get::<i64, _>("attempt_count")?,
+        max_attempts: row.try_get::<i64, _>("max_attempts")?,
+        deliveries: row.try_get::<Option<String>, _>("deliveries")?,
     };
 
     // Mark as processing to avoid multiple workers picking it up
     let claimed = sqlx::query(
-        "UPDATE forge_omni_notifications SET status = 'processing' WHERE id = ? AND status = 'pending'",
+        "UPDATE forge_omni_notifications SET status = 'processing', claimed_at = datetime('now') WHERE id = ? AND status = 'pending'",
     )
     .bind(&row.id)
     .execute(pool)
@@ -421,11 +692,15 @@ async fn process_next_omni_notification(
     }
 
     match handle_omni_notification(pool, config, &row).await {
-        Ok(OmniQueueAction::Sent { message }) => {
+        Ok(OmniQueueAction::Sent {
+            message,
+            recipients,
+        }) => {
             sqlx::query(
-                "UPDATE forge_omni_notifications SET status = 'sent', sent_at = CURRENT_TIMESTAMP, message = ? WHERE id = ?",
+                "UPDATE forge_omni_notifications SET status = 'sent', sent_at = CURRENT_TIMESTAMP, message = ?, recipient = ? WHERE id = ?",
             )
             .bind(&message)
+            .bind(&recipients)
             .bind(&row.id)
             .execute(pool)
             .await?;
@@ -439,39 +714,199 @@ async fn process_next_omni_notification(
             .execute(pool)
             .await?;
         }
-        Err(err) => {
+        Ok(OmniQueueAction::Batched {
+            digest_key,
+            line,
+            window,
+        }) => {
+            // The window runs from when the event was queued, so rows returning
+            // from a failed digest are flushed again right away
+            sqlx::query(
+                r#"UPDATE forge_omni_notifications
+                      SET status = 'batched',
+                          message = ?,
+                          digest_key = ?,
+                          next_attempt_at = datetime(created_at, '+' || ? || ' seconds')
+                    WHERE id = ?"#,
+            )
+            .bind(&line)
+            .bind(&digest_key)
+            .bind(window.as_secs() as i64)
+            .bind(&row.id)
+            .execute(pool)
+            .await?;
+        }
+        Ok(OmniQueueAction::Deferred { until, reason }) => {
+            tracing::info!(notification_id = %row.id, %until, "Omni notification deferred: {reason}");
+            // Not a failure: the attempt counter is left untouched
             sqlx::query(
-                "UPDATE forge_omni_notifications SET status = 'failed', error_message = ? WHERE id = ?",
+                r#"UPDATE forge_omni_notifications
+                      SET status = 'pending',
+                          claimed_at = NULL,
+                          error_message = ?,
+                          next_attempt_at = ?
+                    WHERE id = ?"#,
             )
-            .bind(err.to_string())
+            .bind(&reason)
+            .bind(sqlite_datetime(until))
             .bind(&row.id)
             .execute(pool)
             .await?;
         }
+        Err(err) => {
+            record_failed_attempt(pool, &row, &err.to_string()).await?;
+        }
     }
 
     Ok(true)
 }
 
+/// Base delay before the first retry; doubled for every subsequent attempt.
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(30);
+/// Upper bound for the backoff delay between two attempts.
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(30 * 60);
+
+/// Record a failed send and either reschedule the row or mark it `failed` once
+/// `max_attempts` is exhausted. Every error is appended to `attempt_errors`.
+async fn record_failed_attempt(
+    pool: &SqlitePool,
+    row: &PendingNotification,
+    error: &str,
+) -> Result<()> {
+    let attempt = row.attempt_count + 1;
+
+    if attempt >= row.max_attempts {
+        tracing::warn!(
+            notification_id = %row.id,
+            attempt,
+            "Omni notification failed permanently: {error}"
+        );
+
+        sqlx::query(
+            r#"UPDATE forge_omni_notifications
+                  SET status = 'failed',
+                      error_message = ?,
+                      attempt_count = ?,
+                      next_attempt_at = NULL,
+                      attempt_errors = json_insert(COALESCE(attempt_errors, '[]'), '$[#]',
+                          json_object('attempt', ?, 'error', ?, 'at', datetime('now')))
+                WHERE id = ?"#,
+        )
+        .bind(error)
+        .bind(attempt)
+        .bind(attempt)
+        .bind(error)
+        .bind(&row.id)
+        .execute(pool)
+        .await?;
+
+        return Ok(());
+    }
+
+    let delay = retry_delay(attempt, rand::random::<f64>());
+    tracing::info!(
+        notification_id = %row.id,
+        attempt,
+        retry_in_secs = delay.as_secs(),
+        "Omni notification failed; scheduling retry: {error}"
+    );
+
+    sqlx::query(
+        r#"UPDATE forge_omni_notifications
+              SET status = 'pending',
+                  error_message = ?,
+                  attempt_count = ?,
+                  next_attempt_at = datetime('now', '+' || ? || ' seconds'),
+                  attempt_errors = json_insert(COALESCE(attempt_errors, '[]'), '$[#]',
+                      json_object('attempt', ?, 'error', ?, 'at', datetime('now')))
+            WHERE id = ?"#,
+    )
+    .bind(error)
+    .bind(attempt)
+    .bind(delay.as_secs() as i64)
+    .bind(attempt)
+    .bind(error)
+    .bind(&row.id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Exponential backoff with "equal jitter": half of the capped delay is fixed and
+/// the other half is scaled by `jitter` (expected in `0.0..1.0`).
+fn retry_delay(attempt: i64, jitter: f64) -> Duration {
+    let exponent = attempt.saturating_sub(1).clamp(0, 16) as u32;
+    let capped = RETRY_BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(exponent))
+        .min(RETRY_MAX_DELAY);
+    let half = capped / 2;
+
+    half + half.mul_f64(jitter.clamp(0.0, 1.0))
+}
+
 #[derive(Debug)]
 enum OmniQueueAction {
-    Sent { message: String },
-    Skipped { reason: String },
+    Sent {
+        message: String,
+        /// Comma-separated Omni recipients the message went to
+        recipients: String,
+    },
+    /// Requeued without counting as a failed attempt (quiet hours, rate limit)
+    Deferred {
+        until: DateTime<Utc>,
+        reason: String,
+    },
+    Skipped {
+        reason: String,
+    },
+    /// Held for a digest; `line` is this notification's entry in the summary
+    Batched {
+        digest_key: String,
+        line: String,
+        window: Duration,
+    },
 }
 
 #[derive(Debug)]
 struct PendingNotification {
     id: String,
+    notification_type: NotificationType,
+    /// Set for execution exits
+    execution_process_id: Option<String>,
     metadata: Option<String>,
+    attempt_count: i64,
+    max_attempts: i64,
+    deliveries: Option<String>,
+}
+
+/// Rows queued before the event types existed, or with a type this build does not
+/// know, are treated as execution exits.
+fn stored_notification_type(row: &sqlx::sqlite::SqliteRow) -> Result<NotificationType> {
+    let stored: String = row.try_get("notification_type")?;
+    Ok(NotificationType::parse(&stored).unwrap_or_default())
 }
 
 #[derive(Debug, Deserialize)]
 struct OmniNotificationMetadata {
     task_attempt_id: Option<String>,
+    /// Set instead of `task_attempt_id` by task status changes
+    task_id: Option<String>,
     status: Option<String>,
+    previous_status: Option<String>,
     executor: Option<String>,
     branch: Option<String>,
     project_id: Option<String>,
+    exit_code: Option<i64>,
+    duration_secs: Option<i64>,
+    run_reason: Option<String>,
+    pr_number: Option<i64>,
+    pr_url: Option<String>,
+    /// `rebase` or `merge`, for merge conflicts
+    conflict_op: Option<String>,
+    /// Set when a user resends the row from the notification log
+    #[serde(default)]
+    manual_resend: bool,
 }
 
 async fn handle_omni_notification(
@@ -479,37 +914,80 @@ async fn handle_omni_notification(
     config: &ForgeConfigService,
     row: &PendingNotification,
 ) -> Result<OmniQueueAction> {
-    let metadata: OmniNotificationMetadata = match &row.metadata {
+    let mut raw_metadata: Value = match &row.metadata {
         Some(payload) if !payload.is_empty() => {
             serde_json::from_str(payload).with_context(|| "failed to deserialize omni metadata")?
         }
         _ => return Err(anyhow!("missing metadata for omni notification")),
     };
+    let metadata: OmniNotificationMetadata = serde_json::from_value(raw_metadata.clone())
+        .with_context(|| "failed to deserialize omni metadata")?;
+    // Channels receive the process id with the rest of the metadata
+    if let (Some(process_id), Some(fields)) =
+        (&row.execution_process_id, raw_metadata.as_object_mut())
+    {
+        fields.insert("execution_process_id".into(), json!(process_id));
+    }
 
-    let attempt_id_str = metadata
-        .task_attempt_id
-        .ok_or_else(|| anyhow!("metadata missing task_attempt_id"))?;
-    let attempt_id = Uuid::parse_str(&attempt_id_str)
-        .with_context(|| format!("invalid task_attempt_id UUID: {}", attempt_id_str))?;
+    let notification_type = row.notification_type;
+    let execution = notification_type == NotificationType::ExecutionCompleted;
     let status = metadata
         .status
         .ok_or_else(|| anyhow!("metadata missing status"))?;
 
-    let attempt_row = sqlx::query(
-        r#"SELECT
-                t.id         AS task_id,
-                t.title      AS title,
-                t.project_id AS project_id,
-                ta.branch    AS branch,
-                ta.executor  AS executor
-           FROM task_attempts ta
-           JOIN tasks t ON t.id = ta.task_id
-          WHERE ta.id = ?"#,
-    )
-    .bind(attempt_id)
-    .fetch_optional(pool)
-    .await?
-    .ok_or_else(|| anyhow!("task attempt not found for omni notification"))?;
+    let attempt_row = match (&metadata.task_attempt_id, &metadata.task_id) {
+        (Some(attempt_id), _) => {
+            let attempt_id = Uuid::parse_str(attempt_id)
+                .with_context(|| format!("invalid task_attempt_id UUID: {}", attempt_id))?;
+            sqlx::query(
+                r#"SELECT
+                        t.id         AS task_id,
+                        t.title      AS title,
+                        t.project_id AS project_id,
+                        ta.branch      AS branch,
+                        ta.base_branch AS base_branch,
+                        ta.executor    AS executor,
+                        p.git_repo_path AS git_repo_path
+                   FROM task_attempts ta
+                   JOIN tasks t ON t.id = ta.task_id
+                   LEFT JOIN projects p ON p.id = t.project_id
+                  WHERE ta.id = ?"#,
+            )
+            .bind(attempt_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| anyhow!("task attempt not found for omni notification"))?
+        }
+        // Task events describe the task's latest attempt, if it has one
+        (None, Some(task_id)) => {
+            let task_id = Uuid::parse_str(task_id)
+                .with_context(|| format!("invalid task_id UUID: {}", task_id))?;
+            sqlx::query(
+                r#"SELECT
+                        t.id         AS task_id,
+                        t.title      AS title,
+                        t.project_id AS project_id,
+                        ta.branch      AS branch,
+                        ta.base_branch AS base_branch,
+                        ta.executor    AS executor,
+                        p.git_repo_path AS git_repo_path
+                   FROM tasks t
+                   LEFT JOIN task_attempts ta ON ta.id = (
+                        SELECT id FROM task_attempts
+                         WHERE task_id = t.id
+                         ORDER BY created_at DESC
+                         LIMIT 1
+                   )
+                   LEFT JOIN projects p ON p.id = t.project_id
+                  WHERE t.id = ?"#,
+            )
+            .bind(task_id)
+            .fetch_optional(pool)
+            .await?
+            .ok_or_else(|| anyhow!("task not found for omni notification"))?
+        }
+        (None, None) => return Err(anyhow!("metadata missing task_attempt_id")),
+    };
 
     let project_id = if let Some(pid_str) = metadata.project_id {
         Uuid::parse_str(&pid_str)
@@ -519,20 +997,40 @@ async fn handle_omni_notification(
             .try_get::<Uuid, _>("project_id")
             .with_context(|| "missing project_id in database row")?
     };
-    let omni_config = config.effective_omni_config(Some(project_id)).await?;
+    let task_id: Uuid = attempt_row.try_get("task_id")?;
+    let settings = config
+        .effective_settings(Some(project_id), Some(task_id))
+        .await?;
 
-    if !omni_config.enabled {
+    // Rows are queued for every event; the project decides which ones it wants
+    if !metadata.manual_resend && !settings.notification_types().contains(&notification_type) {
         return Ok(OmniQueueAction::Skipped {
-            reason: "Omni notifications disabled for project".into(),
+            reason: format!(
+                "Notification type '{}' is not enabled for the project",
+                notification_type.as_str()
+            ),
         });
     }
 
-    let host = omni_config
-        .host
-        .as_deref()
-        .ok_or_else(|| anyhow!("Omni host not configured"))?;
-    if host.is_empty() {
-        return Err(anyhow!("Omni host configuration empty"));
+    let omni_config = settings.omni_config();
+    let channel_configs = settings.notification_channels();
+
+    // Recipient status filters describe execution exits
+    let mut channels = active_channels(
+        &channel_configs,
+        &omni_config,
+        execution.then_some(status.as_str()),
+    );
+
+    if channels.is_empty() {
+        let reason = if omni_config.enabled {
+            "No channel or Omni recipient accepts this status"
+        } else {
+            "Omni notifications disabled for project"
+        };
+        return Ok(OmniQueueAction::Skipped {
+            reason: reason.into(),
+        });
     }
 
     let branch = metadata
@@ -550,10 +1048,86 @@ async fn handle_omni_notification(
             .unwrap_or_else(|_| "unknown".into())
     });
 
+    let rules = settings.notification_rules();
+    let event = NotificationEvent {
+        notification_type,
+        run_reason: metadata.run_reason.as_deref(),
+        status: &status,
+        executor: &executor,
+        branch: &branch,
+    };
+    if !metadata.manual_resend
+        && let Some(rule) = rejecting_rule(&rules, &event)
+    {
+        return Ok(OmniQueueAction::Skipped {
+            reason: format!("Filtered by notification rule '{}'", rule.name),
+        });
+    }
+
     let title: String = attempt_row.try_get("title")?;
-    let task_id: Uuid = attempt_row.try_get("task_id")?;
 
-    let status_summary = format_status_summary(&status, &executor, &branch);
+    // Quiet hours are an Omni setting; the other channels are still notified
+    if !metadata.manual_resend
+        && let Some(quiet_hours) = &omni_config.quiet_hours
+        && quiet_hours.action == QuietHoursAction::Skip
+        && quiet_hours.window_end(chrono::Utc::now())?.is_some()
+    {
+        channels.retain(|target| !matches!(target.channel, NotificationChannelConfig::Omni));
+        if channels.is_empty() {
+            return Ok(OmniQueueAction::Skipped {
+                reason: "Dropped during quiet hours".into(),
+            });
+        }
+    }
+
+    if !metadata.manual_resend
+        && let Some(digest) = omni_config.digest.as_ref().filter(|digest| digest.enabled)
+    {
+        // A task with its own settings may route its digests differently
+        let task_settings = settings
+            .origins
+            .values()
+            .any(|origin| *origin == ConfigOrigin::Task);
+        return Ok(OmniQueueAction::Batched {
+            digest_key: digest_key(project_id, task_settings.then_some(task_id)),
+            line: format_digest_line(&status, &title, &branch),
+            window: Duration::from_secs(digest.window_secs),
+        });
+    }
+
+    let mut deliveries: serde_json::Map<String, Value> = row
+        .deliveries
+        .as_deref()
+        .and_then(|raw| serde_json::from_str(raw).ok())
+        .unwrap_or_default();
+    let recipients = omni_recipients(&channels);
+    let held = held_targets(pool, &channels, &deliveries).await?;
+    let ready: Vec<DeliveryTarget<'_>> = channels
+        .iter()
+        .filter(|target| !held.iter().any(|hold| hold.label == target.label))
+        .cloned()
+        .collect();
+    if let Some((until, reason)) = hold_until(&held)
+        && ready
+            .iter()
+            .all(|target| delivery_sent(&deliveries, &target.label))
+    {
+        return Ok(OmniQueueAction::Deferred { until, reason });
+    }
+
+    let status_summary = if execution {
+        format_status_summary(&status, &executor, &branch)
+    } else {
+        format_event_summary(
+            notification_type,
+            &status,
+            metadata.previous_status.as_deref(),
+            metadata.pr_number,
+            metadata.pr_url.as_deref(),
+            metadata.conflict_op.as_deref(),
+            &branch,
+        )
+    };
     let task_url = format!(
         "{}/projects/{}/tasks/{}",
         omni_base_url(),
@@ -562,99 +1136,825 @@ async fn handle_omni_notification(
     );
 
     tracing::info!(
-        "Attempting to send Omni notification for task '{}' with status '{}'",
+        "Attempting to send notification for task '{}' with status '{}'",
         title,
         status_summary
     );
 
-    let omni_service = OmniService::new(omni_config.clone());
+    // Only shell out to git when a template or an Omni recipient shows the diff
+    let omni_message = ready
+        .iter()
+        .any(|target| matches!(target.channel, NotificationChannelConfig::Omni))
+        .then(|| omni_config.message.clone())
+        .flatten()
+        .unwrap_or_default();
+    let template = settings.notification_template(notification_type);
+    let diff_stats = if omni_message.diff_summary
+        || template.as_deref().is_some_and(template_shows_diff_stats)
+    {
+        attempt_diff_stats(&attempt_row, &branch).await
+    } else {
+        None
+    };
+    let attachments = match omni_message.attachment {
+        OmniAttachmentMode::Patch => attempt_patch(&attempt_row, &branch)
+            .await
+            .into_iter()
+            .collect(),
+        OmniAttachmentMode::None => vec![],
+    };
 
-    match omni_service
-        .send_task_notification(&title, &status_summary, Some(&task_url))
-        .await
+    let body = match template {
+        Some(template) => {
+            let context = TemplateContext {
+                event: notification_type.as_str().to_string(),
+                title: title.clone(),
+                status: status.clone(),
+                previous_status: metadata.previous_status,
+                summary: status_summary.clone(),
+                executor,
+                branch: branch.clone(),
+                exit_code: metadata.exit_code,
+                task_url: Some(task_url.clone()),
+                pr_number: metadata.pr_number,
+                pr_url: metadata.pr_url,
+                conflict_op: metadata.conflict_op,
+                ..Default::default()
+            }
+            .with_duration_secs(metadata.duration_secs)
+            .with_diff_stats(diff_stats);
+
+            // Templates are validated on save; a render failure falls back to the default text
+            match render_template(&template, &context) {
+                Ok(rendered) if !rendered.is_empty() => Some(rendered),
+                Ok(_) => None,
+                Err(err) => {
+                    tracing::warn!("Failed to render notification template: {}", err);
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+    let summary = body.clone().unwrap_or_else(|| status_summary.clone());
+
+    let message = NotificationMessage {
+        id: row.id.clone(),
+        title: title.clone(),
+        summary: status_summary,
+        url: Some(task_url),
+        metadata: raw_metadata,
+        notification_type,
+        body,
+        diff_stats,
+        attachments,
+    };
+
+    let failures = deliver_to_channels(&ready, &message, &mut deliveries).await;
+
+    sqlx::query("UPDATE forge_omni_notifications SET deliveries = ? WHERE id = ?")
+        .bind(Value::Object(deliveries).to_string())
+        .bind(&row.id)
+        .execute(pool)
+        .await?;
+
+    if !failures.is_empty() {
+        return Err(anyhow!(failures.join("; ")));
+    }
+
+    // The targets sent to now stay recorded; only the held ones are retried
+    if let Some((until, reason)) = hold_until(&held) {
+        return Ok(OmniQueueAction::Deferred { until, reason });
+    }
+
+    Ok(OmniQueueAction::Sent {
+        message: summary,
+        recipients: recipients.join(","),
+    })
+}
+
+/// A channel a notification is delivered to. Its Omni settings are narrowed to a
+/// single recipient when several are configured, so each recipient is recorded
+/// under its own delivery label.
+#[derive(Clone)]
+struct DeliveryTarget<'a> {
+    label: String,
+    channel: &'a NotificationChannelConfig,
+    omni_config: OmniConfig,
+}
+
+/// Selected channels expanded into delivery targets. Omni keeps its own on/off
+/// switch and per-recipient status filters; the other channels are active once
+/// selected. Digests pass no status and reach every recipient.
+fn active_channels<'a>(
+    channel_configs: &'a [NotificationChannelConfig],
+    omni_config: &OmniConfig,
+    status: Option<&str>,
+) -> Vec<DeliveryTarget<'a>> {
+    let mut targets = Vec::new();
+
+    for (label, channel) in channel_labels(channel_configs)
+        .into_iter()
+        .zip(channel_configs.iter())
     {
-        Ok(()) => {
-            tracing::info!("Successfully sent Omni notification for task '{}'", title);
-            Ok(OmniQueueAction::Sent {
-                message: status_summary,
-            })
+        if !matches!(channel, NotificationChannelConfig::Omni) {
+            targets.push(DeliveryTarget {
+                label,
+                channel,
+                omni_config: omni_config.clone(),
+            });
+            continue;
         }
-        Err(e) => {
-            tracing::error!("Failed to send Omni notification: {}", e);
-            Err(e)
+        if !omni_config.enabled {
+            continue;
+        }
+        // A single legacy recipient keeps the plain channel label
+        if omni_config.recipients.is_empty() {
+            targets.push(DeliveryTarget {
+                label,
+                channel,
+                omni_config: omni_config.clone(),
+            });
+            continue;
+        }
+
+        for recipient in &omni_config.recipients {
+            if status.is_some_and(|status| !recipient.accepts_status(status)) {
+                continue;
+            }
+            targets.push(DeliveryTarget {
+                label: format!("{label}:{}", recipient.display_name()),
+                channel,
+                omni_config: omni_config.for_recipient(recipient),
+            });
         }
     }
+
+    targets
 }
 
-fn format_status_summary(status: &str, executor: &str, branch: &str) -> String {
-    match status {
-        "completed" => format!("✅ Execution completed\nBranch: {branch}\nExecutor: {executor}"),
-        "failed" => format!("❌ Execution failed\nBranch: {branch}\nExecutor: {executor}"),
-        "killed" => format!("🛑 Execution cancelled\nBranch: {branch}\nExecutor: {executor}"),
-        other => format!("{other}\nBranch: {branch}\nExecutor: {executor}"),
+/// Send `message` to every target not yet marked sent in `deliveries`, recording
+/// each result there. Returns the failures as `label: error`.
+async fn deliver_to_channels(
+    targets: &[DeliveryTarget<'_>],
+    message: &NotificationMessage,
+    deliveries: &mut serde_json::Map<String, Value>,
+) -> Vec<String> {
+    let mut failures = Vec::new();
+
+    for DeliveryTarget {
+        label,
+        channel,
+        omni_config,
+    } in targets
+    {
+        if delivery_sent(deliveries, label) {
+            continue;
+        }
+
+        let result = match build_channel(channel, omni_config) {
+            Ok(channel) => channel.send(message).await,
+            Err(err) => Err(err),
+        };
+
+        let delivery = match result {
+            Ok(()) => {
+                tracing::info!(channel = %label, "Sent notification '{}'", message.title);
+                let mut delivery =
+                    json!({ "status": "sent", "at": chrono::Utc::now().to_rfc3339() });
+                // Rate limits count the messages each Omni recipient received
+                if matches!(channel, NotificationChannelConfig::Omni)
+                    && let Some(recipient) = omni_config.targets().first()
+                {
+                    delivery["recipient"] = json!(recipient.recipient);
+                }
+                delivery
+            }
+            Err(err) => {
+                tracing::error!(channel = %label, "Failed to send notification: {}", err);
+                failures.push(format!("{label}: {err}"));
+                json!({
+                    "status": "failed",
+                    "error": err.to_string(),
+                    "at": chrono::Utc::now().to_rfc3339(),
+                })
+            }
+        };
+        deliveries.insert(label.clone(), delivery);
     }
+
+    failures
 }
 
-fn omni_base_url() -> String {
-    if let Ok(url) = std::env::var("PUBLIC_BASE_URL") {
-        return url.trim_end_matches('/').to_string();
+/// Rows of a project are digested together; rows of a task with its own settings
+/// form a group of their own.
+fn digest_key(project_id: Uuid, task_id: Option<Uuid>) -> String {
+    match task_id {
+        Some(task_id) => format!("{project_id}/{task_id}"),
+        None => project_id.to_string(),
     }
+}
 
-    let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
-    let port = std::env::var("BACKEND_PORT")
-        .or_else(|_| std::env::var("PORT"))
-        .unwrap_or_else(|_| "8887".to_string());
+/// Project and, for a task's own group, task of a [`digest_key`].
+fn parse_digest_key(digest_key: &str) -> Result<(Uuid, Option<Uuid>)> {
+    let invalid = || format!("invalid digest key: {digest_key}");
+    let (project_id, task_id) = match digest_key.split_once('/') {
+        Some((project_id, task_id)) => (project_id, Some(task_id)),
+        None => (digest_key, None),
+    };
 
-    format!("http://{host}:{port}")
+    Ok((
+        Uuid::parse_str(project_id).with_context(invalid)?,
+        task_id
+            .map(Uuid::parse_str)
+            .transpose()
+            .with_context(invalid)?,
+    ))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use forge_config::{ForgeConfigService, ForgeProjectSettings, OmniConfig, RecipientType};
-    use httpmock::prelude::*;
-    use serde_json::json;
-    use sqlx::SqlitePool;
-    use uuid::Uuid;
+/// Send one summary message for every digest group whose window has closed or that
+/// reached `max_items`. Returns whether a digest was attempted.
+async fn flush_due_digests(pool: &SqlitePool, config: &ForgeConfigService) -> Result<bool> {
+    let groups: Vec<(String, i64, bool)> = sqlx::query_as(
+        r#"SELECT digest_key,
+                  COUNT(*),
+                  MIN(next_attempt_at) <= datetime('now')
+             FROM forge_omni_notifications
+            WHERE status = 'batched'
+              AND digest_key IS NOT NULL
+            GROUP BY digest_key"#,
+    )
+    .fetch_all(pool)
+    .await?;
 
-    async fn setup_pool() -> SqlitePool {
-        let pool = SqlitePool::connect("sqlite::memory:")
-            .await
-            .expect("failed to create in-memory pool");
+    let mut flushed = false;
+    for (digest_key, count, window_closed) in groups {
+        let (project_id, task_id) = parse_digest_key(&digest_key)?;
+        // Resolved like a single notification, so layered task settings apply too
+        let settings = config.effective_settings(Some(project_id), task_id).await?;
+        let omni_config = settings.omni_config();
+        // Digest switched off while rows were waiting: flush them right away
+        let max_items = omni_config
+            .digest
+            .as_ref()
+            .filter(|digest| digest.enabled)
+            .map_or(1, |digest| i64::from(digest.max_items.max(1)));
+
+        if !window_closed && count < max_items {
+            continue;
+        }
 
-        sqlx::query(
-            r#"CREATE TABLE tasks (
-                id TEXT PRIMARY KEY,
-                project_id TEXT,
-                title TEXT NOT NULL,
-                description TEXT,
-                status TEXT,
-                parent_task_attempt TEXT,
-                created_at TEXT,
-                updated_at TEXT
-            )"#,
-        )
-        .execute(&pool)
-        .await
-        .expect("failed to create tasks table");
+        let channel_configs = settings.notification_channels();
+        let channels = active_channels(&channel_configs, &omni_config, None);
+        let held = held_targets(pool, &channels, &serde_json::Map::new()).await?;
 
-        sqlx::query(
-            r#"CREATE TABLE projects (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL
-            )"#,
-        )
+        if held.len() == channels.len()
+            && let Some((until, reason)) = hold_until(&held)
+        {
+            // Move the group's deadline instead of polling until delivery is allowed
+            tracing::info!(digest_key = %digest_key, "Digest postponed: {reason}");
+            sqlx::query(
+                "UPDATE forge_omni_notifications SET next_attempt_at = ? WHERE status = 'batched' AND digest_key = ?",
+            )
+            .bind(sqlite_datetime(until))
+            .bind(&digest_key)
+            .execute(pool)
+            .await?;
+            continue;
+        }
+
+        send_digest(pool, &digest_key, project_id, &channels, &held, max_items).await?;
+        flushed = true;
+    }
+
+    Ok(flushed)
+}
+
+/// Send a digest of the group's batched rows to every target that is not held.
+/// Rows that only wait for held targets return to the group until the hold ends.
+async fn send_digest(
+    pool: &SqlitePool,
+    digest_key: &str,
+    project_id: Uuid,
+    channels: &[DeliveryTarget<'_>],
+    held: &[HeldTarget],
+    max_items: i64,
+) -> Result<()> {
+    let rows = sqlx::query(
+        r#"SELECT id,
+                  notification_type,
+                  message,
+                  metadata,
+                  attempt_count,
+                  max_attempts,
+                  deliveries
+             FROM forge_omni_notifications
+            WHERE status = 'batched'
+              AND digest_key = ?
+            ORDER BY created_at
+            LIMIT ?"#,
+    )
+    .bind(digest_key)
+    .bind(max_items)
+    .fetch_all(pool)
+    .await?;
+
+    let mut batch = Vec::with_capacity(rows.len());
+    for row in rows {
+        let id: String = row.try_get("id")?;
+        let claimed = sqlx::query(
+            "UPDATE forge_omni_notifications SET status = 'processing', claimed_at = datetime('now') WHERE id = ? AND status = 'batched'",
+        )
+        .bind(&id)
+        .execute(pool)
+        .await?;
+        if claimed.rows_affected() == 0 {
+            continue;
+        }
+
+        let line: String = row.try_get("message")?;
+        batch.push((
+            line,
+            PendingNotification {
+                id,
+                notification_type: stored_notification_type(&row)?,
+                execution_process_id: None,
+                metadata: row.try_get("metadata")?,
+                attempt_count: row.try_get("attempt_count")?,
+                max_attempts: row.try_get("max_attempts")?,
+                deliveries: row.try_get("deliveries")?,
+            },
+        ));
+    }
+
+    if batch.is_empty() {
+        return Ok(());
+    }
+
+    let digest_id = Uuid::new_v4().to_string();
+    // Rows retried after a failed digest keep the channels they already reached
+    let mut row_deliveries: Vec<serde_json::Map<String, Value>> = batch
+        .iter()
+        .map(|(_, row)| {
+            row.deliveries
+                .as_deref()
+                .and_then(|raw| serde_json::from_str(raw).ok())
+                .unwrap_or_default()
+        })
+        .collect();
+
+    // Each channel gets a digest of the rows it has not received yet
+    let mut failures = Vec::new();
+    for target in channels
+        .iter()
+        .filter(|target| !held.iter().any(|hold| hold.label == target.label))
+    {
+        let pending: Vec<usize> = (0..batch.len())
+            .filter(|&index| !delivery_sent(&row_deliveries[index], &target.label))
+            .collect();
+        if pending.is_empty() {
+            continue;
+        }
+
+        let rows: Vec<&(String, PendingNotification)> =
+            pending.iter().map(|&index| &batch[index]).collect();
+        let message = digest_message(project_id, &digest_id, &rows);
+        let mut delivery = serde_json::Map::new();
+        failures.extend(
+            deliver_to_channels(std::slice::from_ref(target), &message, &mut delivery).await,
+        );
+        // A digest counts as one message against rate limits
+        for entry in delivery.values_mut() {
+            entry["digest_id"] = json!(digest_id);
+        }
+        for index in pending {
+            row_deliveries[index].extend(delivery.clone());
+        }
+    }
+
+    let recipients = omni_recipients(channels).join(",");
+    for ((_, row), deliveries) in batch.iter().zip(row_deliveries) {
+        let failed = channels.iter().any(|target| {
+            !delivery_sent(&deliveries, &target.label)
+                && !held.iter().any(|hold| hold.label == target.label)
+        });
+        let waiting = hold_until(
+            held.iter()
+                .filter(|hold| !delivery_sent(&deliveries, &hold.label)),
+        );
+        let deliveries = Value::Object(deliveries).to_string();
+        if !failed && waiting.is_none() {
+            sqlx::query(
+                "UPDATE forge_omni_notifications SET status = 'sent', sent_at = CURRENT_TIMESTAMP, digest_id = ?, deliveries = ?, recipient = ? WHERE id = ?",
+            )
+            .bind(&digest_id)
+            .bind(&deliveries)
+            .bind(&recipients)
+            .bind(&row.id)
+            .execute(pool)
+            .await?;
+        } else if let Some((until, reason)) = waiting.filter(|_| !failed) {
+            // Not a failure: the row rejoins the group once its held targets free up
+            sqlx::query(
+                r#"UPDATE forge_omni_notifications
+                      SET status = 'batched',
+                          claimed_at = NULL,
+                          deliveries = ?,
+                          error_message = ?,
+                          next_attempt_at = ?
+                    WHERE id = ?"#,
+            )
+            .bind(&deliveries)
+            .bind(&reason)
+            .bind(sqlite_datetime(until))
+            .bind(&row.id)
+            .execute(pool)
+            .await?;
+        } else {
+            sqlx::query("UPDATE forge_omni_notifications SET deliveries = ? WHERE id = ?")
+                .bind(&deliveries)
+                .bind(&row.id)
+                .execute(pool)
+                .await?;
+            // Rows go back to `pending` with backoff and join a new digest once due
+            record_failed_attempt(pool, row, &failures.join("; ")).await?;
+        }
+    }
+
+    tracing::info!(
+        digest_id = %digest_id,
+        items = batch.len(),
+        "Processed Omni notification digest"
+    );
+
+    Ok(())
+}
+
+/// Whether `deliveries` records a successful send to the target `label`.
+fn delivery_sent(deliveries: &serde_json::Map<String, Value>, label: &str) -> bool {
+    deliveries
+        .get(label)
+        .and_then(|delivery| delivery.get("status"))
+        .and_then(Value::as_str)
+        == Some("sent")
+}
+
+/// Summary message listing the `batch` rows of one digest.
+fn digest_message(
+    project_id: Uuid,
+    digest_id: &str,
+    batch: &[&(String, PendingNotification)],
+) -> NotificationMessage {
+    let lines: Vec<&str> = batch.iter().map(|(line, _)| line.as_str()).collect();
+    let executions_only = batch
+        .iter()
+        .all(|(_, row)| row.notification_type == NotificationType::ExecutionCompleted);
+    let title = match (batch.len(), executions_only) {
+        (1, true) => "1 task finished".to_string(),
+        (count, true) => format!("{count} tasks finished"),
+        (1, false) => "1 task update".to_string(),
+        (count, false) => format!("{count} task updates"),
+    };
+    let summary = lines.join("\n");
+    NotificationMessage {
+        id: digest_id.to_string(),
+        title: title.clone(),
+        summary: summary.clone(),
+        url: Some(format!("{}/projects/{}/tasks", omni_base_url(), project_id)),
+        metadata: json!({
+            "digest_id": digest_id,
+            "project_id": project_id,
+            "notification_ids": batch.iter().map(|(_, row)| &row.id).collect::<Vec<_>>(),
+        }),
+        notification_type: NotificationType::default(),
+        body: Some(format!("📋 Forge digest: {title}\n\n{summary}")),
+        diff_stats: None,
+        attachments: vec![],
+    }
+}
+
+/// Omni recipients a notification is delivered to.
+fn omni_recipients(targets: &[DeliveryTarget<'_>]) -> Vec<String> {
+    targets
+        .iter()
+        .filter(|target| matches!(target.channel, NotificationChannelConfig::Omni))
+        .flat_map(|target| target.omni_config.targets())
+        .map(|recipient| recipient.recipient)
+        .collect()
+}
+
+/// A delivery target that has to wait, and the moment it can be sent to again.
+struct HeldTarget {
+    label: String,
+    until: DateTime<Utc>,
+    reason: String,
+}
+
+/// Targets not yet sent to according to `deliveries` that are currently held back.
+/// Quiet hours, instance health and rate limits are Omni settings, so they only
+/// ever hold Omni recipients.
+async fn held_targets(
+    pool: &SqlitePool,
+    targets: &[DeliveryTarget<'_>],
+    deliveries: &serde_json::Map<String, Value>,
+) -> Result<Vec<HeldTarget>> {
+    let mut held = Vec::new();
+    for target in targets {
+        if delivery_sent(deliveries, &target.label) {
+            continue;
+        }
+        if let Some((until, reason)) = delivery_blocked_until(pool, target).await? {
+            held.push(HeldTarget {
+                label: target.label.clone(),
+                until,
+                reason,
+            });
+        }
+    }
+
+    Ok(held)
+}
+
+/// When the first of `held` can be delivered to, with every hold as `label: reason`.
+fn hold_until<'a>(
+    held: impl IntoIterator<Item = &'a HeldTarget>,
+) -> Option<(DateTime<Utc>, String)> {
+    let held: Vec<&HeldTarget> = held.into_iter().collect();
+    let until = held.iter().map(|hold| hold.until).min()?;
+    let reason = held
+        .iter()
+        .map(|hold| format!("{}: {}", hold.label, hold.reason))
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    Some((until, reason))
+}
+
+/// When quiet hours (in hold mode), an unhealthy Omni instance or the recipient's
+/// rate limit currently block delivery to an Omni `target`, the moment delivery is
+/// allowed again and the reason.
+async fn delivery_blocked_until(
+    pool: &SqlitePool,
+    target: &DeliveryTarget<'_>,
+) -> Result<Option<(DateTime<Utc>, String)>> {
+    if !matches!(target.channel, NotificationChannelConfig::Omni) {
+        return Ok(None);
+    }
+    let omni_config = &target.omni_config;
+
+    if let Some(quiet_hours) = &omni_config.quiet_hours
+        && let Some(window_end) = quiet_hours.window_end(Utc::now())?
+    {
+        return Ok(Some((window_end, "Held until quiet hours end".into())));
+    }
+
+    let Some(recipient) = omni_config.targets().pop() else {
+        return Ok(None);
+    };
+
+    // Only the instance this recipient is sent through; checked again once the
+    // monitor has run, and recovery resumes the row right away
+    if let Some(instance) = recipient
+        .instance
+        .as_deref()
+        .or(omni_config.instance.as_deref())
+        && omni_health::is_paused(pool, instance).await?
+    {
+        let until = Utc::now() + chrono::Duration::from_std(omni_health::HEALTH_CHECK_INTERVAL)?;
+        return Ok(Some((until, omni_health::paused_reason(instance))));
+    }
+
+    let Some(limit) = &omni_config.rate_limit else {
+        return Ok(None);
+    };
+
+    // Every delivery to an Omni recipient records it; a digest counts as one message
+    let (sent, frees_at): (i64, Option<String>) = sqlx::query_as(
+        r#"SELECT COUNT(DISTINCT COALESCE(json_extract(d.value, '$.digest_id'), n.id)),
+                  datetime(MIN(datetime(json_extract(d.value, '$.at'))), '+' || ? || ' seconds')
+             FROM forge_omni_notifications n, json_each(n.deliveries) d
+            WHERE instr(n.deliveries, ?) > 0
+              AND json_extract(d.value, '$.status') = 'sent'
+              AND json_extract(d.value, '$.recipient') = ?
+              AND datetime(json_extract(d.value, '$.at')) > datetime('now', '-' || ? || ' seconds')"#,
+    )
+    .bind(limit.period_secs as i64)
+    .bind(&recipient.recipient)
+    .bind(&recipient.recipient)
+    .bind(limit.period_secs as i64)
+    .fetch_one(pool)
+    .await?;
+
+    if sent < i64::from(limit.max_messages) {
+        return Ok(None);
+    }
+
+    let until = frees_at
+        .and_then(|value| NaiveDateTime::parse_from_str(&value, "%Y-%m-%d %H:%M:%S").ok())
+        .map_or_else(
+            || Utc::now() + chrono::Duration::seconds(limit.period_secs as i64),
+            |value| value.and_utc(),
+        );
+
+    Ok(Some((
+        until,
+        format!("Rate limit reached for {}", recipient.recipient),
+    )))
+}
+
+/// Format a timestamp the way SQLite's `datetime()` does, so comparisons with
+/// `datetime('now')` work.
+fn sqlite_datetime(value: DateTime<Utc>) -> String {
+    value.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+fn format_digest_line(status: &str, title: &str, branch: &str) -> String {
+    let icon = match status {
+        "completed" => "✅",
+        "failed" => "❌",
+        "killed" => "🛑",
+        "inreview" => "👀",
+        "done" => "🏁",
+        "open" => "🔀",
+        "merged" => "🎉",
+        "conflict" => "⚠️",
+        _ => "•",
+    };
+    format!("{icon} {title} ({branch})")
+}
+
+async fn attempt_diff_stats(
+    attempt_row: &sqlx::sqlite::SqliteRow,
+    branch: &str,
+) -> Option<forge_notifications::DiffStats> {
+    let repo_path: String = attempt_row.try_get("git_repo_path").ok().flatten()?;
+    let base_branch: String = attempt_row.try_get("base_branch").ok().flatten()?;
+    diff_stats::branch_diff_stats(std::path::Path::new(&repo_path), &base_branch, branch).await
+}
+
+async fn attempt_patch(
+    attempt_row: &sqlx::sqlite::SqliteRow,
+    branch: &str,
+) -> Option<NotificationAttachment> {
+    let repo_path: String = attempt_row.try_get("git_repo_path").ok().flatten()?;
+    let base_branch: String = attempt_row.try_get("base_branch").ok().flatten()?;
+    let content = diff_stats::branch_patch(
+        std::path::Path::new(&repo_path),
+        &base_branch,
+        branch,
+        MAX_PATCH_ATTACHMENT_BYTES,
+    )
+    .await?;
+
+    Some(NotificationAttachment {
+        filename: format!("{}.patch", branch.replace('/', "-")),
+        mime_type: "text/x-diff".into(),
+        content,
+    })
+}
+
+fn template_shows_diff_stats(template: &str) -> bool {
+    ["diff_stats", "files_changed", "insertions", "deletions"]
+        .iter()
+        .any(|placeholder| template.contains(placeholder))
+}
+
+fn format_status_summary(status: &str, executor: &str, branch: &str) -> String {
+    match status {
+        "completed" => format!("✅ Execution completed\nBranch: {branch}\nExecutor: {executor}"),
+        "failed" => format!("❌ Execution failed\nBranch: {branch}\nExecutor: {executor}"),
+        "killed" => format!("🛑 Execution cancelled\nBranch: {branch}\nExecutor: {executor}"),
+        other => format!("{other}\nBranch: {branch}\nExecutor: {executor}"),
+    }
+}
+
+/// Summary of an event other than an execution exit.
+fn format_event_summary(
+    notification_type: NotificationType,
+    status: &str,
+    previous_status: Option<&str>,
+    pr_number: Option<i64>,
+    pr_url: Option<&str>,
+    conflict_op: Option<&str>,
+    branch: &str,
+) -> String {
+    let pull_request = pr_number.map_or_else(
+        || "Pull request".to_string(),
+        |n| format!("Pull request #{n}"),
+    );
+    let mut summary = match notification_type {
+        NotificationType::TaskStatusChanged => {
+            let headline = match status {
+                "inreview" => "👀 Ready for review".to_string(),
+                "done" => "🏁 Done".to_string(),
+                other => format!("📋 Moved to {other}"),
+            };
+            match previous_status {
+                Some(previous) => format!("{headline} (was {previous})"),
+                None => headline,
+            }
+        }
+        NotificationType::PrCreated => format!("🔀 {pull_request} opened"),
+        NotificationType::PrMerged => format!("🎉 {pull_request} merged"),
+        NotificationType::MergeConflict => {
+            format!("⚠️ Conflicts during {}", conflict_op.unwrap_or("merge"))
+        }
+        NotificationType::ExecutionCompleted => status.to_string(),
+    };
+    summary.push_str(&format!("\nBranch: {branch}"));
+    if let Some(url) = pr_url {
+        summary.push_str(&format!("\n{url}"));
+    }
+    summary
+}
+
+fn omni_base_url() -> String {
+    if let Ok(url) = std::env::var("PUBLIC_BASE_URL") {
+        return url.trim_end_matches('/').to_string();
+    }
+
+    let host = std::env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+    let port = std::env::var("BACKEND_PORT")
+        .or_else(|_| std::env::var("PORT"))
+        .unwrap_or_else(|_| "8887".to_string());
+
+    format!("http://{host}:{port}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forge_config::{
+        ForgeConfigService, ForgeProjectSettings, OmniConfig, RecipientType, SettingsSource,
+    };
+    use forge_notifications::{NotificationRule, SlackChannelConfig};
+    use forge_omni::{
+        OmniDigestConfig, OmniMessageConfig, OmniRateLimitConfig, OmniRecipient, QuietHoursConfig,
+        fake::FakeOmni,
+    };
+    use httpmock::prelude::*;
+    use serde_json::json;
+    use sqlx::SqlitePool;
+    use utils::{log_msg::LogMsg, msg_store::MsgStore};
+    use uuid::Uuid;
+
+    pub(super) async fn setup_pool() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:")
+            .await
+            .expect("failed to create in-memory pool");
+
+        sqlx::query(
+            r#"CREATE TABLE tasks (
+                id TEXT PRIMARY KEY,
+                project_id TEXT,
+                title TEXT NOT NULL,
+                description TEXT,
+                status TEXT,
+                parent_task_attempt TEXT,
+                created_at TEXT,
+                updated_at TEXT
+            )"#,
+        )
+        .execute(&pool)
+        .await
+        .expect("failed to create tasks table");
+
+        sqlx::query(
+            r#"CREATE TABLE projects (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                git_repo_path TEXT,
+                setup_script TEXT,
+                dev_script TEXT,
+                cleanup_script TEXT,
+                copy_files TEXT
+            )"#,
+        )
         .execute(&pool)
         .await
         .expect("failed to create projects table");
 
+        sqlx::query(
+            r#"CREATE TABLE task_templates (
+                id TEXT PRIMARY KEY,
+                project_id TEXT,
+                title TEXT NOT NULL,
+                description TEXT,
+                template_name TEXT NOT NULL
+            )"#,
+        )
+        .execute(&pool)
+        .await
+        .expect("failed to create task_templates table");
+
         sqlx::query(
             r#"CREATE TABLE task_attempts (
                 id TEXT PRIMARY KEY,
                 task_id TEXT NOT NULL,
                 branch TEXT,
                 base_branch TEXT,
-                executor TEXT
+                executor TEXT,
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP
             )"#,
         )
         .execute(&pool)
@@ -667,6 +1967,10 @@ mod tests {
                 task_attempt_id TEXT NOT NULL,
                 status TEXT NOT NULL,
                 run_reason TEXT NOT NULL,
+                executor_action TEXT NOT NULL DEFAULT '{}',
+                exit_code INTEGER,
+                started_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                completed_at TEXT,
                 updated_at TEXT DEFAULT CURRENT_TIMESTAMP
             )"#,
         )
@@ -674,6 +1978,25 @@ mod tests {
         .await
         .expect("failed to create execution_processes table");
 
+        sqlx::query(
+            r#"CREATE TABLE merges (
+                id TEXT PRIMARY KEY,
+                task_attempt_id TEXT NOT NULL,
+                merge_type TEXT NOT NULL,
+                merge_commit TEXT,
+                pr_number INTEGER,
+                pr_url TEXT,
+                pr_status TEXT,
+                pr_merged_at TEXT,
+                pr_merge_commit_sha TEXT,
+                target_branch_name TEXT,
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP
+            )"#,
+        )
+        .execute(&pool)
+        .await
+        .expect("failed to create merges table");
+
         apply_forge_migrations(&pool)
             .await
             .expect("forge migrations should apply cleanly");
@@ -681,7 +2004,7 @@ mod tests {
         pool
     }
 
-    async fn insert_project(pool: &SqlitePool, project_id: Uuid) {
+    pub(super) async fn insert_project(pool: &SqlitePool, project_id: Uuid) {
         sqlx::query("INSERT INTO projects (id, name) VALUES (?, 'Forge Project')")
             .bind(project_id)
             .execute(pool)
@@ -689,7 +2012,7 @@ mod tests {
             .expect("failed to insert project row");
     }
 
-    async fn insert_task_graph(pool: &SqlitePool, project_id: Uuid) -> (Uuid, Uuid) {
+    pub(super) async fn insert_task_graph(pool: &SqlitePool, project_id: Uuid) -> (Uuid, Uuid) {
         let task_id = Uuid::new_v4();
         let attempt_id = Uuid::new_v4();
 
@@ -716,13 +2039,14 @@ mod tests {
         (task_id, attempt_id)
     }
 
-    fn pending_metadata(attempt_id: Uuid, project_id: Uuid) -> String {
+    pub(super) fn pending_metadata(attempt_id: Uuid, project_id: Uuid) -> String {
         json!({
             "task_attempt_id": attempt_id,
             "status": "completed",
             "executor": "forge-agent",
             "branch": "feature/test",
             "project_id": project_id,
+            "run_reason": "codingagent",
         })
         .to_string()
     }
@@ -743,7 +2067,12 @@ mod tests {
             &config,
             &PendingNotification {
                 id: "notif-1".into(),
+                notification_type: NotificationType::ExecutionCompleted,
+                execution_process_id: None,
                 metadata: Some(pending_metadata(attempt_id, project_id)),
+                attempt_count: 0,
+                max_attempts: 5,
+                deliveries: None,
             },
         )
         .await
@@ -767,19 +2096,16 @@ mod tests {
 
         let config_service = ForgeConfigService::new(pool.clone());
         let settings = ForgeProjectSettings {
-            omni_enabled: true,
+            omni_enabled: Some(true),
             omni_config: Some(OmniConfig {
-                enabled: true,
                 host: None,
-                api_key: None,
-                instance: Some("forge-instance".into()),
-                recipient: Some("+15550001111".into()),
-                recipient_type: Some(RecipientType::PhoneNumber),
+                ..test_omni_config(String::new())
             }),
+            ..Default::default()
         };
 
         config_service
-            .set_global_settings(&settings)
+            .set_global_settings(&settings, SettingsSource::Api)
             .await
             .expect("should store global settings");
 
@@ -788,7 +2114,12 @@ mod tests {
             &config_service,
             &PendingNotification {
                 id: "notif-missing-host".into(),
+                notification_type: NotificationType::ExecutionCompleted,
+                execution_process_id: None,
                 metadata: Some(pending_metadata(attempt_id, project_id)),
+                attempt_count: 0,
+                max_attempts: 5,
+                deliveries: None,
             },
         )
         .await
@@ -797,6 +2128,76 @@ mod tests {
         assert!(err.to_string().contains("Omni host"));
     }
 
+    #[tokio::test]
+    async fn project_template_renders_notification_text() {
+        let pool = setup_pool().await;
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, project_id).await;
+        let (_task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/forge-instance/send-text")
+                .json_body_partial(
+                    json!({ "text": "Falhou: Omni Notification Test (1m 05s, exit 2)" })
+                        .to_string(),
+                );
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .json_body(json!({ "success": true, "message_id": "msg-1", "status": "queued" }));
+        });
+
+        let config_service = ForgeConfigService::new(pool.clone());
+        config_service
+            .set_forge_settings(
+                project_id,
+                &ForgeProjectSettings {
+                    omni_enabled: Some(true),
+                    omni_config: Some(test_omni_config(server.base_url())),
+                    notification_template: Some(
+                        "{% if status == 'failed' %}Falhou{% else %}Concluída{% endif %}: \
+                         {{ title }} ({{ duration }}, exit {{ exit_code }})"
+                            .into(),
+                    ),
+                    ..Default::default()
+                },
+                SettingsSource::Api,
+            )
+            .await
+            .expect("should persist project settings");
+
+        let mut metadata: Value =
+            serde_json::from_str(&pending_metadata(attempt_id, project_id)).unwrap();
+        metadata["status"] = json!("failed");
+        metadata["exit_code"] = json!(2);
+        metadata["duration_secs"] = json!(65);
+
+        let result = handle_omni_notification(
+            &pool,
+            &config_service,
+            &PendingNotification {
+                id: "notif-template".into(),
+                notification_type: NotificationType::ExecutionCompleted,
+                execution_process_id: None,
+                metadata: Some(metadata.to_string()),
+                attempt_count: 0,
+                max_attempts: 5,
+                deliveries: None,
+            },
+        )
+        .await
+        .expect("templated notification should send");
+
+        mock.assert_async().await;
+        match result {
+            OmniQueueAction::Sent { message, .. } => {
+                assert_eq!(message, "Falhou: Omni Notification Test (1m 05s, exit 2)");
+            }
+            other => panic!("expected sent, got {other:?}"),
+        }
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn process_next_notification_marks_sent() {
         let pool = setup_pool().await;
@@ -822,18 +2223,12 @@ mod tests {
         let base_url = server.base_url();
 
         let settings = ForgeProjectSettings {
-            omni_enabled: true,
-            omni_config: Some(OmniConfig {
-                enabled: true,
-                host: Some(base_url.clone()),
-                api_key: None,
-                instance: Some("forge-instance".into()),
-                recipient: Some("+15550001111".into()),
-                recipient_type: Some(RecipientType::PhoneNumber),
-            }),
+            omni_enabled: Some(true),
+            omni_config: Some(test_omni_config(base_url.clone())),
+            ..Default::default()
         };
         config_service
-            .set_global_settings(&settings)
+            .set_global_settings(&settings, SettingsSource::Api)
             .await
             .expect("should persist omni settings");
 
@@ -879,6 +2274,1809 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn notification_rules_skip_filtered_events() {
+        let pool = setup_pool().await;
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, project_id).await;
+        let (task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/forge-instance/send-text");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .json_body(json!({ "success": true, "message_id": "msg-1", "status": "queued" }));
+        });
+
+        let config_service = ForgeConfigService::new(pool.clone());
+        config_service
+            .set_forge_settings(
+                project_id,
+                &ForgeProjectSettings {
+                    omni_enabled: Some(true),
+                    omni_config: Some(test_omni_config(server.base_url())),
+                    notification_rules: vec![
+                        NotificationRule {
+                            name: "coding agent only".into(),
+                            run_reasons: vec!["codingagent".into()],
+                            ..Default::default()
+                        },
+                        NotificationRule {
+                            name: "forge branches".into(),
+                            branch_pattern: Some("feature/*".into()),
+                            ..Default::default()
+                        },
+                    ],
+                    ..Default::default()
+                },
+                SettingsSource::Api,
+            )
+            .await
+            .expect("should persist project settings");
+
+        let mut setup_script: Value =
+            serde_json::from_str(&pending_metadata(attempt_id, project_id)).unwrap();
+        setup_script["run_reason"] = json!("setupscript");
+
+        for (id, metadata) in [
+            ("setup-script", setup_script.to_string()),
+            ("coding-agent", pending_metadata(attempt_id, project_id)),
+        ] {
+            sqlx::query(
+                "INSERT INTO forge_omni_notifications (id, task_id, notification_type, recipient, message, status, metadata)
+                 VALUES (?, ?, 'execution_completed', '', '', 'pending', ?)",
+            )
+            .bind(id)
+            .bind(task_id)
+            .bind(metadata)
+            .execute(&pool)
+            .await
+            .expect("failed to queue notification");
+        }
+
+        while process_next_omni_notification(&pool, &config_service)
+            .await
+            .expect("processing should succeed")
+        {}
+
+        let setup: (String, Option<String>) = sqlx::query_as(
+            "SELECT status, error_message FROM forge_omni_notifications WHERE id = 'setup-script'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(setup.0, "skipped");
+        assert!(setup.1.unwrap_or_default().contains("coding agent only"));
+
+        let status: String = sqlx::query_scalar(
+            "SELECT status FROM forge_omni_notifications WHERE id = 'coding-agent'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(status, "sent");
+        mock.assert_hits_async(1).await;
+    }
+
+    #[tokio::test]
+    async fn digest_mode_groups_notifications() {
+        let pool = setup_pool().await;
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, project_id).await;
+        let (task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/forge-instance/send-text")
+                .body_contains("2 tasks finished");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .json_body(json!({ "success": true, "message_id": "msg-1", "status": "queued" }));
+        });
+        let single = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/forge-instance/send-text")
+                .body_contains("1 task finished");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .json_body(json!({ "success": true, "message_id": "msg-2", "status": "queued" }));
+        });
+
+        let config_service = ForgeConfigService::new(pool.clone());
+        config_service
+            .set_forge_settings(
+                project_id,
+                &ForgeProjectSettings {
+                    omni_enabled: Some(true),
+                    omni_config: Some(OmniConfig {
+                        digest: Some(OmniDigestConfig {
+                            enabled: true,
+                            window_secs: 300,
+                            max_items: 2,
+                        }),
+                        ..test_omni_config(server.base_url())
+                    }),
+                    ..Default::default()
+                },
+                SettingsSource::Api,
+            )
+            .await
+            .expect("should persist project settings");
+
+        for (id, offset) in [("digest-1", -30), ("digest-2", -20), ("digest-3", -10)] {
+            sqlx::query(
+                "INSERT INTO forge_omni_notifications (id, task_id, notification_type, recipient, message, status, metadata, created_at)
+                 VALUES (?, ?, 'execution_completed', '', '', 'pending', ?, datetime('now', ? || ' seconds'))",
+            )
+            .bind(id)
+            .bind(task_id)
+            .bind(pending_metadata(attempt_id, project_id))
+            .bind(offset)
+            .execute(&pool)
+            .await
+            .expect("failed to queue notification");
+        }
+
+        while process_next_omni_notification(&pool, &config_service)
+            .await
+            .expect("processing should succeed")
+        {}
+        let batched: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM forge_omni_notifications WHERE status = 'batched'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(batched, 3);
+        assert_eq!(mock.hits_async().await, 0);
+
+        // max_items reached: the two oldest rows go out together
+        assert!(
+            flush_due_digests(&pool, &config_service)
+                .await
+                .expect("flush should succeed")
+        );
+        let rows: Vec<(String, String, Option<String>)> = sqlx::query_as(
+            "SELECT id, status, digest_id FROM forge_omni_notifications ORDER BY created_at",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(rows[0].1, "sent");
+        assert_eq!(rows[1].1, "sent");
+        assert!(rows[0].2.is_some());
+        assert_eq!(rows[0].2, rows[1].2);
+        assert_eq!(rows[2].1, "batched");
+        mock.assert_async().await;
+
+        // The remaining row waits for its window to close
+        assert!(
+            !flush_due_digests(&pool, &config_service)
+                .await
+                .expect("flush should succeed")
+        );
+        sqlx::query(
+            "UPDATE forge_omni_notifications SET next_attempt_at = datetime('now', '-1 second') WHERE id = 'digest-3'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert!(
+            flush_due_digests(&pool, &config_service)
+                .await
+                .expect("flush should succeed")
+        );
+
+        let (status, digest_id): (String, Option<String>) = sqlx::query_as(
+            "SELECT status, digest_id FROM forge_omni_notifications WHERE id = 'digest-3'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(status, "sent");
+        assert_ne!(digest_id, rows[0].2);
+        single.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn omni_test_message_reports_each_recipient() {
+        let pool = setup_pool().await;
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, project_id).await;
+
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/forge-instance/send-text")
+                .body_contains("Forge test notification");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .json_body(json!({ "success": true, "message_id": "test-1", "status": "queued" }));
+        });
+
+        let config_service = configure_omni(&pool, project_id, server.base_url(), |omni| {
+            // Testing must work before notifications are switched on
+            omni.enabled = false;
+            omni.recipients = vec![
+                OmniRecipient {
+                    recipient: "+15550001111".into(),
+                    ..Default::default()
+                },
+                OmniRecipient {
+                    recipient: "team".into(),
+                    recipient_type: Some(RecipientType::UserId),
+                    instance: Some("missing-instance".into()),
+                    ..Default::default()
+                },
+            ];
+        })
+        .await;
+
+        let config = config_service
+            .effective_omni_config(Some(project_id))
+            .await
+            .unwrap();
+        let deliveries = OmniService::new(config).send_test_message().await.unwrap();
+
+        assert_eq!(deliveries.len(), 2);
+        let response = deliveries[0]
+            .response
+            .as_ref()
+            .expect("first recipient succeeds");
+        assert_eq!(response.message_id.as_deref(), Some("test-1"));
+        assert!(deliveries[0].error.is_none());
+        assert!(deliveries[1].response.is_none());
+        assert!(deliveries[1].error.is_some());
+        mock.assert_hits_async(1).await;
+    }
+
+    fn git(repo: &std::path::Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .arg("-C")
+            .arg(repo)
+            .args([
+                "-c",
+                "user.name=Forge",
+                "-c",
+                "user.email=forge@example.com",
+            ])
+            .args(args)
+            .status()
+            .expect("git should run");
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    #[tokio::test]
+    async fn omni_message_carries_diff_summary_and_patch() {
+        let pool = setup_pool().await;
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, project_id).await;
+        let (task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+
+        // Repository where the attempt branch adds two lines
+        let repo = std::env::temp_dir().join(format!("forge-diff-{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&repo).unwrap();
+        git(&repo, &["init", "-q", "-b", "main"]);
+        std::fs::write(repo.join("README.md"), "forge\n").unwrap();
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "-q", "-m", "init"]);
+        git(&repo, &["checkout", "-q", "-b", "feature/test"]);
+        std::fs::write(repo.join("hello.txt"), "hello\nworld\n").unwrap();
+        git(&repo, &["add", "."]);
+        git(&repo, &["commit", "-q", "-m", "hello"]);
+        sqlx::query("UPDATE projects SET git_repo_path = ? WHERE id = ?")
+            .bind(repo.to_string_lossy().to_string())
+            .bind(project_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let server = MockServer::start_async().await;
+        let text = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/forge-instance/send-text")
+                .body_contains("📊 1 file changed, +2 -0");
+            then.status(200).json_body(json!({
+                "success": true,
+                "message_id": "msg-1",
+                "status": "sent",
+                "error": null
+            }));
+        });
+        let document = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/forge-instance/send-document")
+                .body_contains("\"filename\":\"feature-test.patch\"")
+                .body_contains("\"mime_type\":\"text/x-diff\"");
+            then.status(200).json_body(json!({
+                "success": true,
+                "message_id": "msg-2",
+                "status": "sent",
+                "error": null
+            }));
+        });
+
+        let config_service = configure_omni(&pool, project_id, server.base_url(), |omni| {
+            omni.message = Some(OmniMessageConfig {
+                diff_summary: true,
+                attachment: OmniAttachmentMode::Patch,
+            });
+        })
+        .await;
+        queue_notification(
+            &pool,
+            "execution-diff",
+            task_id,
+            pending_metadata(attempt_id, project_id),
+        )
+        .await;
+
+        assert!(
+            process_next_omni_notification(&pool, &config_service)
+                .await
+                .unwrap()
+        );
+
+        text.assert_async().await;
+        document.assert_async().await;
+        let status: String = sqlx::query_scalar(
+            "SELECT status FROM forge_omni_notifications WHERE id = 'execution-diff'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(status, "sent");
+
+        std::fs::remove_dir_all(repo).ok();
+    }
+
+    pub(super) fn test_omni_config(host: String) -> OmniConfig {
+        OmniConfig {
+            enabled: true,
+            host: Some(host),
+            instance: Some("forge-instance".into()),
+            recipient: Some("+15550001111".into()),
+            recipient_type: Some(RecipientType::PhoneNumber),
+            ..Default::default()
+        }
+    }
+
+    pub(super) async fn configure_omni(
+        pool: &SqlitePool,
+        project_id: Uuid,
+        host: String,
+        customize: impl FnOnce(&mut OmniConfig),
+    ) -> ForgeConfigService {
+        let mut omni_config = test_omni_config(host);
+        customize(&mut omni_config);
+
+        let config_service = ForgeConfigService::new(pool.clone());
+        config_service
+            .set_forge_settings(
+                project_id,
+                &ForgeProjectSettings {
+                    omni_enabled: Some(true),
+                    omni_config: Some(omni_config),
+                    ..Default::default()
+                },
+                SettingsSource::Api,
+            )
+            .await
+            .expect("should persist project settings");
+        config_service
+    }
+
+    pub(super) async fn queue_notification(
+        pool: &SqlitePool,
+        id: &str,
+        task_id: Uuid,
+        metadata: String,
+    ) {
+        sqlx::query(
+            "INSERT INTO forge_omni_notifications (id, task_id, notification_type, recipient, message, status, metadata)
+             VALUES (?, ?, 'execution_completed', '', '', 'pending', ?)",
+        )
+        .bind(id)
+        .bind(task_id)
+        .bind(metadata)
+        .execute(pool)
+        .await
+        .expect("failed to queue notification");
+    }
+
+    fn quiet_hours_around_now(action: QuietHoursAction) -> QuietHoursConfig {
+        let now = Utc::now();
+        QuietHoursConfig {
+            enabled: true,
+            start: (now - chrono::Duration::hours(1))
+                .format("%H:%M")
+                .to_string(),
+            end: (now + chrono::Duration::hours(1))
+                .format("%H:%M")
+                .to_string(),
+            timezone: "UTC".into(),
+            action,
+        }
+    }
+
+    #[tokio::test]
+    async fn quiet_hours_hold_or_skip_notifications() {
+        let pool = setup_pool().await;
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/forge-instance/send-text");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .json_body(json!({ "success": true, "message_id": "msg-1", "status": "queued" }));
+        });
+
+        for (action, id) in [
+            (QuietHoursAction::Hold, "quiet-hold"),
+            (QuietHoursAction::Skip, "quiet-skip"),
+        ] {
+            let project_id = Uuid::new_v4();
+            insert_project(&pool, project_id).await;
+            let (task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+            let config_service = configure_omni(&pool, project_id, server.base_url(), |omni| {
+                omni.quiet_hours = Some(quiet_hours_around_now(action));
+            })
+            .await;
+            queue_notification(&pool, id, task_id, pending_metadata(attempt_id, project_id)).await;
+
+            assert!(
+                process_next_omni_notification(&pool, &config_service)
+                    .await
+                    .expect("processing should succeed")
+            );
+        }
+
+        let held: (String, i64, bool) = sqlx::query_as(
+            "SELECT status, attempt_count, next_attempt_at > datetime('now') FROM forge_omni_notifications WHERE id = 'quiet-hold'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(held, ("pending".to_string(), 0, true));
+
+        let skipped: String = sqlx::query_scalar(
+            "SELECT status FROM forge_omni_notifications WHERE id = 'quiet-skip'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(skipped, "skipped");
+        assert_eq!(mock.hits_async().await, 0);
+    }
+
+    fn next_banner(receiver: &mut tokio::sync::broadcast::Receiver<LogMsg>) -> Value {
+        match receiver.try_recv() {
+            Ok(LogMsg::JsonPatch(patch)) => {
+                let patch = serde_json::to_value(&patch).unwrap();
+                assert_eq!(patch[0]["path"], omni_health::BANNER_EVENT_PATH);
+                patch[0]["value"].clone()
+            }
+            _ => panic!("expected a banner event"),
+        }
+    }
+
+    #[tokio::test]
+    async fn unhealthy_instance_pauses_notifications_until_it_recovers() {
+        let pool = setup_pool().await;
+        let server = FakeOmni::start().await.unwrap();
+        server.add_instance("forge-instance");
+        server.set_instance_health("forge-instance", false, "disconnected");
+
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, project_id).await;
+        let (task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+        let config_service = configure_omni(&pool, project_id, server.base_url(), |_| {}).await;
+        let omni = RwLock::new(OmniService::new(
+            config_service
+                .effective_omni_config(Some(project_id))
+                .await
+                .unwrap(),
+        ));
+        let events = MsgStore::new();
+        let mut receiver = events.get_receiver();
+        let wakeup = Notify::new();
+
+        let check = omni_health::run_health_check(&pool, &omni, &events, &wakeup)
+            .await
+            .unwrap()
+            .expect("an instance is configured");
+        assert!(!check.healthy);
+        assert_eq!(check.status.as_deref(), Some("disconnected"));
+        assert_eq!(next_banner(&mut receiver)["level"], "warning");
+
+        queue_notification(
+            &pool,
+            "paused",
+            task_id,
+            pending_metadata(attempt_id, project_id),
+        )
+        .await;
+        assert!(
+            process_next_omni_notification(&pool, &config_service)
+                .await
+                .unwrap()
+        );
+        let paused: (String, i64, Option<String>) = sqlx::query_as(
+            "SELECT status, attempt_count, error_message FROM forge_omni_notifications WHERE id = 'paused'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            paused,
+            (
+                "pending".to_string(),
+                0,
+                Some(format!(
+                    "omni: {}",
+                    omni_health::paused_reason("forge-instance")
+                ))
+            )
+        );
+        assert!(server.messages().is_empty());
+
+        // Only a change of state is announced
+        omni_health::run_health_check(&pool, &omni, &events, &wakeup)
+            .await
+            .unwrap();
+        assert!(receiver.try_recv().is_err());
+
+        server.set_instance_health("forge-instance", true, "connected");
+        let check = omni_health::run_health_check(&pool, &omni, &events, &wakeup)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(check.healthy);
+        assert_eq!(next_banner(&mut receiver)["level"], "info");
+
+        assert!(
+            process_next_omni_notification(&pool, &config_service)
+                .await
+                .unwrap()
+        );
+        let status: String =
+            sqlx::query_scalar("SELECT status FROM forge_omni_notifications WHERE id = 'paused'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(status, "sent");
+        assert_eq!(server.messages().len(), 1);
+
+        let history = omni_health::health_history(&pool, 10).await.unwrap();
+        assert_eq!(history.len(), 3);
+        assert_eq!(history[0]["healthy"], true);
+        assert_eq!(history[2]["status"], "disconnected");
+    }
+
+    #[tokio::test]
+    async fn health_pause_holds_only_recipients_on_that_instance() {
+        let pool = setup_pool().await;
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, project_id).await;
+        let (task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+
+        let server = MockServer::start_async().await;
+        let forge = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/forge-instance/send-text");
+            then.status(200);
+        });
+        let discord = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/discord-instance/send-text");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .json_body(json!({ "success": true, "message_id": "msg-1", "status": "queued" }));
+        });
+        let config_service = configure_omni(&pool, project_id, server.base_url(), |omni| {
+            omni.recipients = vec![
+                OmniRecipient {
+                    recipient: "+15550001111".into(),
+                    label: Some("whatsapp".into()),
+                    ..Default::default()
+                },
+                OmniRecipient {
+                    recipient: "forge-alerts".into(),
+                    recipient_type: Some(RecipientType::UserId),
+                    instance: Some("discord-instance".into()),
+                    label: Some("discord".into()),
+                    ..Default::default()
+                },
+            ];
+        })
+        .await;
+        omni_health::record_check(
+            &pool,
+            &omni_health::HealthCheck {
+                instance: "forge-instance".into(),
+                healthy: false,
+                status: Some("disconnected".into()),
+                error: None,
+                latency_ms: 5,
+            },
+        )
+        .await
+        .unwrap();
+        queue_notification(
+            &pool,
+            "split",
+            task_id,
+            pending_metadata(attempt_id, project_id),
+        )
+        .await;
+
+        assert!(
+            process_next_omni_notification(&pool, &config_service)
+                .await
+                .unwrap()
+        );
+
+        let (status, error, deliveries): (String, String, String) = sqlx::query_as(
+            "SELECT status, error_message, deliveries FROM forge_omni_notifications WHERE id = 'split'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let deliveries: Value = serde_json::from_str(&deliveries).unwrap();
+        assert_eq!(status, "pending");
+        assert_eq!(
+            error,
+            format!(
+                "omni:whatsapp: {}",
+                omni_health::paused_reason("forge-instance")
+            )
+        );
+        assert_eq!(deliveries["omni:discord"]["status"], "sent");
+        forge.assert_hits_async(0).await;
+        discord.assert_hits_async(1).await;
+    }
+
+    #[tokio::test]
+    async fn rate_limited_notifications_are_requeued() {
+        let pool = setup_pool().await;
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, project_id).await;
+        let (task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/forge-instance/send-text");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .json_body(json!({ "success": true, "message_id": "msg-1", "status": "queued" }));
+        });
+        let config_service = configure_omni(&pool, project_id, server.base_url(), |omni| {
+            omni.rate_limit = Some(OmniRateLimitConfig {
+                max_messages: 1,
+                period_secs: 3600,
+            });
+        })
+        .await;
+
+        queue_notification(
+            &pool,
+            "first",
+            task_id,
+            pending_metadata(attempt_id, project_id),
+        )
+        .await;
+        queue_notification(
+            &pool,
+            "second",
+            task_id,
+            pending_metadata(attempt_id, project_id),
+        )
+        .await;
+
+        while process_next_omni_notification(&pool, &config_service)
+            .await
+            .expect("processing should succeed")
+        {}
+
+        let first: (String, String) = sqlx::query_as(
+            "SELECT status, recipient FROM forge_omni_notifications WHERE id = 'first'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(first, ("sent".to_string(), "+15550001111".to_string()));
+
+        let second: (String, i64, Option<String>, bool) = sqlx::query_as(
+            "SELECT status, attempt_count, error_message, next_attempt_at > datetime('now') FROM forge_omni_notifications WHERE id = 'second'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(second.0, "pending");
+        assert_eq!(second.1, 0);
+        assert!(second.2.unwrap_or_default().contains("Rate limit"));
+        assert!(second.3);
+        mock.assert_hits_async(1).await;
+    }
+
+    #[tokio::test]
+    async fn held_omni_recipients_do_not_delay_other_targets() {
+        let pool = setup_pool().await;
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, project_id).await;
+        let (task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+
+        let server = MockServer::start_async().await;
+        let team = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/forge-instance/send-text")
+                .json_body_partial(r#"{"user_id": "team-group"}"#);
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .json_body(json!({ "success": true, "message_id": "msg-1", "status": "queued" }));
+        });
+        let oncall = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/forge-instance/send-text")
+                .json_body_partial(r#"{"phone_number": "+15550002222"}"#);
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .json_body(json!({ "success": true, "message_id": "msg-1", "status": "queued" }));
+        });
+        let slack = server.mock(|when, then| {
+            when.method(POST).path("/slack/hook");
+            then.status(200).body("ok");
+        });
+
+        let config_service = ForgeConfigService::new(pool.clone());
+        config_service
+            .set_forge_settings(
+                project_id,
+                &ForgeProjectSettings {
+                    omni_enabled: Some(true),
+                    omni_config: Some(OmniConfig {
+                        recipients: vec![
+                            OmniRecipient {
+                                recipient: "team-group".into(),
+                                recipient_type: Some(RecipientType::UserId),
+                                label: Some("team".into()),
+                                ..Default::default()
+                            },
+                            OmniRecipient {
+                                recipient: "+15550002222".into(),
+                                label: Some("oncall".into()),
+                                ..Default::default()
+                            },
+                        ],
+                        rate_limit: Some(OmniRateLimitConfig {
+                            max_messages: 1,
+                            period_secs: 3600,
+                        }),
+                        ..test_omni_config(server.base_url())
+                    }),
+                    notification_channels: vec![
+                        NotificationChannelConfig::Omni,
+                        NotificationChannelConfig::Slack(SlackChannelConfig {
+                            webhook_url: server.url("/slack/hook"),
+                            username: None,
+                            channel: None,
+                        }),
+                    ],
+                    ..Default::default()
+                },
+                SettingsSource::Api,
+            )
+            .await
+            .expect("should persist project settings");
+
+        // The team group already used up its limit
+        sqlx::query(
+            "INSERT INTO forge_omni_notifications (id, notification_type, recipient, message, status, sent_at, deliveries)
+             VALUES ('earlier', 'execution_completed', 'team-group', '', 'sent', datetime('now', '-10 minutes'), ?)",
+        )
+        .bind(
+            json!({
+                "omni:team": {
+                    "status": "sent",
+                    "at": (Utc::now() - chrono::Duration::minutes(10)).to_rfc3339(),
+                    "recipient": "team-group",
+                }
+            })
+            .to_string(),
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        queue_notification(
+            &pool,
+            "held",
+            task_id,
+            pending_metadata(attempt_id, project_id),
+        )
+        .await;
+
+        assert!(
+            process_next_omni_notification(&pool, &config_service)
+                .await
+                .expect("processing should succeed")
+        );
+
+        let (status, attempts, error, deliveries): (String, i64, String, String) = sqlx::query_as(
+            "SELECT status, attempt_count, error_message, deliveries FROM forge_omni_notifications WHERE id = 'held'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let deliveries: Value = serde_json::from_str(&deliveries).unwrap();
+        assert_eq!((status.as_str(), attempts), ("pending", 0));
+        assert_eq!(error, "omni:team: Rate limit reached for team-group");
+        assert_eq!(deliveries["slack"]["status"], "sent");
+        assert_eq!(deliveries["omni:oncall"]["status"], "sent");
+        assert_eq!(deliveries["omni:oncall"]["recipient"], "+15550002222");
+        assert!(deliveries.get("omni:team").is_none());
+        team.assert_hits_async(0).await;
+        oncall.assert_hits_async(1).await;
+        slack.assert_hits_async(1).await;
+    }
+
+    #[tokio::test]
+    async fn quiet_hours_hold_only_omni_in_digests() {
+        let pool = setup_pool().await;
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, project_id).await;
+        let (task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+
+        let server = MockServer::start_async().await;
+        let omni = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/forge-instance/send-text");
+            then.status(200);
+        });
+        let slack = server.mock(|when, then| {
+            when.method(POST)
+                .path("/slack/hook")
+                .body_contains("2 tasks finished");
+            then.status(200).body("ok");
+        });
+
+        let config_service = ForgeConfigService::new(pool.clone());
+        config_service
+            .set_forge_settings(
+                project_id,
+                &ForgeProjectSettings {
+                    omni_enabled: Some(true),
+                    omni_config: Some(OmniConfig {
+                        digest: Some(OmniDigestConfig {
+                            enabled: true,
+                            window_secs: 300,
+                            max_items: 2,
+                        }),
+                        quiet_hours: Some(quiet_hours_around_now(QuietHoursAction::Hold)),
+                        ..test_omni_config(server.base_url())
+                    }),
+                    notification_channels: vec![
+                        NotificationChannelConfig::Omni,
+                        NotificationChannelConfig::Slack(SlackChannelConfig {
+                            webhook_url: server.url("/slack/hook"),
+                            username: None,
+                            channel: None,
+                        }),
+                    ],
+                    ..Default::default()
+                },
+                SettingsSource::Api,
+            )
+            .await
+            .expect("should persist project settings");
+
+        for id in ["digest-1", "digest-2"] {
+            queue_notification(&pool, id, task_id, pending_metadata(attempt_id, project_id)).await;
+        }
+        while process_next_omni_notification(&pool, &config_service)
+            .await
+            .expect("processing should succeed")
+        {}
+        flush_due_digests(&pool, &config_service)
+            .await
+            .expect("flush should succeed");
+
+        let rows: Vec<(String, bool, String)> = sqlx::query_as(
+            "SELECT status, next_attempt_at > datetime('now', '+10 minutes'), deliveries FROM forge_omni_notifications",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(rows.len(), 2);
+        for (status, held_past_window, deliveries) in rows {
+            let deliveries: Value = serde_json::from_str(&deliveries).unwrap();
+            assert_eq!(status, "batched");
+            assert!(held_past_window);
+            assert_eq!(deliveries["slack"]["status"], "sent");
+            assert!(deliveries.get("omni").is_none());
+        }
+        omni.assert_hits_async(0).await;
+        slack.assert_hits_async(1).await;
+    }
+
+    #[tokio::test]
+    async fn digests_use_the_task_settings() {
+        let pool = setup_pool().await;
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, project_id).await;
+        let (task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+
+        let server = MockServer::start_async().await;
+        let omni = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/forge-instance/send-text");
+            then.status(200);
+        });
+        let slack = server.mock(|when, then| {
+            when.method(POST)
+                .path("/slack/hook")
+                .body_contains("2 tasks finished");
+            then.status(200).body("ok");
+        });
+
+        let config_service = configure_omni(&pool, project_id, server.base_url(), |omni| {
+            omni.digest = Some(OmniDigestConfig {
+                enabled: true,
+                window_secs: 300,
+                max_items: 2,
+            });
+        })
+        .await;
+        // The task sends its updates to Slack instead of Omni
+        config_service
+            .set_task_settings(
+                task_id,
+                &ForgeProjectSettings {
+                    notification_channels: vec![NotificationChannelConfig::Slack(
+                        SlackChannelConfig {
+                            webhook_url: server.url("/slack/hook"),
+                            username: None,
+                            channel: None,
+                        },
+                    )],
+                    ..Default::default()
+                },
+                SettingsSource::Api,
+            )
+            .await
+            .expect("should persist task settings");
+
+        for id in ["digest-1", "digest-2"] {
+            queue_notification(&pool, id, task_id, pending_metadata(attempt_id, project_id)).await;
+        }
+        while process_next_omni_notification(&pool, &config_service)
+            .await
+            .expect("processing should succeed")
+        {}
+        flush_due_digests(&pool, &config_service)
+            .await
+            .expect("flush should succeed");
+
+        let rows: Vec<(String, String)> =
+            sqlx::query_as("SELECT status, digest_key FROM forge_omni_notifications")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(rows.len(), 2);
+        for (status, key) in rows {
+            assert_eq!(status, "sent");
+            assert_eq!(key, format!("{project_id}/{task_id}"));
+        }
+        omni.assert_hits_async(0).await;
+        slack.assert_hits_async(1).await;
+    }
+
+    #[tokio::test]
+    async fn omni_recipients_are_delivered_and_recorded_separately() {
+        let pool = setup_pool().await;
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, project_id).await;
+        let (task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+
+        let server = MockServer::start_async().await;
+        let group = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/forge-instance/send-text")
+                .json_body_partial(r#"{"user_id": "team-group"}"#);
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .json_body(json!({ "success": true, "message_id": "msg-1", "status": "queued" }));
+        });
+        let discord_down = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/discord-instance/send-text");
+            then.status(500).body("discord down");
+        });
+        let oncall = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/forge-instance/send-text")
+                .json_body_partial(r#"{"phone_number": "+15550002222"}"#);
+            then.status(200);
+        });
+
+        let config_service = configure_omni(&pool, project_id, server.base_url(), |omni| {
+            omni.recipients = vec![
+                OmniRecipient {
+                    recipient: "team-group".into(),
+                    recipient_type: Some(RecipientType::UserId),
+                    label: Some("whatsapp".into()),
+                    ..Default::default()
+                },
+                OmniRecipient {
+                    recipient: "forge-alerts".into(),
+                    recipient_type: Some(RecipientType::UserId),
+                    instance: Some("discord-instance".into()),
+                    label: Some("discord".into()),
+                    ..Default::default()
+                },
+                OmniRecipient {
+                    recipient: "+15550002222".into(),
+                    statuses: vec!["failed".into()],
+                    ..Default::default()
+                },
+            ];
+        })
+        .await;
+        queue_notification(
+            &pool,
+            "fan-out",
+            task_id,
+            pending_metadata(attempt_id, project_id),
+        )
+        .await;
+
+        assert!(
+            process_next_omni_notification(&pool, &config_service)
+                .await
+                .expect("processing should succeed")
+        );
+
+        let (status, deliveries): (String, String) = sqlx::query_as(
+            "SELECT status, deliveries FROM forge_omni_notifications WHERE id = 'fan-out'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let deliveries: Value = serde_json::from_str(&deliveries).unwrap();
+        assert_eq!(status, "pending");
+        assert_eq!(deliveries["omni:whatsapp"]["status"], "sent");
+        assert_eq!(deliveries["omni:discord"]["status"], "failed");
+        assert!(deliveries.get("omni:+15550002222").is_none());
+
+        discord_down.delete_async().await;
+        let discord = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/discord-instance/send-text");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .json_body(json!({ "success": true, "message_id": "msg-2", "status": "queued" }));
+        });
+        sqlx::query(
+            "UPDATE forge_omni_notifications SET next_attempt_at = NULL WHERE id = 'fan-out'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        assert!(
+            process_next_omni_notification(&pool, &config_service)
+                .await
+                .expect("retry should succeed")
+        );
+
+        let (status, recipients): (String, String) = sqlx::query_as(
+            "SELECT status, recipient FROM forge_omni_notifications WHERE id = 'fan-out'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(status, "sent");
+        assert_eq!(recipients, "team-group,forge-alerts");
+        group.assert_hits_async(1).await;
+        discord.assert_hits_async(1).await;
+        oncall.assert_hits_async(0).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn failed_send_is_retried_until_max_attempts() {
+        let pool = setup_pool().await;
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, ForgeConfigService::GLOBAL_PROJECT_ID).await;
+        insert_project(&pool, project_id).await;
+        let (task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+
+        let config_service = ForgeConfigService::new(pool.clone());
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/forge-instance/send-text");
+            then.status(503).body("omni unavailable");
+        });
+
+        let settings = ForgeProjectSettings {
+            omni_enabled: Some(true),
+            omni_config: Some(test_omni_config(server.base_url())),
+            ..Default::default()
+        };
+        config_service
+            .set_global_settings(&settings, SettingsSource::Api)
+            .await
+            .expect("should persist omni settings");
+
+        sqlx::query(
+            "INSERT INTO forge_omni_notifications (id, task_id, notification_type, recipient, message, status, metadata, max_attempts)
+             VALUES ('execution-retry', ?, 'execution_completed', '', '', 'pending', ?, 2)",
+        )
+        .bind(task_id)
+        .bind(pending_metadata(attempt_id, project_id))
+        .execute(&pool)
+        .await
+        .expect("failed to queue notification");
+
+        assert!(
+            process_next_omni_notification(&pool, &config_service)
+                .await
+                .expect("processing should succeed")
+        );
+
+        let (status, attempts, next_attempt_at): (String, i64, Option<String>) = sqlx::query_as(
+            "SELECT status, attempt_count, next_attempt_at FROM forge_omni_notifications WHERE id = 'execution-retry'",
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("queue row remains accessible");
+        assert_eq!(status, "pending");
+        assert_eq!(attempts, 1);
+        assert!(next_attempt_at.is_some());
+
+        // Scheduled in the future, so the worker must not pick it up yet
+        assert!(
+            !process_next_omni_notification(&pool, &config_service)
+                .await
+                .expect("processing should succeed")
+        );
+
+        sqlx::query(
+            "UPDATE forge_omni_notifications SET next_attempt_at = datetime('now', '-1 seconds') WHERE id = 'execution-retry'",
+        )
+        .execute(&pool)
+        .await
+        .expect("should fast-forward retry schedule");
+
+        assert!(
+            process_next_omni_notification(&pool, &config_service)
+                .await
+                .expect("processing should succeed")
+        );
+
+        let (status, attempts, errors): (String, i64, String) = sqlx::query_as(
+            "SELECT status, attempt_count, attempt_errors FROM forge_omni_notifications WHERE id = 'execution-retry'",
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("queue row remains accessible");
+        assert_eq!(status, "failed");
+        assert_eq!(attempts, 2);
+
+        let errors: Vec<serde_json::Value> =
+            serde_json::from_str(&errors).expect("attempt errors should be a JSON array");
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0]["attempt"], 1);
+        assert!(errors[1]["error"].as_str().unwrap().contains("503"));
+
+        mock.assert_hits_async(2).await;
+    }
+
+    #[tokio::test]
+    async fn task_and_pull_request_events_are_sent_when_enabled() {
+        let pool = setup_pool().await;
+        notification_hook::install_notification_trigger(&pool)
+            .await
+            .expect("triggers should install");
+
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, project_id).await;
+        let (task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+
+        let server = FakeOmni::start().await.unwrap();
+        server.add_instance("forge-instance");
+        let config_service = configure_omni(&pool, project_id, server.base_url(), |_| {}).await;
+        let mut settings = config_service.get_forge_settings(project_id).await.unwrap();
+        settings.notification_types = vec![
+            NotificationType::TaskStatusChanged,
+            NotificationType::PrMerged,
+            NotificationType::MergeConflict,
+        ];
+        settings.notification_templates = [(
+            NotificationType::PrMerged,
+            "Merged PR #{{ pr_number }} on {{ branch }}".to_string(),
+        )]
+        .into();
+        config_service
+            .set_forge_settings(project_id, &settings, SettingsSource::Api)
+            .await
+            .unwrap();
+
+        sqlx::query("UPDATE tasks SET status = 'inreview' WHERE id = ?")
+            .bind(task_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        // Saving a task without changing its status queues nothing
+        sqlx::query("UPDATE tasks SET status = 'inreview' WHERE id = ?")
+            .bind(task_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO merges (id, task_attempt_id, merge_type, pr_number, pr_url, pr_status)
+             VALUES ('merge-1', ?, 'pr', 42, 'https://github.com/forge/app/pull/42', 'open')",
+        )
+        .bind(attempt_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("UPDATE merges SET pr_status = 'merged' WHERE id = 'merge-1'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        notification_hook::queue_conflict_notification(
+            &pool,
+            attempt_id,
+            "rebase",
+            Some("Rebase stopped on conflicts"),
+        )
+        .await
+        .unwrap();
+
+        while process_next_omni_notification(&pool, &config_service)
+            .await
+            .unwrap()
+        {}
+
+        let rows: Vec<(String, String, Option<String>)> = sqlx::query_as(
+            "SELECT notification_type, status, error_message FROM forge_omni_notifications ORDER BY created_at, rowid",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        let outcome: Vec<(&str, &str)> = rows
+            .iter()
+            .map(|(kind, status, _)| (kind.as_str(), status.as_str()))
+            .collect();
+        assert_eq!(
+            outcome,
+            vec![
+                ("task_status_changed", "sent"),
+                ("pr_created", "skipped"),
+                ("pr_merged", "sent"),
+                ("merge_conflict", "sent"),
+            ]
+        );
+        assert!(rows[1].2.as_deref().unwrap().contains("not enabled"));
+
+        let texts: Vec<String> = server
+            .messages()
+            .into_iter()
+            .filter_map(|message| message.text)
+            .collect();
+        assert_eq!(texts.len(), 3);
+        assert!(texts[0].contains("Ready for review (was todo)"));
+        assert_eq!(texts[1], "Merged PR #42 on feature/test");
+        assert!(texts[2].contains("Conflicts during rebase"));
+    }
+
+    #[tokio::test]
+    async fn execution_exits_are_queued_once_per_process() {
+        let pool = setup_pool().await;
+        notification_hook::install_notification_trigger(&pool)
+            .await
+            .expect("trigger should install");
+
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, project_id).await;
+        let (_task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+
+        let process_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO execution_processes (id, task_attempt_id, status, run_reason)
+             VALUES (?, ?, 'running', 'codingagent')",
+        )
+        .bind(process_id)
+        .bind(attempt_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // A process that is restarted and exits again keeps its first notification
+        for status in ["completed", "running", "failed"] {
+            sqlx::query("UPDATE execution_processes SET status = ? WHERE id = ?")
+                .bind(status)
+                .bind(process_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let rows: Vec<(Option<String>, String)> = sqlx::query_as(
+            "SELECT execution_process_id, json_extract(metadata, '$.status') FROM forge_omni_notifications",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            rows,
+            vec![(
+                Some(process_id.simple().to_string()),
+                "completed".to_string()
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn execution_id_backfill_matches_legacy_rows() {
+        let pool = setup_pool().await;
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, project_id).await;
+        let (task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+
+        let setup_id = Uuid::new_v4();
+        let agent_id = Uuid::new_v4();
+        for (id, run_reason, completed_at) in [
+            (setup_id, "setupscript", "-2 hours"),
+            (agent_id, "codingagent", "-1 hours"),
+        ] {
+            sqlx::query(
+                "INSERT INTO execution_processes (id, task_attempt_id, status, run_reason, completed_at)
+                 VALUES (?, ?, 'completed', ?, datetime('now', ?))",
+            )
+            .bind(id)
+            .bind(attempt_id)
+            .bind(run_reason)
+            .bind(completed_at)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        // Rows as the old trigger queued them, the last one a duplicate
+        for (id, run_reason) in [
+            ("legacy-setup", "setupscript"),
+            ("legacy-agent", "codingagent"),
+            ("legacy-agent-again", "codingagent"),
+        ] {
+            let metadata = json!({
+                "task_attempt_id": attempt_id.simple().to_string(),
+                "status": "completed",
+                "run_reason": run_reason,
+            });
+            sqlx::query(
+                "INSERT INTO forge_omni_notifications (id, task_id, notification_type, recipient, message, status, metadata)
+                 VALUES (?, ?, 'execution_completed', '', '', 'sent', ?)",
+            )
+            .bind(id)
+            .bind(task_id)
+            .bind(metadata.to_string())
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        sqlx::query("DROP INDEX idx_forge_omni_notifications_execution_process")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let migration = FORGE_MIGRATIONS
+            .iter()
+            .find(|migration| migration.version == "20261018000007")
+            .unwrap();
+        for statement in split_statements(migration.sql) {
+            if !statement.starts_with("ALTER TABLE") {
+                sqlx::query(&statement).execute(&pool).await.unwrap();
+            }
+        }
+
+        let rows: Vec<(String, Option<String>)> = sqlx::query_as(
+            "SELECT id, execution_process_id FROM forge_omni_notifications ORDER BY rowid",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    "legacy-setup".to_string(),
+                    Some(setup_id.simple().to_string())
+                ),
+                (
+                    "legacy-agent".to_string(),
+                    Some(agent_id.simple().to_string())
+                ),
+                ("legacy-agent-again".to_string(), None),
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn worker_delivers_right_after_completion_event() {
+        let pool = setup_pool().await;
+        notification_hook::install_notification_trigger(&pool)
+            .await
+            .expect("trigger should install");
+
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, ForgeConfigService::GLOBAL_PROJECT_ID).await;
+        insert_project(&pool, project_id).await;
+        let (_task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+
+        let config_service = Arc::new(ForgeConfigService::new(pool.clone()));
+        let server = MockServer::start_async().await;
+        server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/forge-instance/send-text");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .json_body(json!({
+                    "success": true,
+                    "message_id": "msg-wakeup",
+                    "status": "queued",
+                    "error": null
+                }));
+        });
+
+        let settings = ForgeProjectSettings {
+            omni_enabled: Some(true),
+            omni_config: Some(test_omni_config(server.base_url())),
+            ..Default::default()
+        };
+        config_service
+            .set_global_settings(&settings, SettingsSource::Api)
+            .await
+            .expect("should persist omni settings");
+
+        // The upstream event stream, as ForgeServices::new subscribes to it
+        let msg_store = utils::msg_store::MsgStore::new();
+        let wakeup = Arc::new(Notify::new());
+        notification_hook::spawn_event_listener(msg_store.get_receiver(), wakeup.clone());
+        spawn_omni_notification_worker(
+            pool.clone(),
+            config_service,
+            wakeup.clone(),
+            Arc::new(NotificationMetrics::default()),
+        );
+
+        // Let the worker drain the empty queue and go idle
+        sleep(Duration::from_millis(50)).await;
+
+        let process_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO execution_processes (id, task_attempt_id, status, run_reason)
+             VALUES (?, ?, 'running', 'codingagent')",
+        )
+        .bind(process_id)
+        .bind(attempt_id)
+        .execute(&pool)
+        .await
+        .expect("failed to insert execution process");
+
+        let started = std::time::Instant::now();
+        sqlx::query("UPDATE execution_processes SET status = 'completed' WHERE id = ?")
+            .bind(process_id)
+            .execute(&pool)
+            .await
+            .expect("failed to complete execution process");
+        // Patch the upstream event service publishes for the finished process
+        let patch: json_patch::Patch = serde_json::from_value(json!([{
+            "op": "replace",
+            "path": format!("/execution_processes/{process_id}"),
+            "value": { "id": process_id, "status": "completed" }
+        }]))
+        .expect("patch should parse");
+        msg_store.push_patch(patch);
+
+        let mut status = String::new();
+        while started.elapsed() < Duration::from_secs(5) {
+            status = sqlx::query_scalar("SELECT status FROM forge_omni_notifications LIMIT 1")
+                .fetch_optional(&pool)
+                .await
+                .expect("queue should be readable")
+                .unwrap_or_default();
+            if status == "sent" {
+                break;
+            }
+            sleep(Duration::from_millis(5)).await;
+        }
+
+        assert_eq!(status, "sent");
+        assert!(
+            started.elapsed() < Duration::from_millis(500),
+            "delivery took {:?}, expected well below the safety poll",
+            started.elapsed()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn fan_out_retries_only_failed_channels() {
+        let pool = setup_pool().await;
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, ForgeConfigService::GLOBAL_PROJECT_ID).await;
+        insert_project(&pool, project_id).await;
+        let (task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+
+        let config_service = ForgeConfigService::new(pool.clone());
+        let server = MockServer::start_async().await;
+        let omni_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/forge-instance/send-text");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .json_body(json!({
+                    "success": true,
+                    "message_id": "msg-fanout",
+                    "status": "queued",
+                    "error": null
+                }));
+        });
+        let slack_down = server.mock(|when, then| {
+            when.method(POST).path("/slack/hook");
+            then.status(500).body("slack down");
+        });
+
+        let settings = ForgeProjectSettings {
+            omni_enabled: Some(true),
+            omni_config: Some(test_omni_config(server.base_url())),
+            notification_channels: vec![
+                NotificationChannelConfig::Omni,
+                NotificationChannelConfig::Slack(SlackChannelConfig {
+                    webhook_url: server.url("/slack/hook"),
+                    username: None,
+                    channel: None,
+                }),
+            ],
+            ..Default::default()
+        };
+        config_service
+            .set_global_settings(&settings, SettingsSource::Api)
+            .await
+            .expect("should persist notification settings");
+
+        sqlx::query(
+            "INSERT INTO forge_omni_notifications (id, task_id, notification_type, recipient, message, status, metadata)
+             VALUES ('execution-fanout', ?, 'execution_completed', '', '', 'pending', ?)",
+        )
+        .bind(task_id)
+        .bind(pending_metadata(attempt_id, project_id))
+        .execute(&pool)
+        .await
+        .expect("failed to queue notification");
+
+        process_next_omni_notification(&pool, &config_service)
+            .await
+            .expect("processing should succeed");
+
+        let (status, deliveries): (String, String) = sqlx::query_as(
+            "SELECT status, deliveries FROM forge_omni_notifications WHERE id = 'execution-fanout'",
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("queue row remains accessible");
+        let deliveries: Value = serde_json::from_str(&deliveries).unwrap();
+        assert_eq!(status, "pending");
+        assert_eq!(deliveries["omni"]["status"], "sent");
+        assert_eq!(deliveries["slack"]["status"], "failed");
+
+        slack_down.delete_async().await;
+        let slack_up = server.mock(|when, then| {
+            when.method(POST).path("/slack/hook");
+            then.status(200).body("ok");
+        });
+        sqlx::query(
+            "UPDATE forge_omni_notifications SET next_attempt_at = datetime('now', '-1 seconds') WHERE id = 'execution-fanout'",
+        )
+        .execute(&pool)
+        .await
+        .expect("should fast-forward retry schedule");
+
+        process_next_omni_notification(&pool, &config_service)
+            .await
+            .expect("processing should succeed");
+
+        let status: String = sqlx::query_scalar(
+            "SELECT status FROM forge_omni_notifications WHERE id = 'execution-fanout'",
+        )
+        .fetch_one(&pool)
+        .await
+        .expect("queue row remains accessible");
+        assert_eq!(status, "sent");
+
+        // Omni already succeeded on the first attempt and is not resent
+        omni_mock.assert_hits_async(1).await;
+        slack_up.assert_async().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn digest_retries_only_failed_channels() {
+        let pool = setup_pool().await;
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, project_id).await;
+        let (task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+
+        let config_service = ForgeConfigService::new(pool.clone());
+        let server = MockServer::start_async().await;
+        let omni_mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/forge-instance/send-text");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .json_body(
+                    json!({ "success": true, "message_id": "msg-digest", "status": "queued" }),
+                );
+        });
+        let slack_down = server.mock(|when, then| {
+            when.method(POST).path("/slack/hook");
+            then.status(500).body("slack down");
+        });
+
+        config_service
+            .set_forge_settings(
+                project_id,
+                &ForgeProjectSettings {
+                    omni_enabled: Some(true),
+                    omni_config: Some(OmniConfig {
+                        digest: Some(OmniDigestConfig {
+                            enabled: true,
+                            window_secs: 300,
+                            max_items: 2,
+                        }),
+                        ..test_omni_config(server.base_url())
+                    }),
+                    notification_channels: vec![
+                        NotificationChannelConfig::Omni,
+                        NotificationChannelConfig::Slack(SlackChannelConfig {
+                            webhook_url: server.url("/slack/hook"),
+                            username: None,
+                            channel: None,
+                        }),
+                    ],
+                    ..Default::default()
+                },
+                SettingsSource::Api,
+            )
+            .await
+            .expect("should persist project settings");
+
+        for (id, offset) in [("digest-1", -20), ("digest-2", -10)] {
+            sqlx::query(
+                "INSERT INTO forge_omni_notifications (id, task_id, notification_type, recipient, message, status, metadata, created_at)
+                 VALUES (?, ?, 'execution_completed', '', '', 'pending', ?, datetime('now', ? || ' seconds'))",
+            )
+            .bind(id)
+            .bind(task_id)
+            .bind(pending_metadata(attempt_id, project_id))
+            .bind(offset)
+            .execute(&pool)
+            .await
+            .expect("failed to queue notification");
+        }
+        while process_next_omni_notification(&pool, &config_service)
+            .await
+            .expect("processing should succeed")
+        {}
+        flush_due_digests(&pool, &config_service)
+            .await
+            .expect("flush should succeed");
+
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            "SELECT status, deliveries FROM forge_omni_notifications ORDER BY created_at",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        for (status, deliveries) in &rows {
+            let deliveries: Value = serde_json::from_str(deliveries).unwrap();
+            assert_eq!(status, "pending");
+            assert_eq!(deliveries["omni"]["status"], "sent");
+            assert_eq!(deliveries["slack"]["status"], "failed");
+        }
+
+        slack_down.delete_async().await;
+        let slack_up = server.mock(|when, then| {
+            when.method(POST).path("/slack/hook");
+            then.status(200).body("ok");
+        });
+        sqlx::query(
+            "UPDATE forge_omni_notifications SET next_attempt_at = datetime('now', '-1 seconds')",
+        )
+        .execute(&pool)
+        .await
+        .expect("should fast-forward retry schedule");
+        while process_next_omni_notification(&pool, &config_service)
+            .await
+            .expect("processing should succeed")
+        {}
+        flush_due_digests(&pool, &config_service)
+            .await
+            .expect("flush should succeed");
+
+        let statuses: Vec<String> =
+            sqlx::query_scalar("SELECT status FROM forge_omni_notifications")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(statuses, ["sent", "sent"]);
+        // Omni already got the digest and is not sent it again
+        omni_mock.assert_hits_async(1).await;
+        slack_up.assert_async().await;
+    }
+
+    #[tokio::test]
+    async fn expired_leases_return_to_pending() {
+        let pool = setup_pool().await;
+
+        for (id, claimed_at) in [
+            ("stale", Some("-10 minutes")),
+            ("fresh", Some("-10 seconds")),
+            ("legacy", None),
+        ] {
+            sqlx::query(
+                "INSERT INTO forge_omni_notifications (id, notification_type, recipient, message, status, claimed_at)
+                 VALUES (?, 'execution_completed', '', '', 'processing', datetime('now', ?))",
+            )
+            .bind(id)
+            .bind(claimed_at)
+            .execute(&pool)
+            .await
+            .expect("failed to insert claimed notification");
+        }
+
+        let reclaimed = reclaim_expired_leases(&pool, OMNI_LEASE_TIMEOUT)
+            .await
+            .expect("lease sweep should succeed");
+        assert_eq!(reclaimed, 2);
+
+        let rows: Vec<(String, String, Option<String>)> = sqlx::query_as(
+            "SELECT id, status, claimed_at FROM forge_omni_notifications ORDER BY id",
+        )
+        .fetch_all(&pool)
+        .await
+        .expect("queue rows remain accessible");
+
+        assert_eq!(rows[0].0, "fresh");
+        assert_eq!(rows[0].1, "processing");
+        assert!(rows[0].2.is_some());
+        assert_eq!(rows[1].0, "legacy");
+        assert_eq!(rows[1].1, "pending");
+        assert_eq!(rows[2].0, "stale");
+        assert_eq!(rows[2].1, "pending");
+        assert!(rows[2].2.is_none());
+
+        let metrics = NotificationMetrics::default();
+        metrics.record_leases_reclaimed(reclaimed);
+        assert_eq!(metrics.leases_reclaimed(), 2);
+    }
+
+    #[test]
+    fn retry_delay_grows_exponentially_with_bounded_jitter() {
+        assert_eq!(retry_delay(1, 0.0), Duration::from_secs(15));
+        assert_eq!(retry_delay(1, 1.0), Duration::from_secs(30));
+        assert_eq!(retry_delay(3, 1.0), Duration::from_secs(120));
+
+        let jittered = retry_delay(2, 0.5);
+        assert!(jittered >= Duration::from_secs(30) && jittered <= Duration::from_secs(60));
+
+        // Large attempt numbers stay capped
+        assert_eq!(retry_delay(40, 1.0), RETRY_MAX_DELAY);
+    }
+
     #[test]
     fn status_summary_includes_branch_and_executor() {
         let summary = format_status_summary("completed", "forge-agent", "feature/auth");
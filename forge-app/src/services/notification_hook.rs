@@ -2,9 +2,127 @@
 //!
 //! Uses SQLite triggers to detect when tasks complete and queue Omni notifications.
 //! This avoids polling and hooks directly into the execution completion event.
+//! Further triggers queue task status changes and pull request events; merge
+//! conflicts are reported by the merge and rebase routes, which have no row to
+//! hook into. Every event is queued and the worker skips the ones a project has
+//! not enabled.
+//! The upstream event stream is used to wake the queue worker right after the
+//! trigger fires; the durable queue stays the source of truth.
+
+use std::sync::Arc;
 
 use anyhow::Result;
+use deployment::Deployment;
+use serde_json::Value;
+use server::DeploymentImpl;
 use sqlx::SqlitePool;
+use tokio::sync::{
+    Notify,
+    broadcast::{Receiver, error::RecvError},
+};
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+/// Triggers for events other than execution exits, by name.
+const EVENT_TRIGGERS: &[(&str, &str)] = &[
+    (
+        "omni_task_status_changed",
+        r#"
+        CREATE TRIGGER IF NOT EXISTS omni_task_status_changed
+        AFTER UPDATE OF status ON tasks
+        WHEN NEW.status IN ('inreview', 'done')
+          AND OLD.status IS NOT NEW.status
+        BEGIN
+            INSERT INTO forge_omni_notifications (
+                id, task_id, notification_type, recipient, message, status, metadata, created_at
+            )
+            VALUES (
+                lower(hex(randomblob(16))),
+                NULL,
+                'task_status_changed',
+                '',
+                '',
+                'pending',
+                json_object(
+                    'task_id', lower(hex(NEW.id)),
+                    'project_id', lower(hex(NEW.project_id)),
+                    'status', NEW.status,
+                    'previous_status', OLD.status
+                ),
+                datetime('now')
+            );
+        END;
+        "#,
+    ),
+    (
+        "omni_pr_created",
+        r#"
+        CREATE TRIGGER IF NOT EXISTS omni_pr_created
+        AFTER INSERT ON merges
+        WHEN NEW.merge_type = 'pr'
+        BEGIN
+            INSERT INTO forge_omni_notifications (
+                id, task_id, notification_type, recipient, message, status, metadata, created_at
+            )
+            SELECT
+                lower(hex(randomblob(16))),
+                NULL,
+                'pr_created',
+                '',
+                '',
+                'pending',
+                json_object(
+                    'task_attempt_id', lower(hex(NEW.task_attempt_id)),
+                    'project_id', lower(hex(t.project_id)),
+                    'status', 'open',
+                    'branch', COALESCE(ta.branch, ''),
+                    'executor', COALESCE(ta.executor, ''),
+                    'pr_number', NEW.pr_number,
+                    'pr_url', NEW.pr_url
+                ),
+                datetime('now')
+            FROM task_attempts ta
+            JOIN tasks t ON t.id = ta.task_id
+            WHERE ta.id = NEW.task_attempt_id;
+        END;
+        "#,
+    ),
+    (
+        "omni_pr_merged",
+        r#"
+        CREATE TRIGGER IF NOT EXISTS omni_pr_merged
+        AFTER UPDATE OF pr_status ON merges
+        WHEN NEW.merge_type = 'pr'
+          AND NEW.pr_status = 'merged'
+          AND OLD.pr_status IS NOT 'merged'
+        BEGIN
+            INSERT INTO forge_omni_notifications (
+                id, task_id, notification_type, recipient, message, status, metadata, created_at
+            )
+            SELECT
+                lower(hex(randomblob(16))),
+                NULL,
+                'pr_merged',
+                '',
+                '',
+                'pending',
+                json_object(
+                    'task_attempt_id', lower(hex(NEW.task_attempt_id)),
+                    'project_id', lower(hex(t.project_id)),
+                    'status', 'merged',
+                    'branch', COALESCE(ta.branch, ''),
+                    'executor', COALESCE(ta.executor, ''),
+                    'pr_number', NEW.pr_number,
+                    'pr_url', NEW.pr_url
+                ),
+                datetime('now')
+            FROM task_attempts ta
+            JOIN tasks t ON t.id = ta.task_id
+            WHERE ta.id = NEW.task_attempt_id;
+        END;
+        "#,
+    ),
+];
 
 /// Install a SQLite trigger that fires when execution_processes complete
 /// This trigger will insert a record into forge_omni_notifications
@@ -22,9 +140,10 @@ pub async fn install_notification_trigger(pool: &SqlitePool) -> Result<()> {
         WHEN NEW.status IN ('completed', 'failed', 'killed')
           AND OLD.status NOT IN ('completed', 'failed', 'killed')
         BEGIN
-            INSERT INTO forge_omni_notifications (
+            INSERT OR IGNORE INTO forge_omni_notifications (
                 id,
                 task_id,
+                execution_process_id,
                 notification_type,
                 recipient,
                 message,
@@ -35,6 +154,7 @@ pub async fn install_notification_trigger(pool: &SqlitePool) -> Result<()> {
             SELECT
                 lower(hex(randomblob(16))),
                 NULL,
+                lower(hex(NEW.id)),
                 'execution_completed',
                 '',
                 '',
@@ -45,23 +165,200 @@ pub async fn install_notification_trigger(pool: &SqlitePool) -> Result<()> {
                     'executor', COALESCE(ta.executor, ''),
                     'branch', COALESCE(ta.branch, ''),
                     'project_id', lower(hex(t.project_id)),
-                    'exit_code', COALESCE(NEW.exit_code, 0)
+                    'run_reason', NEW.run_reason,
+                    'exit_code', COALESCE(NEW.exit_code, 0),
+                    'duration_secs', CAST(
+                        (julianday(COALESCE(NEW.completed_at, datetime('now')))
+                            - julianday(NEW.started_at)) * 86400 AS INTEGER
+                    )
                 ),
                 datetime('now')
             FROM task_attempts ta
             JOIN tasks t ON t.id = ta.task_id
-            WHERE ta.id = NEW.task_attempt_id
-              AND NOT EXISTS (
-                  -- Prevent duplicate notifications for the same execution
-                  SELECT 1 FROM forge_omni_notifications
-                  WHERE metadata LIKE '%' || NEW.id || '%'
-              );
+            -- The unique index on execution_process_id drops a second row for
+            -- the same execution
+            WHERE ta.id = NEW.task_attempt_id;
         END;
         "#,
     )
     .execute(pool)
     .await?;
 
-    tracing::info!("Installed Omni notification trigger");
+    for (name, trigger) in EVENT_TRIGGERS {
+        sqlx::query(&format!("DROP TRIGGER IF EXISTS {name}"))
+            .execute(pool)
+            .await?;
+        sqlx::query(trigger).execute(pool).await?;
+    }
+
+    tracing::info!("Installed Omni notification triggers");
+    Ok(())
+}
+
+/// Queue a merge conflict hit by a merge or rebase of `attempt_id`. `op` is the
+/// operation that stopped, e.g. `rebase`.
+pub async fn queue_conflict_notification(
+    pool: &SqlitePool,
+    attempt_id: Uuid,
+    op: &str,
+    message: Option<&str>,
+) -> Result<()> {
+    sqlx::query(
+        r#"INSERT INTO forge_omni_notifications (
+               id, task_id, notification_type, recipient, message, status, metadata, created_at
+           )
+           SELECT
+               lower(hex(randomblob(16))),
+               NULL,
+               'merge_conflict',
+               '',
+               '',
+               'pending',
+               json_object(
+                   'task_attempt_id', lower(hex(ta.id)),
+                   'project_id', lower(hex(t.project_id)),
+                   'status', 'conflict',
+                   'branch', COALESCE(ta.branch, ''),
+                   'executor', COALESCE(ta.executor, ''),
+                   'conflict_op', ?,
+                   'conflict_message', ?
+               ),
+               datetime('now')
+           FROM task_attempts ta
+           JOIN tasks t ON t.id = ta.task_id
+           WHERE ta.id = ?"#,
+    )
+    .bind(op)
+    .bind(message)
+    .bind(attempt_id)
+    .execute(pool)
+    .await?;
+
     Ok(())
 }
+
+/// Subscribe to the upstream event stream and wake the notification worker whenever
+/// an execution process reaches a terminal status or a task moves to review or done.
+pub fn spawn_completion_listener(deployment: &DeploymentImpl, wakeup: Arc<Notify>) {
+    spawn_event_listener(deployment.events().msg_store().get_receiver(), wakeup);
+}
+
+/// Wake `wakeup` for the completions on `receiver`, a subscription to the event stream.
+pub(super) fn spawn_event_listener(mut receiver: Receiver<LogMsg>, wakeup: Arc<Notify>) {
+    tokio::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(LogMsg::JsonPatch(patch)) => {
+                    let Ok(value) = serde_json::to_value(&patch) else {
+                        continue;
+                    };
+                    if is_execution_exit_patch(&value) || is_task_status_patch(&value) {
+                        wakeup.notify_one();
+                    }
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => {
+                    // We may have missed a completion; let the worker check the queue
+                    tracing::debug!(skipped, "Omni completion listener lagged behind events");
+                    wakeup.notify_one();
+                }
+                Err(RecvError::Closed) => {
+                    tracing::warn!(
+                        "Upstream event stream closed; Omni worker falls back to polling"
+                    );
+                    break;
+                }
+            }
+        }
+    });
+}
+
+/// True when a JSON patch replaces or adds an execution process in a terminal status.
+fn is_execution_exit_patch(patch: &Value) -> bool {
+    let Some(operations) = patch.as_array() else {
+        return false;
+    };
+
+    operations.iter().any(|op| {
+        let is_upsert = matches!(
+            op.get("op").and_then(Value::as_str),
+            Some("add" | "replace")
+        );
+        let is_execution = op
+            .get("path")
+            .and_then(Value::as_str)
+            .is_some_and(|path| path.starts_with("/execution_processes/"));
+        let is_terminal = matches!(
+            op.pointer("/value/status").and_then(Value::as_str),
+            Some("completed" | "failed" | "killed")
+        );
+
+        is_upsert && is_execution && is_terminal
+    })
+}
+
+/// True when a JSON patch replaces a task with a status that queues a notification.
+fn is_task_status_patch(patch: &Value) -> bool {
+    let Some(operations) = patch.as_array() else {
+        return false;
+    };
+
+    operations.iter().any(|op| {
+        op.get("op").and_then(Value::as_str) == Some("replace")
+            && op
+                .get("path")
+                .and_then(Value::as_str)
+                .is_some_and(|path| path.starts_with("/tasks/"))
+            && matches!(
+                op.pointer("/value/status").and_then(Value::as_str),
+                Some("inreview" | "done")
+            )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn detects_terminal_execution_patches() {
+        let patch = json!([{
+            "op": "replace",
+            "path": "/execution_processes/7f1c",
+            "value": { "id": "7f1c", "status": "failed" }
+        }]);
+        assert!(is_execution_exit_patch(&patch));
+
+        let running = json!([{
+            "op": "replace",
+            "path": "/execution_processes/7f1c",
+            "value": { "id": "7f1c", "status": "running" }
+        }]);
+        assert!(!is_execution_exit_patch(&running));
+
+        let other_table = json!([{
+            "op": "replace",
+            "path": "/tasks/7f1c",
+            "value": { "id": "7f1c", "status": "completed" }
+        }]);
+        assert!(!is_execution_exit_patch(&other_table));
+    }
+
+    #[test]
+    fn detects_task_status_patches() {
+        let review = json!([{
+            "op": "replace",
+            "path": "/tasks/7f1c",
+            "value": { "id": "7f1c", "status": "inreview" }
+        }]);
+        assert!(is_task_status_patch(&review));
+
+        let todo = json!([{
+            "op": "replace",
+            "path": "/tasks/7f1c",
+            "value": { "id": "7f1c", "status": "todo" }
+        }]);
+        assert!(!is_task_status_patch(&todo));
+    }
+}
@@ -0,0 +1,442 @@
+//! Notification Log
+//!
+//! Queries and manual operations on `forge_omni_notifications` behind
+//! `/api/forge/omni/notifications`. Rows the worker may be holding (`processing`)
+//! are never modified here.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use sqlx::{QueryBuilder, Row, Sqlite, SqlitePool, sqlite::SqliteRow};
+use uuid::Uuid;
+
+use super::sqlite_datetime;
+
+pub const DEFAULT_PAGE_SIZE: i64 = 50;
+pub const MAX_PAGE_SIZE: i64 = 200;
+
+/// Filters accepted by the notification list endpoint.
+#[derive(Debug, Default, Deserialize)]
+pub struct NotificationLogQuery {
+    pub status: Option<String>,
+    pub task_id: Option<Uuid>,
+    pub project_id: Option<Uuid>,
+    /// Only rows created at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only rows created before this time
+    pub until: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+impl NotificationLogQuery {
+    pub fn limit(&self) -> i64 {
+        self.limit
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, MAX_PAGE_SIZE)
+    }
+
+    pub fn offset(&self) -> i64 {
+        self.offset.unwrap_or(0).max(0)
+    }
+
+    fn push_filters<'a>(&'a self, builder: &mut QueryBuilder<'a, Sqlite>) {
+        builder.push(" WHERE 1 = 1");
+        if let Some(status) = &self.status {
+            builder.push(" AND status = ").push_bind(status);
+        }
+        if let Some(task_id) = self.task_id {
+            // The triggers leave the `task_id` column empty; status changes name the
+            // task in their metadata, execution exits and PRs name the attempt
+            builder
+                .push(" AND (replace(lower(json_extract(metadata, '$.task_id')), '-', '') = ")
+                .push_bind(task_id.simple().to_string())
+                .push(
+                    " OR replace(lower(json_extract(metadata, '$.task_attempt_id')), '-', '') IN \
+                     (SELECT lower(hex(id)) FROM task_attempts WHERE task_id = ",
+                )
+                .push_bind(task_id)
+                .push("))");
+        }
+        if let Some(project_id) = self.project_id {
+            builder
+                .push(" AND replace(lower(json_extract(metadata, '$.project_id')), '-', '') = ")
+                .push_bind(project_id.simple().to_string());
+        }
+        if let Some(since) = self.since {
+            builder
+                .push(" AND created_at >= ")
+                .push_bind(sqlite_datetime(since));
+        }
+        if let Some(until) = self.until {
+            builder
+                .push(" AND created_at < ")
+                .push_bind(sqlite_datetime(until));
+        }
+    }
+}
+
+/// Outcome of a manual operation on one row.
+#[derive(Debug, PartialEq, Eq)]
+pub enum LogUpdate {
+    Updated,
+    NotFound,
+    /// The row exists but its current status does not allow the operation
+    Conflict(String),
+}
+
+/// One page of the log, newest first, and the number of rows matching the filters.
+pub async fn list_notifications(
+    pool: &SqlitePool,
+    query: &NotificationLogQuery,
+) -> Result<(Vec<Value>, i64)> {
+    let mut count = QueryBuilder::new("SELECT COUNT(*) FROM forge_omni_notifications");
+    query.push_filters(&mut count);
+    let total: i64 = count.build_query_scalar().fetch_one(pool).await?;
+
+    let mut select = QueryBuilder::new(
+        r#"SELECT
+                id,
+                task_id,
+                execution_process_id,
+                notification_type,
+                status,
+                message,
+                error_message,
+                sent_at,
+                created_at,
+                metadata,
+                attempt_count,
+                max_attempts,
+                next_attempt_at,
+                attempt_errors,
+                deliveries,
+                digest_id
+           FROM forge_omni_notifications"#,
+    );
+    query.push_filters(&mut select);
+    select
+        .push(" ORDER BY created_at DESC, rowid DESC LIMIT ")
+        .push_bind(query.limit())
+        .push(" OFFSET ")
+        .push_bind(query.offset());
+
+    let rows = select.build().fetch_all(pool).await?;
+    Ok((rows.iter().map(notification_json).collect(), total))
+}
+
+fn notification_json(row: &SqliteRow) -> Value {
+    let metadata = match row.try_get::<Option<String>, _>("metadata") {
+        Ok(Some(raw)) => serde_json::from_str::<Value>(&raw).ok(),
+        _ => None,
+    };
+    let attempt_errors = row
+        .try_get::<Option<String>, _>("attempt_errors")
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str::<Value>(&raw).ok())
+        .unwrap_or_else(|| json!([]));
+    let deliveries = row
+        .try_get::<Option<String>, _>("deliveries")
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str::<Value>(&raw).ok())
+        .unwrap_or_else(|| json!({}));
+
+    json!({
+        "id": row.try_get::<String, _>("id").unwrap_or_default(),
+        "task_id": row.try_get::<Option<String>, _>("task_id").unwrap_or(None),
+        "execution_process_id": row
+            .try_get::<Option<String>, _>("execution_process_id")
+            .unwrap_or(None),
+        "notification_type": row
+            .try_get::<String, _>("notification_type")
+            .unwrap_or_else(|_| "unknown".to_string()),
+        "status": row
+            .try_get::<String, _>("status")
+            .unwrap_or_else(|_| "pending".to_string()),
+        "message": row.try_get::<Option<String>, _>("message").unwrap_or(None),
+        "error_message": row
+            .try_get::<Option<String>, _>("error_message")
+            .unwrap_or(None),
+        "sent_at": row.try_get::<Option<String>, _>("sent_at").unwrap_or(None),
+        "created_at": row
+            .try_get::<String, _>("created_at")
+            .unwrap_or_else(|_| Utc::now().to_rfc3339()),
+        "metadata": metadata,
+        "attempt_count": row.try_get::<i64, _>("attempt_count").unwrap_or(0),
+        "max_attempts": row.try_get::<i64, _>("max_attempts").unwrap_or(0),
+        "next_attempt_at": row
+            .try_get::<Option<String>, _>("next_attempt_at")
+            .unwrap_or(None),
+        "attempt_errors": attempt_errors,
+        "deliveries": deliveries,
+        "digest_id": row.try_get::<Option<String>, _>("digest_id").unwrap_or(None),
+    })
+}
+
+/// Queue a failed or skipped notification again with a fresh retry budget.
+///
+/// The row is marked `manual_resend`, so notification rules, quiet hours in skip
+/// mode and digests no longer apply to it; channels that already received it are
+/// not sent to again.
+pub async fn resend_notification(pool: &SqlitePool, id: &str) -> Result<LogUpdate> {
+    let result = sqlx::query(
+        r#"UPDATE forge_omni_notifications
+              SET status = 'pending',
+                  attempt_count = 0,
+                  next_attempt_at = NULL,
+                  claimed_at = NULL,
+                  error_message = NULL,
+                  metadata = json_set(COALESCE(metadata, '{}'), '$.manual_resend', json('true'))
+            WHERE id = ? AND status IN ('failed', 'skipped')"#,
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    update_outcome(pool, id, result.rows_affected()).await
+}
+
+/// Stop a queued notification from being delivered.
+pub async fn cancel_notification(pool: &SqlitePool, id: &str) -> Result<LogUpdate> {
+    let result = sqlx::query(
+        r#"UPDATE forge_omni_notifications
+              SET status = 'cancelled',
+                  next_attempt_at = NULL,
+                  error_message = 'Cancelled manually'
+            WHERE id = ? AND status IN ('pending', 'batched')"#,
+    )
+    .bind(id)
+    .execute(pool)
+    .await?;
+
+    update_outcome(pool, id, result.rows_affected()).await
+}
+
+/// Delete finished rows created more than `older_than_days` days ago.
+pub async fn purge_notifications(pool: &SqlitePool, older_than_days: u32) -> Result<u64> {
+    let result = sqlx::query(
+        r#"DELETE FROM forge_omni_notifications
+            WHERE created_at < datetime('now', '-' || ? || ' days')
+              AND status NOT IN ('pending', 'processing', 'batched')"#,
+    )
+    .bind(older_than_days)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+async fn update_outcome(pool: &SqlitePool, id: &str, rows_affected: u64) -> Result<LogUpdate> {
+    if rows_affected > 0 {
+        return Ok(LogUpdate::Updated);
+    }
+
+    let status: Option<String> =
+        sqlx::query_scalar("SELECT status FROM forge_omni_notifications WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await?;
+    Ok(status.map_or(LogUpdate::NotFound, LogUpdate::Conflict))
+}
+
+#[cfg(test)]
+mod tests {
+    use forge_config::SettingsSource;
+    use forge_notifications::NotificationRule;
+    use httpmock::prelude::*;
+
+    use super::*;
+    use crate::services::tests::{
+        configure_omni, insert_project, insert_task_graph, pending_metadata, queue_notification,
+        setup_pool,
+    };
+    use crate::services::{notification_hook, process_next_omni_notification};
+
+    #[tokio::test]
+    async fn notification_log_filters_and_manual_operations() {
+        let pool = setup_pool().await;
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, project_id).await;
+        let (task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+        let other_project_id = Uuid::new_v4();
+        insert_project(&pool, other_project_id).await;
+        let (other_task_id, other_attempt_id) = insert_task_graph(&pool, other_project_id).await;
+
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/api/v1/instance/forge-instance/send-text");
+            then.status(200)
+                .header("Content-Type", "application/json")
+                .json_body(json!({ "success": true, "message_id": "msg-1", "status": "queued" }));
+        });
+
+        let config_service = configure_omni(&pool, project_id, server.base_url(), |_| {}).await;
+        let mut settings = config_service.get_forge_settings(project_id).await.unwrap();
+        settings.notification_rules = vec![NotificationRule {
+            name: "failures only".into(),
+            statuses: vec!["failed".into()],
+            ..Default::default()
+        }];
+        config_service
+            .set_forge_settings(project_id, &settings, SettingsSource::Api)
+            .await
+            .unwrap();
+
+        queue_notification(
+            &pool,
+            "filtered",
+            task_id,
+            pending_metadata(attempt_id, project_id),
+        )
+        .await;
+        assert!(
+            process_next_omni_notification(&pool, &config_service)
+                .await
+                .unwrap()
+        );
+        queue_notification(
+            &pool,
+            "other",
+            other_task_id,
+            pending_metadata(other_attempt_id, other_project_id),
+        )
+        .await;
+
+        let ids = |rows: Vec<Value>| -> Vec<String> {
+            rows.iter()
+                .map(|row| row["id"].as_str().unwrap().to_string())
+                .collect()
+        };
+        let (rows, total) = list_notifications(
+            &pool,
+            &NotificationLogQuery {
+                project_id: Some(project_id),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!((ids(rows), total), (vec!["filtered".to_string()], 1));
+        let (rows, _) = list_notifications(
+            &pool,
+            &NotificationLogQuery {
+                task_id: Some(other_task_id),
+                status: Some("pending".into()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(ids(rows), vec!["other".to_string()]);
+        let (rows, total) = list_notifications(
+            &pool,
+            &NotificationLogQuery {
+                limit: Some(1),
+                offset: Some(1),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!((rows.len(), total), (1, 2));
+
+        assert_eq!(
+            resend_notification(&pool, "other").await.unwrap(),
+            LogUpdate::Conflict("pending".into())
+        );
+        assert_eq!(
+            resend_notification(&pool, "missing").await.unwrap(),
+            LogUpdate::NotFound
+        );
+        assert_eq!(
+            cancel_notification(&pool, "other").await.unwrap(),
+            LogUpdate::Updated
+        );
+
+        // A resent row is delivered even though the rule filtered it the first time
+        assert_eq!(
+            resend_notification(&pool, "filtered").await.unwrap(),
+            LogUpdate::Updated
+        );
+        assert!(
+            process_next_omni_notification(&pool, &config_service)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !process_next_omni_notification(&pool, &config_service)
+                .await
+                .unwrap()
+        );
+        mock.assert_hits_async(1).await;
+
+        let statuses: Vec<(String, String)> =
+            sqlx::query_as("SELECT id, status FROM forge_omni_notifications ORDER BY id")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            statuses,
+            vec![
+                ("filtered".to_string(), "sent".to_string()),
+                ("other".to_string(), "cancelled".to_string()),
+            ]
+        );
+
+        sqlx::query(
+            "UPDATE forge_omni_notifications SET created_at = datetime('now', '-40 days') WHERE id = 'filtered'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert_eq!(purge_notifications(&pool, 30).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn task_filter_matches_status_changes() {
+        let pool = setup_pool().await;
+        notification_hook::install_notification_trigger(&pool)
+            .await
+            .unwrap();
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, project_id).await;
+        let (task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+        let (other_task_id, _) = insert_task_graph(&pool, project_id).await;
+
+        queue_notification(
+            &pool,
+            "exit",
+            task_id,
+            pending_metadata(attempt_id, project_id),
+        )
+        .await;
+        for id in [task_id, other_task_id] {
+            sqlx::query("UPDATE tasks SET status = 'done' WHERE id = ?")
+                .bind(id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let (rows, total) = list_notifications(
+            &pool,
+            &NotificationLogQuery {
+                task_id: Some(task_id),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(total, 2);
+        let mut types: Vec<&str> = rows
+            .iter()
+            .map(|row| row["notification_type"].as_str().unwrap())
+            .collect();
+        types.sort();
+        assert_eq!(types, ["execution_completed", "task_status_changed"]);
+    }
+}
@@ -0,0 +1,281 @@
+//! Omni Health
+//!
+//! Background checks of the configured Omni instance. Every check is stored in
+//! `forge_omni_health_checks` and listed by `/api/forge/omni/status`. While the
+//! latest check of an instance is unhealthy the notification worker holds rows
+//! for it instead of failing them, and each change between healthy and unhealthy
+//! is pushed to the upstream event stream as a banner.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use forge_omni::OmniService;
+use serde_json::{Value, json};
+use sqlx::{Row, SqlitePool};
+use tokio::{
+    sync::{Notify, RwLock},
+    time::{Instant, sleep},
+};
+use utils::msg_store::MsgStore;
+
+pub const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Older checks no longer pause notifications, e.g. once the monitor has stopped.
+const HEALTH_CHECK_MAX_AGE: Duration = Duration::from_secs(3 * 60);
+
+const HEALTH_HISTORY_RETENTION_DAYS: i64 = 7;
+
+/// Checks returned by the status endpoint, newest first.
+pub const HEALTH_HISTORY_LIMIT: i64 = 50;
+
+/// Reason recorded on notifications held for the unhealthy `instance`.
+pub fn paused_reason(instance: &str) -> String {
+    format!("Paused while the Omni instance '{instance}' is unhealthy")
+}
+
+/// JSON pointer the banner event is added at.
+pub const BANNER_EVENT_PATH: &str = "/forge_banner";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HealthCheck {
+    pub instance: String,
+    pub healthy: bool,
+    /// Status reported by Omni for the instance
+    pub status: Option<String>,
+    pub error: Option<String>,
+    pub latency_ms: i64,
+}
+
+pub fn spawn_health_monitor(
+    pool: SqlitePool,
+    omni: Arc<RwLock<OmniService>>,
+    events: Arc<MsgStore>,
+    wakeup: Arc<Notify>,
+) {
+    tokio::spawn(async move {
+        loop {
+            if let Err(err) = run_health_check(&pool, &omni, &events, &wakeup).await {
+                tracing::error!("Omni health check failed: {err:?}");
+            }
+            sleep(HEALTH_CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Check the configured instance, store the result and announce a change of state.
+/// Notifications paused for the instance are resumed once it is healthy again.
+pub async fn run_health_check(
+    pool: &SqlitePool,
+    omni: &RwLock<OmniService>,
+    events: &MsgStore,
+    wakeup: &Notify,
+) -> Result<Option<HealthCheck>> {
+    let check = {
+        let omni = omni.read().await;
+        check_instance(&omni).await
+    };
+    let Some(check) = check else {
+        return Ok(None);
+    };
+
+    let previous = record_check(pool, &check).await?;
+    match (previous, check.healthy) {
+        (Some(true) | None, false) => {
+            tracing::warn!(
+                instance = %check.instance,
+                "Omni instance is unhealthy; pausing notifications"
+            );
+            events.push_patch(banner_patch(&check)?);
+        }
+        (Some(false), true) => {
+            let resumed = resume_paused(pool, &check.instance).await?;
+            tracing::info!(
+                instance = %check.instance,
+                resumed,
+                "Omni instance is healthy again"
+            );
+            events.push_patch(banner_patch(&check)?);
+            wakeup.notify_one();
+        }
+        _ => {}
+    }
+
+    Ok(Some(check))
+}
+
+/// Look up the configured instance on the Omni server. `None` when Omni is disabled
+/// or has no instance to check.
+pub async fn check_instance(omni: &OmniService) -> Option<HealthCheck> {
+    let config = omni.config();
+    let instance = config
+        .instance
+        .as_deref()
+        .filter(|instance| !instance.trim().is_empty())?
+        .to_string();
+    if !config.enabled || config.host.as_deref().is_none_or(str::is_empty) {
+        return None;
+    }
+
+    let started = Instant::now();
+    let result = omni.list_instances().await;
+    let latency_ms = started.elapsed().as_millis() as i64;
+
+    let (healthy, status, error) = match result {
+        Ok(instances) => match instances
+            .into_iter()
+            .find(|candidate| candidate.instance_name == instance)
+        {
+            Some(found) => (found.is_healthy, Some(found.status), None),
+            None => (
+                false,
+                None,
+                Some(format!(
+                    "Instance '{instance}' not found on the Omni server"
+                )),
+            ),
+        },
+        Err(err) => (false, None, Some(err.to_string())),
+    };
+
+    Some(HealthCheck {
+        instance,
+        healthy,
+        status,
+        error,
+        latency_ms,
+    })
+}
+
+/// Store a check and drop history past the retention period. Returns whether the
+/// previous check of the same instance was healthy, if there was one.
+pub async fn record_check(pool: &SqlitePool, check: &HealthCheck) -> Result<Option<bool>> {
+    let previous: Option<bool> = sqlx::query_scalar(
+        r#"SELECT healthy
+             FROM forge_omni_health_checks
+            WHERE instance = ?
+            ORDER BY id DESC
+            LIMIT 1"#,
+    )
+    .bind(&check.instance)
+    .fetch_optional(pool)
+    .await?;
+
+    sqlx::query(
+        r#"INSERT INTO forge_omni_health_checks (instance, healthy, status, error_message, latency_ms)
+           VALUES (?, ?, ?, ?, ?)"#,
+    )
+    .bind(&check.instance)
+    .bind(check.healthy)
+    .bind(&check.status)
+    .bind(&check.error)
+    .bind(check.latency_ms)
+    .execute(pool)
+    .await?;
+
+    sqlx::query(
+        "DELETE FROM forge_omni_health_checks WHERE checked_at < datetime('now', '-' || ? || ' days')",
+    )
+    .bind(HEALTH_HISTORY_RETENTION_DAYS)
+    .execute(pool)
+    .await?;
+
+    Ok(previous)
+}
+
+/// True while the latest recent check of `instance` is unhealthy.
+pub async fn is_paused(pool: &SqlitePool, instance: &str) -> Result<bool> {
+    let healthy: Option<bool> = sqlx::query_scalar(
+        r#"SELECT healthy
+             FROM forge_omni_health_checks
+            WHERE instance = ?
+              AND checked_at > datetime('now', '-' || ? || ' seconds')
+            ORDER BY id DESC
+            LIMIT 1"#,
+    )
+    .bind(instance)
+    .bind(HEALTH_CHECK_MAX_AGE.as_secs() as i64)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(healthy == Some(false))
+}
+
+/// Make notifications paused for `instance` due right away. The reason names each
+/// held target, so a row held for several reasons is matched as well.
+async fn resume_paused(pool: &SqlitePool, instance: &str) -> Result<u64> {
+    let result = sqlx::query(
+        r#"UPDATE forge_omni_notifications
+              SET next_attempt_at = NULL,
+                  error_message = NULL
+            WHERE status = 'pending' AND instr(error_message, ?) > 0"#,
+    )
+    .bind(paused_reason(instance))
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Latest checks, newest first.
+pub async fn health_history(pool: &SqlitePool, limit: i64) -> Result<Vec<Value>> {
+    let rows = sqlx::query(
+        r#"SELECT instance, healthy, status, error_message, latency_ms, checked_at
+             FROM forge_omni_health_checks
+            ORDER BY id DESC
+            LIMIT ?"#,
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    rows.iter()
+        .map(|row| {
+            Ok(json!({
+                "instance": row.try_get::<String, _>("instance")?,
+                "healthy": row.try_get::<bool, _>("healthy")?,
+                "status": row.try_get::<Option<String>, _>("status")?,
+                "error_message": row.try_get::<Option<String>, _>("error_message")?,
+                "latency_ms": row.try_get::<Option<i64>, _>("latency_ms")?,
+                "checked_at": row.try_get::<String, _>("checked_at")?,
+            }))
+        })
+        .collect()
+}
+
+fn banner_patch(check: &HealthCheck) -> Result<json_patch::Patch> {
+    let (level, message) = if check.healthy {
+        (
+            "info",
+            format!(
+                "Omni instance '{}' is healthy again; notifications resumed",
+                check.instance
+            ),
+        )
+    } else {
+        let detail = check
+            .error
+            .as_deref()
+            .or(check.status.as_deref())
+            .unwrap_or("unhealthy");
+        (
+            "warning",
+            format!(
+                "Omni instance '{}' is unavailable ({detail}); notifications are paused",
+                check.instance
+            ),
+        )
+    };
+
+    Ok(serde_json::from_value(json!([{
+        "op": "add",
+        "path": BANNER_EVENT_PATH,
+        "value": {
+            "id": "omni_health",
+            "level": level,
+            "message": message,
+            "instance": check.instance,
+            "healthy": check.healthy,
+            "at": chrono::Utc::now().to_rfc3339(),
+        },
+    }]))?)
+}
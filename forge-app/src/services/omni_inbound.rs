@@ -0,0 +1,230 @@
+//! Omni Inbound
+//!
+//! Lookups behind the chat commands received on `/api/forge/omni/inbound`. Ids are
+//! typed on a phone, so tasks and attempts are resolved from a prefix of their hex
+//! UUID, as shown in the `status` reply and in `forge/<prefix>-...` branch names.
+
+use anyhow::{Result, anyhow};
+use sqlx::{Row, SqlitePool};
+use uuid::Uuid;
+
+/// Running agents listed by `status`; the reply mentions how many were left out.
+const STATUS_LIMIT: i64 = 10;
+
+/// Executor, variant and base branch of the most recent attempt, reused by `retry`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryTarget {
+    pub task_title: String,
+    pub executor: String,
+    /// Variant the attempt asked for; `None` when it used the default
+    pub variant: Option<String>,
+    pub base_branch: String,
+}
+
+/// First eight hex digits of an id, as shown in replies.
+pub fn short_id(id: &Uuid) -> String {
+    id.simple().to_string()[..8].to_string()
+}
+
+pub async fn resolve_task(pool: &SqlitePool, prefix: &str) -> Result<Uuid> {
+    resolve_id(pool, "tasks", "task", prefix).await
+}
+
+pub async fn resolve_attempt(pool: &SqlitePool, prefix: &str) -> Result<Uuid> {
+    resolve_id(pool, "task_attempts", "attempt", prefix).await
+}
+
+async fn resolve_id(
+    pool: &SqlitePool,
+    table: &'static str,
+    kind: &str,
+    prefix: &str,
+) -> Result<Uuid> {
+    let ids: Vec<String> = sqlx::query_scalar(&format!(
+        "SELECT lower(hex(id)) FROM {table} WHERE lower(hex(id)) LIKE ? || '%' LIMIT 2"
+    ))
+    .bind(prefix)
+    .fetch_all(pool)
+    .await?;
+
+    match ids.as_slice() {
+        [id] => Ok(Uuid::parse_str(id)?),
+        [] => Err(anyhow!("No {kind} matches '{prefix}'")),
+        _ => Err(anyhow!(
+            "'{prefix}' matches several {kind}s; send more characters of the id"
+        )),
+    }
+}
+
+pub async fn retry_target(pool: &SqlitePool, task_id: Uuid) -> Result<RetryTarget> {
+    let row = sqlx::query(
+        r#"SELECT t.title, ta.executor, ta.base_branch,
+                  (SELECT json_extract(ep.executor_action, '$.typ.executor_profile_id.variant')
+                     FROM execution_processes ep
+                    WHERE ep.task_attempt_id = ta.id AND ep.run_reason = 'codingagent'
+                    ORDER BY ep.started_at
+                    LIMIT 1) AS variant
+             FROM tasks t
+             JOIN task_attempts ta ON ta.task_id = t.id
+            WHERE t.id = ?
+            ORDER BY ta.created_at DESC
+            LIMIT 1"#,
+    )
+    .bind(task_id)
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| anyhow!("Task has no previous attempt to retry"))?;
+
+    Ok(RetryTarget {
+        task_title: row.try_get("title")?,
+        executor: row.try_get("executor")?,
+        variant: row
+            .try_get::<Option<String>, _>("variant")?
+            .and_then(|variant| requested_variant(&variant)),
+        base_branch: row.try_get("base_branch")?,
+    })
+}
+
+/// Variant an attempt asked for, from the variant it was started under. A
+/// project variant (`FORGE_1A2B3C4D_FAST`, see `ResolvedExecutor::profile_variant`)
+/// gives its name back, so a retry resolves it again through the project's
+/// current custom executors.
+fn requested_variant(started: &str) -> Option<String> {
+    let variant = match started.strip_prefix("FORGE_") {
+        Some(project_variant) => project_variant.get(9..)?,
+        None => started,
+    };
+    (variant != "DEFAULT").then(|| variant.to_string())
+}
+
+/// Execution processes of an attempt that are still running.
+pub async fn running_processes(pool: &SqlitePool, attempt_id: Uuid) -> Result<i64> {
+    Ok(sqlx::query_scalar(
+        "SELECT COUNT(*) FROM execution_processes WHERE task_attempt_id = ? AND status = 'running'",
+    )
+    .bind(attempt_id)
+    .fetch_one(pool)
+    .await?)
+}
+
+/// Text reply for `status`: coding agents that are currently running.
+pub async fn status_report(pool: &SqlitePool) -> Result<String> {
+    let rows = sqlx::query(
+        r#"SELECT substr(lower(hex(ta.id)), 1, 8) AS attempt,
+                  t.title,
+                  COALESCE(ta.executor, '') AS executor,
+                  CAST((julianday('now') - julianday(ep.started_at)) * 1440 AS INTEGER) AS minutes,
+                  COUNT(*) OVER () AS total
+             FROM execution_processes ep
+             JOIN task_attempts ta ON ta.id = ep.task_attempt_id
+             JOIN tasks t ON t.id = ta.task_id
+            WHERE ep.status = 'running' AND ep.run_reason = 'codingagent'
+            ORDER BY ep.started_at
+            LIMIT ?"#,
+    )
+    .bind(STATUS_LIMIT)
+    .fetch_all(pool)
+    .await?;
+
+    let Some(first) = rows.first() else {
+        return Ok("No agents running.".to_string());
+    };
+    let total: i64 = first.try_get("total")?;
+
+    let mut lines = vec![format!("🤖 {total} running")];
+    for row in &rows {
+        lines.push(format!(
+            "▶ {} {} — {}, {}m",
+            row.try_get::<String, _>("attempt")?,
+            row.try_get::<String, _>("title")?,
+            row.try_get::<String, _>("executor")?,
+            row.try_get::<i64, _>("minutes")?.max(0),
+        ));
+    }
+    if total > rows.len() as i64 {
+        lines.push(format!("…and {} more", total - rows.len() as i64));
+    }
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    use crate::services::tests::{insert_project, insert_task_graph, setup_pool};
+
+    #[tokio::test]
+    async fn inbound_commands_resolve_id_prefixes() {
+        let pool = setup_pool().await;
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, project_id).await;
+        let (task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+
+        let prefix = &attempt_id.simple().to_string()[..8];
+        assert_eq!(resolve_attempt(&pool, prefix).await.unwrap(), attempt_id);
+        let task_hex = task_id.simple().to_string();
+        assert_eq!(resolve_task(&pool, &task_hex).await.unwrap(), task_id);
+        let missing = resolve_task(&pool, prefix).await.unwrap_err();
+        assert!(missing.to_string().starts_with("No task matches"));
+
+        let retry = retry_target(&pool, task_id).await.unwrap();
+        assert_eq!(retry.executor, "forge-agent");
+        assert_eq!(retry.variant, None);
+        assert_eq!(retry.base_branch, "main");
+
+        assert_eq!(status_report(&pool).await.unwrap(), "No agents running.");
+        sqlx::query(
+            "INSERT INTO execution_processes (id, task_attempt_id, status, run_reason)
+             VALUES ('running-agent', ?, 'running', 'codingagent')",
+        )
+        .bind(attempt_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let report = status_report(&pool).await.unwrap();
+        assert_eq!(
+            report,
+            format!("🤖 1 running\n▶ {prefix} Omni Notification Test — forge-agent, 0m")
+        );
+        assert_eq!(running_processes(&pool, attempt_id).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn retry_reuses_the_variant_the_attempt_asked_for() {
+        let pool = setup_pool().await;
+        let project_id = Uuid::new_v4();
+        insert_project(&pool, project_id).await;
+        let (task_id, attempt_id) = insert_task_graph(&pool, project_id).await;
+
+        for (id, variant) in [("setup", "PLAN"), ("agent", "FORGE_1A2B3C4D_FAST")] {
+            let action = json!({
+                "typ": {
+                    "type": "CodingAgentInitialRequest",
+                    "executor_profile_id": { "executor": "CLAUDE_CODE", "variant": variant }
+                }
+            });
+            let run_reason = if id == "agent" {
+                "codingagent"
+            } else {
+                "setupscript"
+            };
+            sqlx::query(
+                "INSERT INTO execution_processes (id, task_attempt_id, status, run_reason, executor_action)
+                 VALUES (?, ?, 'completed', ?, ?)",
+            )
+            .bind(id)
+            .bind(attempt_id)
+            .bind(run_reason)
+            .bind(action.to_string())
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let retry = retry_target(&pool, task_id).await.unwrap();
+        assert_eq!(retry.variant.as_deref(), Some("FAST"));
+        assert_eq!(requested_variant("PLAN").as_deref(), Some("PLAN"));
+        assert_eq!(requested_variant("FORGE_1A2B3C4D_DEFAULT"), None);
+    }
+}
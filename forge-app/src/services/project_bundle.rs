@@ -0,0 +1,675 @@
+//! Project Bundles
+//!
+//! Export of a project with its forge settings, custom executors, task templates
+//! and optionally tasks, and the matching import behind
+//! `/api/forge/projects/{id}/export` and `/api/forge/projects/import`. Imported
+//! rows get new ids; settings are upgraded to the current schema and recorded in
+//! the settings history as an import. Everything is written in one transaction.
+
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use forge_config::bundle::{
+    BUNDLE_FORMAT_VERSION, BundledProject, BundledTask, BundledTaskTemplate, ImportConflict,
+    ImportConflictKind, ProjectBundle, ProjectImportReport, ProjectImportRequest,
+};
+use forge_config::schema::{parse_settings, stamp_version};
+use forge_config::secrets::strip_secrets;
+use forge_config::{ForgeConfigService, ForgeProjectSettings, SettingsScope, SettingsSource};
+use serde::Deserialize;
+use serde_json::Value;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Statuses the `tasks` table accepts; `agent` is added by the forge migration
+/// `20251020000001_add_agent_task_status`.
+const TASK_STATUSES: &[&str] = &[
+    "todo",
+    "inprogress",
+    "inreview",
+    "done",
+    "cancelled",
+    "agent",
+];
+
+/// Options of the export endpoint.
+#[derive(Debug, Default, Deserialize)]
+pub struct ExportOptions {
+    /// Keep the secret fields of the settings, in plain text
+    #[serde(default)]
+    pub include_secrets: bool,
+    #[serde(default)]
+    pub include_tasks: bool,
+}
+
+pub enum ImportOutcome {
+    /// Imported, or checked when the request was a dry run
+    Completed(ProjectImportReport),
+    /// A blocking conflict was found; nothing was written
+    Blocked(ProjectImportReport),
+    /// The bundle cannot be imported; the message says why
+    Invalid(String),
+}
+
+type ProjectRow = (
+    Uuid,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+/// Bundle for a project, or `None` when there is no such project.
+pub async fn export_project(
+    pool: &SqlitePool,
+    config: &ForgeConfigService,
+    project_id: Uuid,
+    options: &ExportOptions,
+) -> Result<Option<ProjectBundle>> {
+    let row: Option<ProjectRow> = sqlx::query_as(
+        "SELECT id, name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files
+           FROM projects
+          WHERE id = ?",
+    )
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await?;
+    let Some((id, name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files)) = row
+    else {
+        return Ok(None);
+    };
+
+    let project_config = config.get_project_config(project_id).await?;
+    let settings = project_config
+        .as_ref()
+        .and_then(|project_config| project_config.forge_config.clone())
+        .map(|value| {
+            let settings = parse_settings(value)
+                .with_context(|| format!("Stored settings for project {project_id} are invalid"))?;
+            export_settings(&settings, options)
+        })
+        .transpose()?;
+    let custom_executors =
+        project_config.and_then(|project_config| project_config.custom_executors);
+
+    let templates: Vec<(Uuid, String, Option<String>, String)> = sqlx::query_as(
+        "SELECT id, title, description, template_name
+           FROM task_templates
+          WHERE project_id = ?
+          ORDER BY template_name",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut tasks = Vec::new();
+    if options.include_tasks {
+        let rows: Vec<(Uuid, String, Option<String>, String)> = sqlx::query_as(
+            "SELECT id, title, description, status
+               FROM tasks
+              WHERE project_id = ?
+              ORDER BY created_at",
+        )
+        .bind(project_id)
+        .fetch_all(pool)
+        .await?;
+
+        for (id, title, description, status) in rows {
+            let settings = config
+                .get_task_settings(id)
+                .await?
+                .map(|settings| export_settings(&settings, options))
+                .transpose()?;
+            tasks.push(BundledTask {
+                id,
+                title,
+                description,
+                status,
+                settings,
+            });
+        }
+    }
+
+    Ok(Some(ProjectBundle {
+        format_version: BUNDLE_FORMAT_VERSION,
+        exported_at: Utc::now(),
+        includes_secrets: options.include_secrets,
+        project: BundledProject {
+            id,
+            name,
+            git_repo_path,
+            setup_script,
+            dev_script,
+            cleanup_script,
+            copy_files,
+        },
+        settings,
+        custom_executors,
+        task_templates: templates
+            .into_iter()
+            .map(
+                |(id, title, description, template_name)| BundledTaskTemplate {
+                    id,
+                    title,
+                    description,
+                    template_name,
+                },
+            )
+            .collect(),
+        tasks,
+    }))
+}
+
+fn export_settings(settings: &ForgeProjectSettings, options: &ExportOptions) -> Result<Value> {
+    let mut value = serde_json::to_value(settings)?;
+    stamp_version(&mut value);
+    if !options.include_secrets {
+        strip_secrets(&mut value);
+    }
+    Ok(value)
+}
+
+/// Create a project from a bundle with new ids. Settings are checked before
+/// anything is written, and conflicts are reported in the outcome.
+pub async fn import_project(
+    pool: &SqlitePool,
+    config: &ForgeConfigService,
+    request: ProjectImportRequest,
+) -> Result<ImportOutcome> {
+    let bundle = request.bundle;
+    if bundle.format_version > BUNDLE_FORMAT_VERSION {
+        return Ok(ImportOutcome::Invalid(format!(
+            "Bundle format {} is newer than the supported {BUNDLE_FORMAT_VERSION}",
+            bundle.format_version
+        )));
+    }
+
+    let project_settings = match bundle.settings.clone().map(import_settings).transpose() {
+        Ok(settings) => settings,
+        Err(err) => {
+            return Ok(ImportOutcome::Invalid(format!(
+                "Project settings are invalid: {err:#}"
+            )));
+        }
+    };
+    if let Some(custom_executors) = &bundle.custom_executors
+        && let Err(err) = custom_executors.validate()
+    {
+        return Ok(ImportOutcome::Invalid(format!(
+            "Custom executors are invalid: {err:#}"
+        )));
+    }
+    let mut task_settings = Vec::with_capacity(bundle.tasks.len());
+    for task in &bundle.tasks {
+        if !TASK_STATUSES.contains(&task.status.as_str()) {
+            return Ok(ImportOutcome::Invalid(format!(
+                "Task '{}' has an unknown status '{}'",
+                task.title, task.status
+            )));
+        }
+        match task.settings.clone().map(import_settings).transpose() {
+            Ok(settings) => task_settings.push(settings),
+            Err(err) => {
+                return Ok(ImportOutcome::Invalid(format!(
+                    "Settings of task '{}' are invalid: {err:#}",
+                    task.title
+                )));
+            }
+        }
+    }
+
+    let name = request.name.unwrap_or_else(|| bundle.project.name.clone());
+    let git_repo_path = request
+        .git_repo_path
+        .unwrap_or_else(|| bundle.project.git_repo_path.clone());
+    let project_id = Uuid::new_v4();
+    let mut conflicts = Vec::new();
+
+    let existing: Option<(Uuid, String)> =
+        sqlx::query_as("SELECT id, name FROM projects WHERE git_repo_path = ?")
+            .bind(&git_repo_path)
+            .fetch_optional(pool)
+            .await?;
+    if let Some((existing_id, existing_name)) = existing {
+        conflicts.push(ImportConflict {
+            kind: ImportConflictKind::ProjectPathInUse,
+            message: format!(
+                "Project '{existing_name}' ({existing_id}) already uses {git_repo_path}; \
+                 import with another git_repo_path"
+            ),
+        });
+    }
+
+    let secrets_needed = [&project_settings]
+        .into_iter()
+        .chain(task_settings.iter())
+        .flatten()
+        .any(|settings| {
+            settings.omni_enabled == Some(true) || !settings.notification_channels.is_empty()
+        });
+    if !bundle.includes_secrets && secrets_needed {
+        conflicts.push(ImportConflict {
+            kind: ImportConflictKind::SecretsNotIncluded,
+            message: "The bundle was exported without secrets; enter the Omni API key and \
+                      channel credentials again"
+                .into(),
+        });
+    }
+
+    let mut id_map = BTreeMap::from([(bundle.project.id, project_id)]);
+    let mut template_names = HashSet::new();
+    let mut templates = Vec::new();
+    for template in &bundle.task_templates {
+        if !template_names.insert(template.template_name.as_str()) {
+            conflicts.push(ImportConflict {
+                kind: ImportConflictKind::DuplicateTemplateName,
+                message: format!(
+                    "Template '{}' appears more than once; only the first is imported",
+                    template.template_name
+                ),
+            });
+            continue;
+        }
+        let id = Uuid::new_v4();
+        id_map.insert(template.id, id);
+        templates.push((id, template));
+    }
+    let tasks: Vec<_> = bundle
+        .tasks
+        .iter()
+        .zip(task_settings)
+        .map(|(task, settings)| {
+            let id = Uuid::new_v4();
+            id_map.insert(task.id, id);
+            (id, task, settings)
+        })
+        .collect();
+
+    let mut report = ProjectImportReport {
+        imported: false,
+        project_id,
+        id_map,
+        task_templates: templates.len(),
+        tasks: tasks.len(),
+        conflicts,
+    };
+    if report.is_blocked() {
+        return Ok(ImportOutcome::Blocked(report));
+    }
+    if request.dry_run {
+        return Ok(ImportOutcome::Completed(report));
+    }
+
+    let project = &bundle.project;
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        "INSERT INTO projects (id, name, git_repo_path, setup_script, dev_script, cleanup_script, copy_files)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(project_id)
+    .bind(&name)
+    .bind(&git_repo_path)
+    .bind(&project.setup_script)
+    .bind(&project.dev_script)
+    .bind(&project.cleanup_script)
+    .bind(&project.copy_files)
+    .execute(&mut *tx)
+    .await?;
+
+    for (id, template) in &templates {
+        sqlx::query(
+            "INSERT INTO task_templates (id, project_id, title, description, template_name)
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(id)
+        .bind(project_id)
+        .bind(&template.title)
+        .bind(&template.description)
+        .bind(&template.template_name)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    for (id, task, _) in &tasks {
+        sqlx::query(
+            "INSERT INTO tasks (id, project_id, title, description, status)
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(id)
+        .bind(project_id)
+        .bind(&task.title)
+        .bind(&task.description)
+        .bind(&task.status)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    // Settings go in the same transaction, so a failure leaves no project behind
+    if let Some(settings) = &project_settings {
+        config
+            .write_settings_in(
+                &mut tx,
+                SettingsScope::Project,
+                Some(project_id),
+                settings,
+                SettingsSource::Import,
+            )
+            .await?;
+    }
+    if bundle.custom_executors.is_some() {
+        config
+            .set_custom_executors_in(&mut tx, project_id, bundle.custom_executors.as_ref())
+            .await?;
+    }
+    for (id, _, settings) in &tasks {
+        if let Some(settings) = settings {
+            config
+                .write_settings_in(
+                    &mut tx,
+                    SettingsScope::Task,
+                    Some(*id),
+                    settings,
+                    SettingsSource::Import,
+                )
+                .await?;
+        }
+    }
+    tx.commit().await?;
+
+    report.imported = true;
+    Ok(ImportOutcome::Completed(report))
+}
+
+fn import_settings(value: Value) -> Result<ForgeProjectSettings> {
+    let settings = parse_settings(value)?;
+    settings.validate()?;
+    Ok(settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use forge_config::{CustomExecutors, OmniConfig, ProjectImportRequest};
+    use serde_json::json;
+
+    use super::*;
+    use crate::services::tests::{insert_task_graph, setup_pool};
+
+    #[tokio::test]
+    async fn agent_tasks_round_trip() {
+        let pool = setup_pool().await;
+        let config = ForgeConfigService::new(pool.clone());
+        let project_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO projects (id, name, git_repo_path) VALUES (?, 'Forge', '/work/forge')",
+        )
+        .bind(project_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO tasks (id, project_id, title, status) VALUES (?, ?, 'Agent run', 'agent')",
+        )
+        .bind(Uuid::new_v4())
+        .bind(project_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let bundle = export_project(
+            &pool,
+            &config,
+            project_id,
+            &ExportOptions {
+                include_secrets: false,
+                include_tasks: true,
+            },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(bundle.tasks[0].status, "agent");
+
+        let outcome = import_project(
+            &pool,
+            &config,
+            ProjectImportRequest {
+                bundle,
+                name: None,
+                git_repo_path: Some("/home/dev/forge".into()),
+                dry_run: false,
+            },
+        )
+        .await
+        .unwrap();
+        let ImportOutcome::Completed(report) = outcome else {
+            panic!("a bundle with agent tasks should import");
+        };
+        let status: String = sqlx::query_scalar("SELECT status FROM tasks WHERE project_id = ?")
+            .bind(report.project_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(status, "agent");
+    }
+
+    #[tokio::test]
+    async fn command_channels_are_not_imported() {
+        let pool = setup_pool().await;
+        let config = ForgeConfigService::new(pool.clone());
+        let project_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO projects (id, name, git_repo_path) VALUES (?, 'Forge', '/work/forge')",
+        )
+        .bind(project_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let settings = ForgeProjectSettings {
+            notification_channels: vec![forge_notifications::NotificationChannelConfig::Command(
+                forge_notifications::CommandChannelConfig {
+                    command: "sh".into(),
+                    args: vec!["-c".into(), "touch /tmp/pwned".into()],
+                    timeout_secs: None,
+                },
+            )],
+            ..Default::default()
+        };
+        config
+            .set_forge_settings(project_id, &settings, SettingsSource::Api)
+            .await
+            .unwrap();
+        let bundle = export_project(&pool, &config, project_id, &ExportOptions::default())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let outcome = import_project(
+            &pool,
+            &config,
+            ProjectImportRequest {
+                bundle,
+                name: None,
+                git_repo_path: Some("/home/dev/forge".into()),
+                dry_run: false,
+            },
+        )
+        .await
+        .unwrap();
+        let ImportOutcome::Invalid(message) = outcome else {
+            panic!("a bundle with a command channel should be rejected");
+        };
+        assert!(message.contains(forge_notifications::COMMAND_CHANNELS_ENV_VAR));
+    }
+
+    #[tokio::test]
+    async fn project_bundles_round_trip_with_new_ids() {
+        let pool = setup_pool().await;
+        let config = ForgeConfigService::new(pool.clone());
+        let project_id = Uuid::new_v4();
+        sqlx::query(
+            "INSERT INTO projects (id, name, git_repo_path, setup_script)
+             VALUES (?, 'Forge', '/work/forge', 'pnpm install')",
+        )
+        .bind(project_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let (task_id, _) = insert_task_graph(&pool, project_id).await;
+        for name in ["bugfix", "feature"] {
+            sqlx::query(
+                "INSERT INTO task_templates (id, project_id, title, template_name)
+                 VALUES (?, ?, ?, ?)",
+            )
+            .bind(Uuid::new_v4())
+            .bind(project_id)
+            .bind(format!("New {name}"))
+            .bind(name)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let omni_settings = ForgeProjectSettings {
+            omni_enabled: Some(true),
+            omni_config: Some(OmniConfig {
+                enabled: true,
+                host: Some("https://omni.local".into()),
+                api_key: Some("omni-key".into()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        config
+            .set_forge_settings(project_id, &omni_settings, SettingsSource::Api)
+            .await
+            .unwrap();
+        let custom_executors: CustomExecutors = serde_json::from_value(json!({
+            "CLAUDE_CODE": { "variants": { "FAST": { "model": "sonnet" } } }
+        }))
+        .unwrap();
+        config
+            .set_custom_executors(project_id, Some(&custom_executors))
+            .await
+            .unwrap();
+        config
+            .set_task_settings(task_id, &omni_settings, SettingsSource::Api)
+            .await
+            .unwrap();
+
+        let bundle = export_project(
+            &pool,
+            &config,
+            project_id,
+            &ExportOptions {
+                include_secrets: false,
+                include_tasks: true,
+            },
+        )
+        .await
+        .unwrap()
+        .expect("project should export");
+        assert_eq!(bundle.task_templates.len(), 2);
+        assert_eq!(bundle.tasks.len(), 1);
+        let settings = bundle.settings.as_ref().unwrap();
+        assert_eq!(settings["omni_config"]["api_key"], "");
+        assert_eq!(
+            settings["schema_version"],
+            forge_config::SETTINGS_SCHEMA_VERSION
+        );
+
+        // The same repository path is taken by the exported project
+        let request = |git_repo_path: Option<&str>, dry_run| ProjectImportRequest {
+            bundle: bundle.clone(),
+            name: None,
+            git_repo_path: git_repo_path.map(str::to_string),
+            dry_run,
+        };
+        let ImportOutcome::Blocked(report) = import_project(&pool, &config, request(None, false))
+            .await
+            .unwrap()
+        else {
+            panic!("import into a used path should be blocked");
+        };
+        assert!(!report.imported);
+
+        let ImportOutcome::Completed(dry_run) =
+            import_project(&pool, &config, request(Some("/home/dev/forge"), true))
+                .await
+                .unwrap()
+        else {
+            panic!("dry run should complete");
+        };
+        assert!(!dry_run.imported);
+
+        let ImportOutcome::Completed(report) =
+            import_project(&pool, &config, request(Some("/home/dev/forge"), false))
+                .await
+                .unwrap()
+        else {
+            panic!("import should complete");
+        };
+        assert!(report.imported);
+        assert_eq!(report.id_map.len(), 4);
+        assert_eq!(report.id_map[&project_id], report.project_id);
+        assert!(
+            report
+                .conflicts
+                .iter()
+                .any(|conflict| conflict.kind == ImportConflictKind::SecretsNotIncluded)
+        );
+
+        let projects: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM projects")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(projects, 2);
+        let imported = config.get_forge_settings(report.project_id).await.unwrap();
+        let omni = imported.omni_config.unwrap();
+        assert_eq!(omni.host.as_deref(), Some("https://omni.local"));
+        assert_eq!(omni.api_key.as_deref(), Some(""));
+        let executors = config
+            .get_custom_executors(report.project_id)
+            .await
+            .unwrap();
+        assert_eq!(executors, custom_executors);
+        let new_task_id = report.id_map[&task_id];
+        assert!(
+            config
+                .get_task_settings(new_task_id)
+                .await
+                .unwrap()
+                .is_some()
+        );
+
+        let history = config
+            .settings_history(
+                Some((
+                    forge_config::SettingsScope::Project,
+                    Some(report.project_id),
+                )),
+                10,
+            )
+            .await
+            .unwrap();
+        assert_eq!(history[0].source, SettingsSource::Import);
+
+        // A failing settings write leaves no half-imported project behind
+        sqlx::query("DROP TABLE forge_task_settings")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(
+            import_project(&pool, &config, request(Some("/home/dev/forge-2"), false))
+                .await
+                .is_err()
+        );
+        let projects: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM projects")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(projects, 2);
+    }
+}
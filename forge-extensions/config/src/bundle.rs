@@ -0,0 +1,132 @@
+//! Project bundles.
+//!
+//! A bundle is a portable copy of a project's setup — the project definition, its
+//! forge settings, custom executors, task templates and optionally its tasks — so
+//! the same repository can be set up on another forge install. Ids in a bundle
+//! are the exporting install's; an import creates new ones and reports how they
+//! map.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::executors::CustomExecutors;
+
+/// Bundle layout written by this version. Settings inside carry their own
+/// `schema_version` and are upgraded on import.
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ProjectBundle {
+    pub format_version: u32,
+    pub exported_at: DateTime<Utc>,
+    /// Whether secret fields of the settings were exported; otherwise they are empty
+    pub includes_secrets: bool,
+    pub project: BundledProject,
+    /// Project settings, as stored
+    #[serde(default)]
+    #[ts(type = "JsonValue | null")]
+    pub settings: Option<Value>,
+    #[serde(default)]
+    pub custom_executors: Option<CustomExecutors>,
+    #[serde(default)]
+    pub task_templates: Vec<BundledTaskTemplate>,
+    /// Only filled when tasks were requested
+    #[serde(default)]
+    pub tasks: Vec<BundledTask>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct BundledProject {
+    pub id: Uuid,
+    pub name: String,
+    pub git_repo_path: String,
+    pub setup_script: Option<String>,
+    pub dev_script: Option<String>,
+    pub cleanup_script: Option<String>,
+    pub copy_files: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct BundledTaskTemplate {
+    pub id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub template_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct BundledTask {
+    pub id: Uuid,
+    pub title: String,
+    pub description: Option<String>,
+    pub status: String,
+    /// Settings that override the project's for this task
+    #[serde(default)]
+    #[ts(type = "JsonValue | null")]
+    pub settings: Option<Value>,
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct ProjectImportRequest {
+    pub bundle: ProjectBundle,
+    /// Name for the new project; the bundle's when unset
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Where the repository is checked out on this machine; the bundle's path
+    /// when unset
+    #[serde(default)]
+    pub git_repo_path: Option<String>,
+    /// Report what an import would do without writing anything
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportConflictKind {
+    /// Another project already uses the repository path; nothing is imported
+    ProjectPathInUse,
+    /// A second template with the same name; it is skipped
+    DuplicateTemplateName,
+    /// The settings were exported without their secrets, which must be entered again
+    SecretsNotIncluded,
+}
+
+impl ImportConflictKind {
+    /// Whether the conflict stops the import.
+    pub fn is_blocking(self) -> bool {
+        matches!(self, Self::ProjectPathInUse)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ImportConflict {
+    pub kind: ImportConflictKind,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProjectImportReport {
+    /// Whether anything was written; false for dry runs and blocked imports
+    pub imported: bool,
+    /// Id of the new project, or the one it would get
+    pub project_id: Uuid,
+    /// Bundle ids of the project, templates and tasks mapped to their new ids
+    pub id_map: BTreeMap<Uuid, Uuid>,
+    pub task_templates: usize,
+    pub tasks: usize,
+    pub conflicts: Vec<ImportConflict>,
+}
+
+impl ProjectImportReport {
+    pub fn is_blocked(&self) -> bool {
+        self.conflicts
+            .iter()
+            .any(|conflict| conflict.kind.is_blocking())
+    }
+}
@@ -0,0 +1,494 @@
+//! Custom executors.
+//!
+//! A project's overlay on the upstream executor profiles. Per executor it can
+//! add variants built on an upstream variant, pick the variant used when an
+//! attempt names none, and set the model, extra CLI arguments and environment
+//! of the executor. Attempts of the project name these variants like upstream
+//! ones; [`CustomExecutors::resolve`] turns the request into the upstream
+//! variant to start from and the overrides to apply to it.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use anyhow::{Context, Result, anyhow, bail};
+use executors::executors::BaseCodingAgent;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Overlay of each executor, keyed by its upstream name (`CLAUDE_CODE`, `CODEX`, ...).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[serde(transparent)]
+pub struct CustomExecutors(pub BTreeMap<String, ExecutorOverlay>);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct ExecutorOverlay {
+    /// Variant used when an attempt names none; the upstream default when unset
+    #[serde(default)]
+    pub default_variant: Option<String>,
+    /// Applied to every variant of the executor, under the variant's own
+    #[serde(flatten)]
+    pub overrides: ExecutorOverrides,
+    /// Variants of the project, by name
+    #[serde(default)]
+    pub variants: BTreeMap<String, ExecutorVariant>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct ExecutorVariant {
+    /// Upstream variant this one builds on; the executor's default when unset
+    #[serde(default)]
+    pub base_variant: Option<String>,
+    #[serde(flatten)]
+    pub overrides: ExecutorOverrides,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+pub struct ExecutorOverrides {
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Appended to the executor's command line
+    #[serde(default)]
+    pub additional_params: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+impl ExecutorOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.model.is_none() && self.additional_params.is_empty() && self.env.is_empty()
+    }
+
+    /// `other` on top of these: its model wins, arguments are appended and
+    /// variables replace those of the same name.
+    fn merged(&self, other: &ExecutorOverrides) -> ExecutorOverrides {
+        let mut env = self.env.clone();
+        env.extend(other.env.clone());
+        ExecutorOverrides {
+            model: other.model.clone().or_else(|| self.model.clone()),
+            additional_params: self
+                .additional_params
+                .iter()
+                .chain(&other.additional_params)
+                .cloned()
+                .collect(),
+            env,
+        }
+    }
+
+    /// Apply to a serialized upstream profile, `{ "<EXECUTOR>": { ... } }`.
+    pub fn apply(&self, profile: &mut Value) -> Result<()> {
+        let fields = profile
+            .as_object_mut()
+            .and_then(|profile| profile.values_mut().next())
+            .and_then(Value::as_object_mut)
+            .ok_or_else(|| anyhow!("executor profile is not an object"))?;
+
+        if let Some(model) = &self.model {
+            fields.insert("model".into(), Value::from(model.as_str()));
+        }
+        if !self.additional_params.is_empty() {
+            let params = fields
+                .entry("additional_params")
+                .or_insert_with(|| Value::Array(Vec::new()));
+            if params.is_null() {
+                *params = Value::Array(Vec::new());
+            }
+            params
+                .as_array_mut()
+                .context("additional_params of the executor profile is not a list")?
+                .extend(
+                    self.additional_params
+                        .iter()
+                        .map(|param| Value::from(param.as_str())),
+                );
+        }
+        if !self.env.is_empty() {
+            let env = fields
+                .entry("env")
+                .or_insert_with(|| Value::Object(Default::default()));
+            if env.is_null() {
+                *env = Value::Object(Default::default());
+            }
+            let env = env
+                .as_object_mut()
+                .context("env of the executor profile is not an object")?;
+            for (name, value) in &self.env {
+                env.insert(name.clone(), Value::from(value.as_str()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Profile an attempt starts with, after the project's overlay.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResolvedExecutor {
+    /// Upstream variant to start from; the default when unset
+    pub base_variant: Option<String>,
+    /// Variant the attempt asked for, or the project's default one
+    pub variant: Option<String>,
+    pub overrides: ExecutorOverrides,
+}
+
+impl ResolvedExecutor {
+    /// Name the overridden profile of `project_id` is started under, e.g.
+    /// `FORGE_1A2B3C4D_FAST`.
+    pub fn profile_variant(&self, project_id: Uuid) -> String {
+        let project = project_id.simple().to_string();
+        format!(
+            "FORGE_{}_{}",
+            project[..8].to_ascii_uppercase(),
+            self.variant.as_deref().unwrap_or("DEFAULT")
+        )
+    }
+}
+
+impl CustomExecutors {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Reject overlays of unknown executors and names the upstream profiles
+    /// could not hold.
+    pub fn validate(&self) -> Result<()> {
+        for (executor, overlay) in &self.0 {
+            if BaseCodingAgent::from_str(executor).is_err() {
+                bail!("Unknown executor '{executor}'");
+            }
+            if let Some(variant) = &overlay.default_variant {
+                validate_variant_name(executor, variant)?;
+            }
+            validate_overrides(executor, &overlay.overrides)?;
+            for (name, variant) in &overlay.variants {
+                validate_variant_name(executor, name)?;
+                if let Some(base) = &variant.base_variant {
+                    validate_variant_name(executor, base)?;
+                }
+                validate_overrides(&format!("{executor} {name}"), &variant.overrides)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Reject upstream variants the profiles do not define: the base of every
+    /// project variant, and a default variant the project does not define
+    /// itself. `has_variant(executor, variant)` looks a variant up upstream.
+    pub fn validate_base_variants(
+        &self,
+        has_variant: impl Fn(BaseCodingAgent, &str) -> bool,
+    ) -> Result<()> {
+        for (executor, overlay) in &self.0 {
+            let agent = BaseCodingAgent::from_str(executor)
+                .map_err(|_| anyhow!("Unknown executor '{executor}'"))?;
+            let upstream_default = overlay
+                .default_variant
+                .as_deref()
+                .filter(|variant| !overlay.variants.contains_key(*variant));
+            let bases = overlay
+                .variants
+                .values()
+                .filter_map(|variant| variant.base_variant.as_deref());
+            for base in upstream_default.into_iter().chain(bases) {
+                if !has_variant(agent, base) {
+                    bail!("{executor} has no upstream variant '{base}'");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Profile for an attempt asking for `variant` of `executor`. Variants the
+    /// project does not define are upstream ones and only get the executor's
+    /// overrides.
+    pub fn resolve(&self, executor: &str, variant: Option<&str>) -> ResolvedExecutor {
+        let Some(overlay) = self.0.get(executor) else {
+            return ResolvedExecutor {
+                base_variant: variant.map(str::to_string),
+                variant: variant.map(str::to_string),
+                overrides: ExecutorOverrides::default(),
+            };
+        };
+
+        let variant = variant.or(overlay.default_variant.as_deref());
+        match variant.and_then(|name| overlay.variants.get(name)) {
+            Some(custom) => ResolvedExecutor {
+                base_variant: custom.base_variant.clone(),
+                variant: variant.map(str::to_string),
+                overrides: overlay.overrides.merged(&custom.overrides),
+            },
+            None => ResolvedExecutor {
+                base_variant: variant.map(str::to_string),
+                variant: variant.map(str::to_string),
+                overrides: overlay.overrides.clone(),
+            },
+        }
+    }
+}
+
+/// Custom executors from a stored value, which may still use the free-form
+/// layout saved before they were typed: variants in the upstream profile
+/// layout, `{ "<EXECUTOR>": { "<VARIANT>": { "<EXECUTOR>": { ... } } } }`,
+/// optionally under `executors`. Their model, arguments and environment are
+/// kept; other profile fields have no place in the overlay and are dropped.
+pub fn upgrade_custom_executors(value: Value) -> Result<CustomExecutors> {
+    let value = match value {
+        Value::Object(mut map) if map.len() == 1 && map.contains_key("executors") => {
+            map.remove("executors").unwrap_or_default()
+        }
+        value => value,
+    };
+    let Value::Object(executors) = value else {
+        bail!("Custom executors must be an object keyed by executor");
+    };
+
+    let executors = executors
+        .into_iter()
+        .map(|(executor, overlay)| {
+            let overlay = if is_profile_layout(&executor, &overlay) {
+                overlay_from_profiles(&executor, overlay)
+            } else {
+                overlay
+            };
+            (executor, overlay)
+        })
+        .collect();
+    let custom_executors: CustomExecutors = serde_json::from_value(Value::Object(executors))
+        .context("Custom executors do not match the expected layout")?;
+    custom_executors.validate()?;
+    Ok(custom_executors)
+}
+
+/// Whether `overlay` holds variants in the upstream profile layout.
+fn is_profile_layout(executor: &str, overlay: &Value) -> bool {
+    overlay.as_object().is_some_and(|variants| {
+        !variants.is_empty()
+            && variants.values().all(|profile| {
+                profile
+                    .as_object()
+                    .is_some_and(|profile| profile.len() == 1 && profile.contains_key(executor))
+            })
+    })
+}
+
+fn overlay_from_profiles(executor: &str, profiles: Value) -> Value {
+    let mut overlay = serde_json::Map::new();
+    let mut variants = serde_json::Map::new();
+    for (variant, mut profile) in profiles.as_object().cloned().unwrap_or_default() {
+        let fields = profile
+            .get_mut(executor)
+            .and_then(Value::as_object_mut)
+            .map(std::mem::take)
+            .unwrap_or_default();
+        let overrides: serde_json::Map<String, Value> = ["model", "additional_params", "env"]
+            .into_iter()
+            .filter_map(|key| {
+                fields
+                    .get(key)
+                    .filter(|value| !value.is_null())
+                    .map(|value| (key.to_string(), value.clone()))
+            })
+            .collect();
+        // The default variant's settings apply to the whole executor
+        if variant == "DEFAULT" {
+            overlay.extend(overrides);
+        } else {
+            variants.insert(variant, Value::Object(overrides));
+        }
+    }
+    overlay.insert("variants".into(), Value::Object(variants));
+    Value::Object(overlay)
+}
+
+fn validate_variant_name(executor: &str, name: &str) -> Result<()> {
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+    {
+        bail!("Variant '{name}' of {executor} must be upper case letters, digits and underscores");
+    }
+    Ok(())
+}
+
+fn validate_overrides(owner: &str, overrides: &ExecutorOverrides) -> Result<()> {
+    if overrides
+        .model
+        .as_ref()
+        .is_some_and(|model| model.trim().is_empty())
+    {
+        bail!("{owner}: model must not be empty");
+    }
+    if overrides
+        .additional_params
+        .iter()
+        .any(|param| param.trim().is_empty())
+    {
+        bail!("{owner}: additional parameters must not be empty");
+    }
+    if let Some(name) = overrides.env.keys().find(|name| !is_env_name(name)) {
+        bail!("{owner}: '{name}' is not a valid environment variable name");
+    }
+    Ok(())
+}
+
+fn is_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn overlay() -> CustomExecutors {
+        serde_json::from_value(json!({
+            "CLAUDE_CODE": {
+                "default_variant": "FAST",
+                "additional_params": ["--verbose"],
+                "env": { "FORGE_PROJECT": "demo" },
+                "variants": {
+                    "FAST": {
+                        "base_variant": "PLAN",
+                        "model": "sonnet",
+                        "additional_params": ["--max-turns", "20"],
+                        "env": { "FORGE_PROJECT": "fast" }
+                    }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn overlays_are_validated_against_known_executors() {
+        overlay().validate().unwrap();
+
+        let unknown: CustomExecutors =
+            serde_json::from_value(json!({ "CLAUDE": { "model": "sonnet" } })).unwrap();
+        assert!(
+            unknown
+                .validate()
+                .unwrap_err()
+                .to_string()
+                .contains("Unknown executor 'CLAUDE'")
+        );
+
+        let mut lower_case = overlay();
+        lower_case.0.get_mut("CLAUDE_CODE").unwrap().default_variant = Some("fast".into());
+        assert!(lower_case.validate().is_err());
+
+        let mut bad_env = overlay();
+        bad_env
+            .0
+            .get_mut("CLAUDE_CODE")
+            .unwrap()
+            .overrides
+            .env
+            .insert("1PATH".into(), "x".into());
+        assert!(bad_env.validate().is_err());
+    }
+
+    #[test]
+    fn base_variants_must_exist_upstream() {
+        let upstream = |agent: BaseCodingAgent, variant: &str| {
+            agent == BaseCodingAgent::ClaudeCode && ["DEFAULT", "PLAN"].contains(&variant)
+        };
+        overlay().validate_base_variants(upstream).unwrap();
+
+        let mut missing_base = overlay();
+        missing_base
+            .0
+            .get_mut("CLAUDE_CODE")
+            .unwrap()
+            .variants
+            .get_mut("FAST")
+            .unwrap()
+            .base_variant = Some("ROUTER".into());
+        let error = missing_base.validate_base_variants(upstream).unwrap_err();
+        assert!(error.to_string().contains("no upstream variant 'ROUTER'"));
+
+        // A default variant outside the project's own must be an upstream one
+        let mut missing_default = overlay();
+        missing_default
+            .0
+            .get_mut("CLAUDE_CODE")
+            .unwrap()
+            .default_variant = Some("SLOW".into());
+        assert!(missing_default.validate_base_variants(upstream).is_err());
+    }
+
+    #[test]
+    fn legacy_profile_layout_is_upgraded() {
+        let upgraded = upgrade_custom_executors(json!({
+            "executors": {
+                "CLAUDE_CODE": {
+                    "DEFAULT": { "CLAUDE_CODE": { "model": "opus", "plan": false } },
+                    "FAST": { "CLAUDE_CODE": { "model": "sonnet", "additional_params": ["--verbose"] } }
+                }
+            }
+        }))
+        .unwrap();
+        let claude = &upgraded.0["CLAUDE_CODE"];
+        assert_eq!(claude.overrides.model.as_deref(), Some("opus"));
+        assert_eq!(
+            claude.variants["FAST"].overrides.model.as_deref(),
+            Some("sonnet")
+        );
+        assert_eq!(
+            claude.variants["FAST"].overrides.additional_params,
+            ["--verbose"]
+        );
+
+        // Already typed overlays pass through unchanged
+        assert_eq!(
+            upgrade_custom_executors(serde_json::to_value(overlay()).unwrap()).unwrap(),
+            overlay()
+        );
+        assert!(upgrade_custom_executors(json!(["CLAUDE_CODE"])).is_err());
+        assert!(upgrade_custom_executors(json!({ "CLAUDE": {} })).is_err());
+    }
+
+    #[test]
+    fn profiles_resolve_through_the_overlay() {
+        let custom = overlay();
+
+        let fast = custom.resolve("CLAUDE_CODE", None);
+        assert_eq!(fast.base_variant.as_deref(), Some("PLAN"));
+        assert_eq!(fast.variant.as_deref(), Some("FAST"));
+        assert_eq!(fast.overrides.model.as_deref(), Some("sonnet"));
+        assert_eq!(
+            fast.overrides.additional_params,
+            ["--verbose", "--max-turns", "20"]
+        );
+        assert_eq!(fast.overrides.env["FORGE_PROJECT"], "fast");
+
+        let upstream = custom.resolve("CLAUDE_CODE", Some("APPROVALS"));
+        assert_eq!(upstream.base_variant.as_deref(), Some("APPROVALS"));
+        assert_eq!(upstream.overrides.additional_params, ["--verbose"]);
+
+        let plain = custom.resolve("CODEX", Some("HIGH"));
+        assert_eq!(plain.base_variant.as_deref(), Some("HIGH"));
+        assert!(plain.overrides.is_empty());
+
+        let project_id = Uuid::parse_str("1a2b3c4d-0000-0000-0000-000000000000").unwrap();
+        assert_eq!(fast.profile_variant(project_id), "FORGE_1A2B3C4D_FAST");
+
+        let mut profile = json!({ "CLAUDE_CODE": { "model": null, "additional_params": null } });
+        fast.overrides.apply(&mut profile).unwrap();
+        assert_eq!(
+            profile,
+            json!({ "CLAUDE_CODE": {
+                "model": "sonnet",
+                "additional_params": ["--verbose", "--max-turns", "20"],
+                "env": { "FORGE_PROJECT": "fast" }
+            } })
+        );
+    }
+}
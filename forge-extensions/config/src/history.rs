@@ -0,0 +1,198 @@
+//! Settings history.
+//!
+//! Every write of global, project or task settings is recorded in
+//! `forge_settings_history` with the value before and after, and where the write
+//! came from. Secrets are redacted in both values, so restoring an entry keeps the
+//! secrets currently stored.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::SqliteConnection;
+use ts_rs::TS;
+use uuid::Uuid;
+
+use crate::secrets::redact_secrets;
+
+/// Header a client sets to tell where a settings write came from.
+pub const SOURCE_HEADER: &str = "X-Forge-Settings-Source";
+
+/// Settings level an entry belongs to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum SettingsScope {
+    Global,
+    Project,
+    Task,
+}
+
+impl SettingsScope {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Global => "global",
+            Self::Project => "project",
+            Self::Task => "task",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "global" => Some(Self::Global),
+            "project" => Some(Self::Project),
+            "task" => Some(Self::Task),
+            _ => None,
+        }
+    }
+}
+
+/// Where a settings write came from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum SettingsSource {
+    #[default]
+    Api,
+    Mcp,
+    Import,
+}
+
+impl SettingsSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Api => "api",
+            Self::Mcp => "mcp",
+            Self::Import => "import",
+        }
+    }
+
+    /// Source named by a [`SOURCE_HEADER`] value; anything unknown is the API.
+    pub fn from_header(value: Option<&str>) -> Self {
+        match value.map(str::trim) {
+            Some(source) if source.eq_ignore_ascii_case("mcp") => Self::Mcp,
+            Some(source) if source.eq_ignore_ascii_case("import") => Self::Import,
+            _ => Self::Api,
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        Self::from_header(Some(value))
+    }
+}
+
+#[derive(Clone, Debug, Serialize, TS)]
+pub struct SettingsHistoryEntry {
+    #[ts(type = "number")]
+    pub id: i64,
+    pub scope: SettingsScope,
+    /// Project or task id; `None` for the global settings
+    pub scope_id: Option<Uuid>,
+    /// Settings before the write, `None` when there were none
+    #[ts(type = "Record<string, unknown> | null")]
+    pub old_value: Option<Value>,
+    #[ts(type = "Record<string, unknown>")]
+    pub new_value: Value,
+    pub source: SettingsSource,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Record a write; `old` and `new` are the decrypted values.
+pub(crate) async fn record(
+    conn: &mut SqliteConnection,
+    scope: SettingsScope,
+    scope_id: Option<Uuid>,
+    old: Option<&Value>,
+    new: &Value,
+    source: SettingsSource,
+) -> Result<()> {
+    let redacted = |value: &Value| {
+        let mut value = value.clone();
+        redact_secrets(&mut value);
+        value.to_string()
+    };
+
+    sqlx::query(
+        "INSERT INTO forge_settings_history (scope, scope_id, old_value, new_value, source)
+         VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(scope.as_str())
+    .bind(scope_id)
+    .bind(old.map(redacted))
+    .bind(redacted(new))
+    .bind(source.as_str())
+    .execute(conn)
+    .await?;
+
+    Ok(())
+}
+
+type HistoryRow = (
+    i64,
+    String,
+    Option<Uuid>,
+    Option<String>,
+    String,
+    String,
+    DateTime<Utc>,
+);
+
+const SELECT_HISTORY: &str = "SELECT id, scope, scope_id, old_value, new_value, source, created_at
+                                FROM forge_settings_history";
+
+/// Entries for one scope, newest first. Without a scope every entry is listed.
+pub(crate) async fn list(
+    conn: &mut SqliteConnection,
+    scope: Option<(SettingsScope, Option<Uuid>)>,
+    limit: i64,
+) -> Result<Vec<SettingsHistoryEntry>> {
+    let rows: Vec<HistoryRow> = match scope {
+        Some((scope, scope_id)) => {
+            sqlx::query_as(&format!(
+                "{SELECT_HISTORY} WHERE scope = ? AND scope_id IS ? ORDER BY id DESC LIMIT ?"
+            ))
+            .bind(scope.as_str())
+            .bind(scope_id)
+            .bind(limit)
+            .fetch_all(conn)
+            .await?
+        }
+        None => {
+            sqlx::query_as(&format!("{SELECT_HISTORY} ORDER BY id DESC LIMIT ?"))
+                .bind(limit)
+                .fetch_all(conn)
+                .await?
+        }
+    };
+
+    rows.into_iter().map(entry_from_row).collect()
+}
+
+pub(crate) async fn get(
+    conn: &mut SqliteConnection,
+    id: i64,
+) -> Result<Option<SettingsHistoryEntry>> {
+    let row: Option<HistoryRow> = sqlx::query_as(&format!("{SELECT_HISTORY} WHERE id = ?"))
+        .bind(id)
+        .fetch_optional(conn)
+        .await?;
+
+    row.map(entry_from_row).transpose()
+}
+
+fn entry_from_row(
+    (id, scope, scope_id, old_value, new_value, source, created_at): HistoryRow,
+) -> Result<SettingsHistoryEntry> {
+    let scope = SettingsScope::parse(&scope)
+        .ok_or_else(|| anyhow::anyhow!("Unknown settings scope '{scope}'"))?;
+
+    Ok(SettingsHistoryEntry {
+        id,
+        scope,
+        scope_id,
+        old_value: old_value
+            .map(|value| serde_json::from_str(&value))
+            .transpose()?,
+        new_value: serde_json::from_str(&new_value)?,
+        source: SettingsSource::parse(&source),
+        created_at,
+    })
+}
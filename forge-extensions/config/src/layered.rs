@@ -0,0 +1,273 @@
+//! Layered settings.
+//!
+//! Settings are stored at up to three levels — global, project and task — and
+//! merged field by field, the more specific level winning. A field a level leaves
+//! unset (`null`, an empty string, list or map) is inherited, so a project that
+//! only sets a recipient keeps the global Omni host and API key. Nested objects
+//! such as `omni_config` are merged the same way; lists are replaced as a whole.
+
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use forge_notifications::{NotificationChannelConfig, NotificationRule, NotificationType};
+use forge_omni::OmniConfig;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use ts_rs::TS;
+
+use crate::schema::upgrade_settings;
+use crate::types::ForgeProjectSettings;
+
+/// Level a setting was resolved from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigOrigin {
+    Default,
+    Global,
+    Project,
+    Task,
+}
+
+impl ConfigOrigin {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Global => "global",
+            Self::Project => "project",
+            Self::Task => "task",
+        }
+    }
+}
+
+/// Settings merged over every level, with the level each field came from.
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct EffectiveSettings {
+    pub settings: ForgeProjectSettings,
+    /// Origin by dotted field path, e.g. `omni_config.host`. A nested object
+    /// nobody set is listed once, under its own name.
+    pub origins: BTreeMap<String, ConfigOrigin>,
+    /// The stored levels, least specific first
+    #[serde(skip)]
+    #[ts(skip)]
+    levels: Vec<(ConfigOrigin, ForgeProjectSettings)>,
+}
+
+impl EffectiveSettings {
+    /// Merge `levels`, given least specific first as raw stored JSON. Each level is
+    /// upgraded to the current schema first; one that does not parse is an error.
+    pub fn resolve(levels: Vec<(ConfigOrigin, Value)>) -> Result<Self> {
+        let mut merged = serde_json::to_value(ForgeProjectSettings::default())?;
+        let mut origins = BTreeMap::new();
+        record_origins(&merged, "", ConfigOrigin::Default, &mut origins);
+
+        let mut typed = Vec::with_capacity(levels.len());
+        for (origin, value) in levels {
+            let invalid = || format!("Stored {} settings are invalid", origin.name());
+            let value = upgrade_settings(value).with_context(invalid)?;
+            let settings = serde_json::from_value::<ForgeProjectSettings>(value.clone())
+                .with_context(invalid)?;
+            if let (Some(target), Some(overlay)) = (merged.as_object_mut(), value.as_object()) {
+                merge_object(target, overlay, "", origin, &mut origins);
+            }
+            typed.push((origin, settings));
+        }
+
+        let settings = serde_json::from_value(merged).context("Merged settings are invalid")?;
+        Ok(Self {
+            settings,
+            origins,
+            levels: typed,
+        })
+    }
+
+    /// Omni settings, switched on by `omni_enabled`.
+    pub fn omni_config(&self) -> OmniConfig {
+        let mut config = self.settings.omni_config.clone().unwrap_or_default();
+        config.enabled = self.settings.omni_enabled.unwrap_or_default();
+        config
+    }
+
+    /// Channels that receive notifications; Omni only when none are configured.
+    pub fn notification_channels(&self) -> Vec<NotificationChannelConfig> {
+        let mut channels = self.settings.notification_channels.clone();
+        if channels.is_empty() {
+            channels.push(NotificationChannelConfig::Omni);
+        }
+        channels
+    }
+
+    pub fn notification_rules(&self) -> Vec<NotificationRule> {
+        self.settings.notification_rules.clone()
+    }
+
+    /// Events that queue notifications; execution exits only when none are configured.
+    pub fn notification_types(&self) -> Vec<NotificationType> {
+        let mut types = self.settings.notification_types.clone();
+        if types.is_empty() {
+            types.push(NotificationType::ExecutionCompleted);
+        }
+        types
+    }
+
+    /// Template for an event. Levels are searched most specific first; at each
+    /// level a template for the event wins over `notification_template`, which
+    /// only applies to execution exits.
+    pub fn notification_template(&self, notification_type: NotificationType) -> Option<String> {
+        self.levels
+            .iter()
+            .rev()
+            .find_map(|(_, settings)| template_for(settings, notification_type))
+    }
+}
+
+fn template_for(
+    settings: &ForgeProjectSettings,
+    notification_type: NotificationType,
+) -> Option<String> {
+    settings
+        .notification_templates
+        .get(&notification_type)
+        .cloned()
+        .or_else(|| {
+            (notification_type == NotificationType::ExecutionCompleted)
+                .then(|| settings.notification_template.clone())
+                .flatten()
+        })
+}
+
+/// A value that leaves the field to the levels below.
+fn is_unset(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::String(text) => text.is_empty(),
+        Value::Array(items) => items.is_empty(),
+        Value::Object(fields) => fields.is_empty(),
+        _ => false,
+    }
+}
+
+fn merge_object(
+    target: &mut Map<String, Value>,
+    overlay: &Map<String, Value>,
+    prefix: &str,
+    origin: ConfigOrigin,
+    origins: &mut BTreeMap<String, ConfigOrigin>,
+) {
+    for (key, value) in overlay {
+        if is_unset(value) {
+            continue;
+        }
+        let path = join(prefix, key);
+
+        match (target.get_mut(key), value) {
+            (Some(Value::Object(current)), Value::Object(nested)) => {
+                merge_object(current, nested, &path, origin, origins);
+            }
+            _ => {
+                // The field replaces whatever was inherited below it
+                origins.retain(|existing, _| {
+                    existing != &path && !existing.starts_with(&format!("{path}."))
+                });
+                record_origins(value, &path, origin, origins);
+                target.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// Record `origin` for every set field of `value`, or for `path` itself when it
+/// is not an object.
+fn record_origins(
+    value: &Value,
+    path: &str,
+    origin: ConfigOrigin,
+    origins: &mut BTreeMap<String, ConfigOrigin>,
+) {
+    match value {
+        Value::Object(fields) if !fields.is_empty() => {
+            for (key, nested) in fields {
+                if path.is_empty() || !is_unset(nested) {
+                    record_origins(nested, &join(path, key), origin, origins);
+                }
+            }
+        }
+        _ => {
+            origins.insert(path.to_string(), origin);
+        }
+    }
+}
+
+fn join(prefix: &str, key: &str) -> String {
+    if prefix.is_empty() {
+        key.to_string()
+    } else {
+        format!("{prefix}.{key}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn merges_fields_and_reports_their_origin() {
+        let global = json!({
+            "omni_enabled": true,
+            "omni_config": {
+                "enabled": true,
+                "host": "https://omni.global",
+                "api_key": "global-key",
+                "instance": "forge",
+                "recipient": "+15550000000",
+            },
+            "notification_template": "{{ title }}",
+        });
+        let project = json!({
+            "omni_enabled": true,
+            "omni_config": {
+                "enabled": false,
+                "host": null,
+                "recipient": "+15551111111",
+                "recipients": [],
+            },
+            "notification_channels": [],
+        });
+        let task = json!({
+            "notification_types": ["merge_conflict"],
+        });
+
+        let effective = EffectiveSettings::resolve(vec![
+            (ConfigOrigin::Global, global),
+            (ConfigOrigin::Project, project),
+            (ConfigOrigin::Task, task),
+        ])
+        .unwrap();
+
+        let omni = effective.omni_config();
+        assert!(omni.enabled);
+        assert_eq!(omni.host.as_deref(), Some("https://omni.global"));
+        assert_eq!(omni.api_key.as_deref(), Some("global-key"));
+        assert_eq!(omni.recipient.as_deref(), Some("+15551111111"));
+        assert_eq!(
+            effective.notification_types(),
+            vec![NotificationType::MergeConflict]
+        );
+        assert!(matches!(
+            effective.notification_channels().as_slice(),
+            [NotificationChannelConfig::Omni]
+        ));
+
+        let origin = |path: &str| effective.origins.get(path).copied();
+        assert_eq!(origin("omni_enabled"), Some(ConfigOrigin::Project));
+        assert_eq!(origin("omni_config.host"), Some(ConfigOrigin::Global));
+        assert_eq!(origin("omni_config.recipient"), Some(ConfigOrigin::Project));
+        assert_eq!(origin("notification_types"), Some(ConfigOrigin::Task));
+        assert_eq!(origin("notification_channels"), Some(ConfigOrigin::Default));
+        assert_eq!(origin("omni_config"), None);
+        assert_eq!(
+            effective.notification_template(NotificationType::ExecutionCompleted),
+            Some("{{ title }}".into())
+        );
+    }
+}
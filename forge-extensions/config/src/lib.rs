@@ -3,10 +3,24 @@
 //! This module contains forge-specific configuration functionality.
 //! For Task 2, this focuses on project-level config management and Omni integration.
 
+pub mod bundle;
+pub mod executors;
+pub mod history;
+pub mod layered;
+pub mod schema;
+pub mod secrets;
 pub mod service;
 pub mod types;
 
-pub use service::ForgeConfigService;
+pub use bundle::{
+    ImportConflict, ImportConflictKind, ProjectBundle, ProjectImportReport, ProjectImportRequest,
+};
+pub use executors::{CustomExecutors, ExecutorOverlay, ExecutorOverrides, ExecutorVariant};
+pub use history::{SettingsHistoryEntry, SettingsScope, SettingsSource};
+pub use layered::{ConfigOrigin, EffectiveSettings};
+pub use schema::SETTINGS_SCHEMA_VERSION;
+pub use secrets::{KeySource, SecretCipher};
+pub use service::{ForgeConfigService, SettingsCheck};
 pub use types::*;
 
 // Re-export upstream config primitives so downstream code can switch to forge-config without churn
@@ -17,3 +31,6 @@ pub use services::services::config::{
 
 // Re-export Omni config for compatibility
 pub use forge_omni::{OmniConfig, RecipientType};
+
+// Re-export notification channel settings alongside the Omni config
+pub use forge_notifications::NotificationChannelConfig;
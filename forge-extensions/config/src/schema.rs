@@ -0,0 +1,136 @@
+//! Settings schema versions.
+//!
+//! Stored settings carry a `schema_version`. Payloads written by an older forge
+//! (or without a version at all, which counts as version 1) are upgraded step by
+//! step before they are read, and a payload that still does not match
+//! [`ForgeProjectSettings`] is reported instead of being replaced by defaults.
+
+use anyhow::{Context, Result, bail};
+use serde_json::{Map, Value};
+
+use crate::types::ForgeProjectSettings;
+
+pub const SETTINGS_SCHEMA_VERSION: u64 = 2;
+
+const VERSION_FIELD: &str = "schema_version";
+
+/// Upgrade from version `n + 1` to `n + 2`, by index.
+type Upgrade = fn(&mut Map<String, Value>);
+
+const UPGRADES: &[Upgrade] = &[upgrade_v1_to_v2];
+
+/// Version 1 payloads were written by hand or by early releases: `omni_config`
+/// could lack `enabled`, and lists could be `null`.
+fn upgrade_v1_to_v2(settings: &mut Map<String, Value>) {
+    let omni_enabled = settings
+        .get("omni_enabled")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    if let Some(Value::Object(omni)) = settings.get_mut("omni_config") {
+        omni.entry("enabled").or_insert(Value::Bool(omni_enabled));
+        omni.retain(|key, value| !(key == "recipients" && value.is_null()));
+    }
+
+    for list in [
+        "notification_channels",
+        "notification_rules",
+        "notification_types",
+        "notification_templates",
+    ] {
+        if settings.get(list).is_some_and(Value::is_null) {
+            settings.remove(list);
+        }
+    }
+}
+
+/// Version of a stored payload.
+pub fn schema_version(value: &Value) -> u64 {
+    value
+        .get(VERSION_FIELD)
+        .and_then(Value::as_u64)
+        .unwrap_or(1)
+}
+
+/// Bring a payload to the current version. The version field is removed, so the
+/// result holds settings only.
+pub fn upgrade_settings(mut value: Value) -> Result<Value> {
+    let Some(settings) = value.as_object_mut() else {
+        bail!("Settings must be a JSON object");
+    };
+
+    let version = settings
+        .remove(VERSION_FIELD)
+        .map(|version| {
+            version
+                .as_u64()
+                .filter(|version| *version >= 1)
+                .with_context(|| format!("Invalid {VERSION_FIELD} {version}"))
+        })
+        .transpose()?
+        .unwrap_or(1);
+    if version > SETTINGS_SCHEMA_VERSION {
+        bail!(
+            "Settings have schema version {version}, newer than the supported {SETTINGS_SCHEMA_VERSION}"
+        );
+    }
+
+    for upgrade in &UPGRADES[(version - 1) as usize..] {
+        upgrade(settings);
+    }
+
+    Ok(value)
+}
+
+/// Upgrade a payload and read it as settings.
+pub fn parse_settings(value: Value) -> Result<ForgeProjectSettings> {
+    let value = upgrade_settings(value)?;
+    serde_json::from_value(value).context("Settings do not match the expected schema")
+}
+
+/// Mark a payload about to be stored with the current version.
+pub fn stamp_version(value: &mut Value) {
+    if let Some(settings) = value.as_object_mut() {
+        settings.insert(VERSION_FIELD.into(), SETTINGS_SCHEMA_VERSION.into());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn upgrades_unversioned_payloads() {
+        let legacy = json!({
+            "omni_enabled": true,
+            "omni_config": { "host": "https://omni.local", "recipients": null },
+            "notification_channels": null,
+        });
+
+        let settings = parse_settings(legacy).unwrap();
+        assert!(settings.omni_config.unwrap().enabled);
+        assert!(settings.notification_channels.is_empty());
+
+        let mut current = serde_json::to_value(ForgeProjectSettings::default()).unwrap();
+        stamp_version(&mut current);
+        assert_eq!(schema_version(&current), SETTINGS_SCHEMA_VERSION);
+        let upgraded = upgrade_settings(current).unwrap();
+        assert!(upgraded.get(VERSION_FIELD).is_none());
+    }
+
+    #[test]
+    fn rejects_unknown_versions_and_shapes() {
+        let newer = json!({ "schema_version": SETTINGS_SCHEMA_VERSION + 1 });
+        assert!(parse_settings(newer).is_err());
+
+        let typo = json!({ "omni_enabled": "yes" });
+        let error = parse_settings(typo).unwrap_err();
+        assert!(format!("{error:#}").contains("invalid type"));
+
+        assert!(parse_settings(json!([])).is_err());
+
+        let misspelled = json!({ "omni_enabled": true, "notification_tempalte": "{{ title }}" });
+        let error = parse_settings(misspelled).unwrap_err();
+        assert!(format!("{error:#}").contains("unknown field `notification_tempalte`"));
+    }
+}
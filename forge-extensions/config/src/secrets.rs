@@ -0,0 +1,529 @@
+//! Secrets at rest.
+//!
+//! Secret fields of the forge settings (the Omni API key, the inbound secret and the
+//! credentials of notification channels) are stored encrypted with AES-256-GCM as
+//! `enc:v1:<base64 nonce + ciphertext>`. The key is read from [`KEY_ENV_VAR`] or from
+//! a key file generated on first start. API responses show [`REDACTED`] instead of
+//! the secret, and saving settings that still contain it keeps the stored value.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{Context, Result, anyhow};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use serde::Serialize;
+use serde_json::Value;
+
+use forge_omni::OmniConfig;
+
+use crate::types::InvalidSettings;
+
+/// Base64-encoded 32-byte key; takes precedence over the key file.
+pub const KEY_ENV_VAR: &str = "FORGE_SECRET_KEY";
+pub const KEY_FILE_NAME: &str = "forge-secret.key";
+/// Placeholder returned by the API in place of a stored secret.
+pub const REDACTED: &str = "********";
+
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+const NONCE_LEN: usize = 12;
+
+/// Secret field of each notification channel type, and the fields naming the
+/// endpoint the secret belongs to.
+const CHANNEL_SECRET_FIELDS: &[(&str, &str, &[&str])] = &[
+    ("slack", "webhook_url", &["username", "channel"]),
+    ("email", "password", &["smtp_host", "smtp_port", "username"]),
+    ("webhook", "secret", &["url"]),
+];
+
+/// Where the key in use was loaded from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeySource {
+    Env,
+    File,
+}
+
+#[derive(Clone)]
+pub struct SecretCipher {
+    key: Key<Aes256Gcm>,
+}
+
+impl fmt::Debug for SecretCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SecretCipher(..)")
+    }
+}
+
+impl SecretCipher {
+    pub fn generate() -> Self {
+        Self {
+            key: Aes256Gcm::generate_key(OsRng),
+        }
+    }
+
+    pub fn from_base64(encoded: &str) -> Result<Self> {
+        let bytes = STANDARD
+            .decode(encoded.trim())
+            .context("Secret key is not valid base64")?;
+        if bytes.len() != 32 {
+            return Err(anyhow!(
+                "Secret key must be 32 bytes, got {} bytes",
+                bytes.len()
+            ));
+        }
+        Ok(Self {
+            key: *Key::<Aes256Gcm>::from_slice(&bytes),
+        })
+    }
+
+    pub fn to_base64(&self) -> String {
+        STANDARD.encode(self.key)
+    }
+
+    /// Load the key from [`KEY_ENV_VAR`], or from `key_file`, creating the file with
+    /// a new key when it does not exist yet.
+    pub fn load(key_file: &Path) -> Result<(Self, KeySource)> {
+        if let Ok(encoded) = std::env::var(KEY_ENV_VAR)
+            && !encoded.trim().is_empty()
+        {
+            let cipher =
+                Self::from_base64(&encoded).with_context(|| format!("Invalid {KEY_ENV_VAR}"))?;
+            return Ok((cipher, KeySource::Env));
+        }
+
+        if key_file.exists() {
+            let encoded = std::fs::read_to_string(key_file)
+                .with_context(|| format!("Failed to read {}", key_file.display()))?;
+            let cipher = Self::from_base64(&encoded)
+                .with_context(|| format!("Invalid key file {}", key_file.display()))?;
+            return Ok((cipher, KeySource::File));
+        }
+
+        let cipher = Self::generate();
+        cipher.write_key_file(key_file)?;
+        tracing::info!(path = %key_file.display(), "Generated forge secret key");
+        Ok((cipher, KeySource::File))
+    }
+
+    /// Replace `key_file` with this key, readable by the current user only.
+    pub fn write_key_file(&self, key_file: &Path) -> Result<()> {
+        if let Some(parent) = key_file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let staging = staging_path(key_file);
+        std::fs::write(&staging, self.to_base64())
+            .with_context(|| format!("Failed to write {}", staging.display()))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&staging, std::fs::Permissions::from_mode(0o600))?;
+        }
+        std::fs::rename(&staging, key_file)
+            .with_context(|| format!("Failed to replace {}", key_file.display()))?;
+        Ok(())
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> Result<String> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = Aes256Gcm::new(&self.key)
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| anyhow!("Failed to encrypt secret"))?;
+
+        let mut payload = nonce.to_vec();
+        payload.extend_from_slice(&ciphertext);
+        Ok(format!("{ENCRYPTED_PREFIX}{}", STANDARD.encode(payload)))
+    }
+
+    /// Decrypt a value written by [`SecretCipher::encrypt`]; plaintext left over
+    /// from before encryption is returned unchanged.
+    pub fn decrypt(&self, value: &str) -> Result<String> {
+        let Some(encoded) = value.strip_prefix(ENCRYPTED_PREFIX) else {
+            return Ok(value.to_string());
+        };
+        let payload = STANDARD
+            .decode(encoded)
+            .context("Encrypted secret is not valid base64")?;
+        if payload.len() < NONCE_LEN {
+            return Err(anyhow!("Encrypted secret is truncated"));
+        }
+        let (nonce, ciphertext) = payload.split_at(NONCE_LEN);
+        let plaintext = Aes256Gcm::new(&self.key)
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow!("Failed to decrypt secret; the forge secret key has changed"))?;
+        Ok(String::from_utf8(plaintext)?)
+    }
+
+    /// Encrypt the secret fields of serialized settings in place.
+    pub fn encrypt_secrets(&self, settings: &mut Value) -> Result<()> {
+        for pointer in secret_pointers(settings) {
+            if let Some(Value::String(secret)) = settings.pointer_mut(&pointer)
+                && !secret.is_empty()
+                && !is_encrypted(secret)
+            {
+                *secret = self.encrypt(secret)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Decrypt the secret fields of serialized settings in place.
+    pub fn decrypt_secrets(&self, settings: &mut Value) -> Result<()> {
+        for pointer in secret_pointers(settings) {
+            if let Some(Value::String(secret)) = settings.pointer_mut(&pointer)
+                && is_encrypted(secret)
+            {
+                *secret = self.decrypt(secret)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn is_encrypted(value: &str) -> bool {
+    value.starts_with(ENCRYPTED_PREFIX)
+}
+
+/// Fail when serialized settings hold encrypted secrets but no key is configured.
+pub fn ensure_no_encrypted_secrets(settings: &Value) -> Result<()> {
+    let encrypted = secret_pointers(settings).into_iter().any(|pointer| {
+        settings
+            .pointer(&pointer)
+            .and_then(Value::as_str)
+            .is_some_and(is_encrypted)
+    });
+    if encrypted {
+        return Err(anyhow!(
+            "Settings contain encrypted secrets but no forge secret key is configured"
+        ));
+    }
+    Ok(())
+}
+
+/// Replace every non-empty secret of serialized settings with [`REDACTED`].
+pub fn redact_secrets(settings: &mut Value) {
+    for pointer in secret_pointers(settings) {
+        if let Some(Value::String(secret)) = settings.pointer_mut(&pointer)
+            && !secret.is_empty()
+        {
+            *secret = REDACTED.to_string();
+        }
+    }
+}
+
+/// Empty every secret of serialized settings, for copies that leave this install.
+pub fn strip_secrets(settings: &mut Value) {
+    for pointer in secret_pointers(settings) {
+        if let Some(Value::String(secret)) = settings.pointer_mut(&pointer) {
+            secret.clear();
+        }
+    }
+}
+
+/// Put the stored secret back wherever `settings` still holds [`REDACTED`], so a
+/// client can save settings it loaded from the API without re-entering secrets.
+/// A channel gets the secret of the stored channel with the same type and endpoint,
+/// wherever it is in the list, and the Omni API key is only kept for the stored
+/// host. A placeholder with nothing to restore is rejected.
+pub fn restore_redacted(settings: &mut Value, stored: Option<&Value>) -> Result<()> {
+    let empty = Value::Null;
+    let stored = stored.unwrap_or(&empty);
+    let mut claimed = Vec::new();
+
+    for pointer in secret_pointers(settings) {
+        if settings.pointer(&pointer).and_then(Value::as_str) != Some(REDACTED) {
+            continue;
+        }
+
+        let previous = match pointer.strip_prefix("/notification_channels/") {
+            Some(rest) => {
+                let (index, field) = rest.split_once('/').unwrap_or_default();
+                let channel = &settings["notification_channels"][index.parse::<usize>()?];
+                let kind = channel["type"].as_str().unwrap_or_default();
+                let Some(stored_index) = matching_channel(channel, stored, &claimed) else {
+                    return Err(InvalidSettings(format!(
+                        "No saved {kind} channel matches channel {}; enter its {field} again",
+                        index.parse::<usize>()? + 1
+                    ))
+                    .into());
+                };
+                claimed.push(stored_index);
+                stored["notification_channels"][stored_index][field].as_str()
+            }
+            None => {
+                if pointer == "/omni_config/api_key"
+                    && settings.pointer("/omni_config/host") != stored.pointer("/omni_config/host")
+                {
+                    return Err(InvalidSettings(
+                        "The saved Omni API key can only be used with the saved host; enter the key for the new host".into(),
+                    )
+                    .into());
+                }
+                stored.pointer(&pointer).and_then(Value::as_str)
+            }
+        };
+        let Some(previous) = previous.filter(|previous| !previous.is_empty()) else {
+            return Err(InvalidSettings(format!(
+                "No saved secret to keep for {}; enter it again",
+                pointer.trim_start_matches('/').replace('/', ".")
+            ))
+            .into());
+        };
+
+        let previous = Value::String(previous.to_string());
+        if let Some(slot) = settings.pointer_mut(&pointer) {
+            *slot = previous;
+        }
+    }
+
+    Ok(())
+}
+
+/// Omni config as shown by the API, with its secrets redacted.
+pub fn redacted_omni_config(config: &OmniConfig) -> Value {
+    let mut settings = serde_json::json!({ "omni_config": config });
+    redact_secrets(&mut settings);
+    settings["omni_config"].take()
+}
+
+/// Omni API key to use when a client sends back [`REDACTED`] instead of the key.
+/// The stored key only goes to the stored host; naming another host with the
+/// placeholder is an error, so the key cannot be sent anywhere else.
+pub fn restore_api_key(
+    api_key: Option<String>,
+    host: Option<&str>,
+    stored: &OmniConfig,
+) -> Result<Option<String>> {
+    match api_key {
+        Some(key) if key == REDACTED => {
+            if host.is_none() || host != stored.host.as_deref() {
+                return Err(anyhow!(
+                    "The saved Omni API key can only be used with the saved host; enter the key for the new host"
+                ));
+            }
+            Ok(stored.api_key.clone())
+        }
+        other => Ok(other),
+    }
+}
+
+/// JSON pointers of the secret fields present in serialized settings.
+fn secret_pointers(settings: &Value) -> Vec<String> {
+    let mut pointers = vec![
+        "/omni_config/api_key".to_string(),
+        "/omni_config/inbound/secret".to_string(),
+    ];
+    if let Some(channels) = settings
+        .get("notification_channels")
+        .and_then(Value::as_array)
+    {
+        for (index, channel) in channels.iter().enumerate() {
+            let kind = channel.get("type").and_then(Value::as_str);
+            for (channel_type, field, _) in CHANNEL_SECRET_FIELDS {
+                if kind == Some(*channel_type) {
+                    pointers.push(format!("/notification_channels/{index}/{field}"));
+                }
+            }
+        }
+    }
+    pointers.retain(|pointer| settings.pointer(pointer).is_some());
+    pointers
+}
+
+/// Index of the first stored channel not `claimed` yet with the type and endpoint
+/// of `channel`.
+fn matching_channel(channel: &Value, stored: &Value, claimed: &[usize]) -> Option<usize> {
+    let (_, _, endpoint) = CHANNEL_SECRET_FIELDS
+        .iter()
+        .find(|(channel_type, ..)| channel["type"] == *channel_type)?;
+
+    stored
+        .get("notification_channels")?
+        .as_array()?
+        .iter()
+        .enumerate()
+        .find(|(index, candidate)| {
+            !claimed.contains(index)
+                && candidate["type"] == channel["type"]
+                && endpoint
+                    .iter()
+                    .all(|field| candidate[*field] == channel[*field])
+        })
+        .map(|(index, _)| index)
+}
+
+fn staging_path(key_file: &Path) -> PathBuf {
+    let mut name = key_file.as_os_str().to_owned();
+    name.push(".new");
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn settings() -> Value {
+        json!({
+            "omni_enabled": true,
+            "omni_config": {
+                "host": "http://omni.local",
+                "api_key": "omni-key",
+                "inbound": { "enabled": true, "secret": "inbound-secret" }
+            },
+            "notification_channels": [
+                { "type": "omni" },
+                { "type": "slack", "webhook_url": "https://hooks.slack.com/T/B/X" },
+                { "type": "email", "smtp_host": "smtp", "password": null, "from": "a@b", "to": [] },
+                { "type": "webhook", "url": "https://example.com", "secret": "hmac" }
+            ]
+        })
+    }
+
+    #[test]
+    fn encrypts_only_secret_fields() {
+        let cipher = SecretCipher::generate();
+        let mut value = settings();
+        cipher.encrypt_secrets(&mut value).unwrap();
+
+        assert!(is_encrypted(
+            value["omni_config"]["api_key"].as_str().unwrap()
+        ));
+        assert!(is_encrypted(
+            value["omni_config"]["inbound"]["secret"].as_str().unwrap()
+        ));
+        assert!(is_encrypted(
+            value["notification_channels"][1]["webhook_url"]
+                .as_str()
+                .unwrap()
+        ));
+        assert!(is_encrypted(
+            value["notification_channels"][3]["secret"]
+                .as_str()
+                .unwrap()
+        ));
+        assert_eq!(value["notification_channels"][2]["password"], Value::Null);
+        assert_eq!(value["omni_config"]["host"], "http://omni.local");
+        assert_eq!(
+            value["notification_channels"][3]["url"],
+            "https://example.com"
+        );
+
+        // Encrypting twice leaves the ciphertext alone
+        let once = value.clone();
+        cipher.encrypt_secrets(&mut value).unwrap();
+        assert_eq!(value, once);
+
+        cipher.decrypt_secrets(&mut value).unwrap();
+        assert_eq!(value, settings());
+
+        let other = SecretCipher::generate();
+        assert!(other.decrypt_secrets(&mut once.clone()).is_err());
+        assert!(ensure_no_encrypted_secrets(&once).is_err());
+        assert!(ensure_no_encrypted_secrets(&settings()).is_ok());
+    }
+
+    #[test]
+    fn redacted_values_are_restored_from_stored_settings() {
+        let stored = settings();
+        let mut value = settings();
+        redact_secrets(&mut value);
+        assert_eq!(value["omni_config"]["api_key"], REDACTED);
+        assert_eq!(value["notification_channels"][1]["webhook_url"], REDACTED);
+
+        value["omni_config"]["inbound"]["secret"] = json!("new-secret");
+        restore_redacted(&mut value, Some(&stored)).unwrap();
+        assert_eq!(value["omni_config"]["api_key"], "omni-key");
+        assert_eq!(value["omni_config"]["inbound"]["secret"], "new-secret");
+        assert_eq!(
+            value["notification_channels"][1]["webhook_url"],
+            "https://hooks.slack.com/T/B/X"
+        );
+
+        // A placeholder for a secret that was never stored is rejected
+        let mut unsaved = settings();
+        redact_secrets(&mut unsaved);
+        assert!(restore_redacted(&mut unsaved, None).is_err());
+
+        // The Omni key stays with its host
+        let mut moved_host = stored.clone();
+        redact_secrets(&mut moved_host);
+        moved_host["omni_config"]["host"] = json!("http://elsewhere.local");
+        assert!(restore_redacted(&mut moved_host, Some(&stored)).is_err());
+    }
+
+    #[test]
+    fn channel_secrets_follow_their_endpoint() {
+        let webhook =
+            |url: &str, secret: &str| json!({ "type": "webhook", "url": url, "secret": secret });
+        let stored = json!({
+            "notification_channels": [webhook("https://a", "secret-a"), webhook("https://b", "secret-b")]
+        });
+
+        // Reordered and with the first one deleted, each keeps its own secret
+        let mut reordered = json!({
+            "notification_channels": [webhook("https://b", REDACTED), { "type": "omni" }]
+        });
+        restore_redacted(&mut reordered, Some(&stored)).unwrap();
+        assert_eq!(reordered["notification_channels"][0]["secret"], "secret-b");
+
+        // A channel now pointing somewhere else needs its secret again
+        let mut moved = json!({
+            "notification_channels": [webhook("https://c", REDACTED)]
+        });
+        let err = restore_redacted(&mut moved, Some(&stored)).unwrap_err();
+        assert!(err.downcast_ref::<InvalidSettings>().is_some());
+
+        // Two channels cannot both take the same stored secret
+        let mut copied = json!({
+            "notification_channels": [webhook("https://a", REDACTED), webhook("https://a", REDACTED)]
+        });
+        assert!(restore_redacted(&mut copied, Some(&stored)).is_err());
+    }
+
+    #[test]
+    fn api_key_is_restored_for_the_stored_host_only() {
+        let stored = OmniConfig {
+            host: Some("http://omni.local".into()),
+            api_key: Some("omni-key".into()),
+            ..Default::default()
+        };
+        let redacted = || Some(REDACTED.to_string());
+
+        assert_eq!(
+            restore_api_key(redacted(), Some("http://omni.local"), &stored).unwrap(),
+            Some("omni-key".into())
+        );
+        assert!(restore_api_key(redacted(), Some("http://attacker.example"), &stored).is_err());
+        assert!(restore_api_key(redacted(), Some("http://omni.local/"), &stored).is_err());
+        assert!(restore_api_key(redacted(), None, &stored).is_err());
+
+        // A real key goes to any host
+        assert_eq!(
+            restore_api_key(Some("new-key".into()), Some("http://other"), &stored).unwrap(),
+            Some("new-key".into())
+        );
+        assert_eq!(
+            restore_api_key(None, Some("http://other"), &stored).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn key_round_trips_through_file() {
+        let dir = std::env::temp_dir().join(format!("forge-secrets-{}", uuid::Uuid::new_v4()));
+        let key_file = dir.join(KEY_FILE_NAME);
+
+        let cipher = SecretCipher::generate();
+        cipher.write_key_file(&key_file).unwrap();
+        let loaded =
+            SecretCipher::from_base64(&std::fs::read_to_string(&key_file).unwrap()).unwrap();
+        let encrypted = cipher.encrypt("omni-key").unwrap();
+        assert_eq!(loaded.decrypt(&encrypted).unwrap(), "omni-key");
+        assert!(!staging_path(&key_file).exists());
+
+        assert!(SecretCipher::from_base64("c2hvcnQ=").is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,22 +1,47 @@
-use anyhow::Result;
-use sqlx::SqlitePool;
+use anyhow::{Context, Result};
+use serde_json::Value;
+use sqlx::{SqliteConnection, SqlitePool};
+use tokio::sync::RwLock;
 use uuid::Uuid;
 
-use crate::types::{ForgeProjectSettings, ProjectConfig};
+use crate::executors::{CustomExecutors, upgrade_custom_executors};
+use crate::history::{self, SettingsHistoryEntry, SettingsScope, SettingsSource};
+use crate::layered::{ConfigOrigin, EffectiveSettings};
+use crate::schema::{
+    SETTINGS_SCHEMA_VERSION, parse_settings, schema_version, stamp_version, upgrade_settings,
+};
+use crate::secrets::{SecretCipher, ensure_no_encrypted_secrets, restore_redacted};
+use crate::types::{ForgeProjectSettings, InvalidSettings, ProjectConfig};
+use forge_notifications::{NotificationChannelConfig, NotificationRule, NotificationType};
 use forge_omni::OmniConfig;
 
 pub struct ForgeConfigService {
     pool: SqlitePool,
+    /// Key for secret fields; without one they are stored as plain JSON. Writers
+    /// hold the read lock so a key rotation never interleaves with them.
+    secrets: RwLock<Option<SecretCipher>>,
 }
 
 impl ForgeConfigService {
     pub const GLOBAL_PROJECT_ID: Uuid = Uuid::nil();
 
     pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            secrets: RwLock::new(None),
+        }
+    }
+
+    /// Encrypt secret fields with `cipher` when settings are saved.
+    pub fn with_secret_cipher(pool: SqlitePool, cipher: SecretCipher) -> Self {
+        Self {
+            pool,
+            secrets: RwLock::new(Some(cipher)),
+        }
     }
 
     pub async fn get_project_config(&self, project_id: Uuid) -> Result<Option<ProjectConfig>> {
+        let secrets = self.secrets.read().await;
         let record: Option<ProjectConfigRow> = sqlx::query_as(
             r#"SELECT
                 project_id,
@@ -32,124 +57,681 @@ impl ForgeConfigService {
         if let Some(row) = record {
             Ok(Some(ProjectConfig {
                 project_id: row.project_id,
-                custom_executors: row
-                    .custom_executors
-                    .and_then(|s| serde_json::from_str(&s).ok()),
-                forge_config: row.forge_config.and_then(|s| serde_json::from_str(&s).ok()),
+                // Invalid ones are reported by `upgrade_stored_settings`; the
+                // project runs without an overlay until they are saved again
+                custom_executors: row.custom_executors.and_then(|raw| {
+                    parse_custom_executors(&raw)
+                        .inspect_err(|err| {
+                            tracing::warn!(
+                                "Stored custom executors for project {} are invalid, ignoring them: {:#}",
+                                project_id,
+                                err
+                            )
+                        })
+                        .ok()
+                }),
+                forge_config: decrypt_json(secrets.as_ref(), row.forge_config)?,
             }))
         } else {
             Ok(None)
         }
     }
 
-    pub async fn set_project_config(&self, config: &ProjectConfig) -> Result<()> {
+    /// Save a project's row. A `forge_config` is recorded in the settings history.
+    pub async fn set_project_config(
+        &self,
+        config: &ProjectConfig,
+        source: SettingsSource,
+    ) -> Result<()> {
+        if let Some(custom_executors) = &config.custom_executors {
+            custom_executors.validate()?;
+        }
         let custom_executors_json = config
             .custom_executors
             .as_ref()
             .map(serde_json::to_string)
             .transpose()?;
 
-        let forge_config_json = config
-            .forge_config
-            .as_ref()
-            .map(serde_json::to_string)
-            .transpose()?;
+        let secrets = self.secrets.read().await;
+        let mut tx = self.pool.begin().await?;
+
+        let forge_config_json = match &config.forge_config {
+            Some(value) => {
+                let previous = stored_settings(
+                    &mut tx,
+                    SettingsScope::Project,
+                    Some(config.project_id),
+                    secrets.as_ref(),
+                )
+                .await?;
+                history::record(
+                    &mut tx,
+                    SettingsScope::Project,
+                    Some(config.project_id),
+                    previous.as_ref(),
+                    value,
+                    source,
+                )
+                .await?;
+
+                let mut value = value.clone();
+                if let Some(cipher) = secrets.as_ref() {
+                    cipher.encrypt_secrets(&mut value)?;
+                }
+                Some(serde_json::to_string(&value)?)
+            }
+            None => None,
+        };
 
         sqlx::query(
-            "INSERT OR REPLACE INTO forge_project_settings (project_id, custom_executors, forge_config) VALUES (?, ?, ?)"
+            "INSERT INTO forge_project_settings (project_id, custom_executors, forge_config) VALUES (?, ?, ?)
+             ON CONFLICT(project_id) DO UPDATE SET
+                 custom_executors = excluded.custom_executors,
+                 forge_config = excluded.forge_config",
         )
         .bind(config.project_id)
         .bind(custom_executors_json)
         .bind(forge_config_json)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
 
+        tx.commit().await?;
         Ok(())
     }
 
-    pub async fn get_forge_settings(&self, project_id: Uuid) -> Result<ForgeProjectSettings> {
-        if let Some(config) = self.get_project_config(project_id).await?
-            && let Some(forge_config) = config.forge_config
-            && let Ok(settings) = serde_json::from_value::<ForgeProjectSettings>(forge_config)
-        {
-            return Ok(settings);
-        }
+    /// A project's custom executors; empty when it has none.
+    pub async fn get_custom_executors(&self, project_id: Uuid) -> Result<CustomExecutors> {
+        Ok(self
+            .get_project_config(project_id)
+            .await?
+            .and_then(|config| config.custom_executors)
+            .unwrap_or_default())
+    }
 
-        Ok(ForgeProjectSettings::default())
+    /// Replace a project's custom executors, keeping its settings. They are
+    /// validated first.
+    pub async fn set_custom_executors(
+        &self,
+        project_id: Uuid,
+        custom_executors: Option<&CustomExecutors>,
+    ) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        self.set_custom_executors_in(&mut conn, project_id, custom_executors)
+            .await
     }
 
-    pub async fn set_forge_settings(
+    /// [`Self::set_custom_executors`] as part of the caller's transaction on `conn`.
+    pub async fn set_custom_executors_in(
         &self,
+        conn: &mut SqliteConnection,
         project_id: Uuid,
-        settings: &ForgeProjectSettings,
+        custom_executors: Option<&CustomExecutors>,
     ) -> Result<()> {
-        let forge_config_value = serde_json::to_value(settings)?;
+        if let Some(custom_executors) = custom_executors {
+            custom_executors.validate()?;
+        }
+        let custom_executors_json = custom_executors.map(serde_json::to_string).transpose()?;
 
-        // Get existing config or create new one
-        let mut config = self
+        sqlx::query(
+            "INSERT INTO forge_project_settings (project_id, custom_executors) VALUES (?, ?)
+             ON CONFLICT(project_id) DO UPDATE SET custom_executors = excluded.custom_executors",
+        )
+        .bind(project_id)
+        .bind(custom_executors_json)
+        .execute(conn)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_forge_settings(&self, project_id: Uuid) -> Result<ForgeProjectSettings> {
+        let Some(forge_config) = self
             .get_project_config(project_id)
             .await?
-            .unwrap_or_else(|| ProjectConfig {
-                project_id,
-                custom_executors: None,
-                forge_config: None,
-            });
+            .and_then(|config| config.forge_config)
+        else {
+            return Ok(ForgeProjectSettings::default());
+        };
 
-        config.forge_config = Some(forge_config_value);
+        parse_settings(forge_config)
+            .with_context(|| format!("Stored settings for project {project_id} are invalid"))
+    }
 
-        self.set_project_config(&config).await
+    pub async fn set_forge_settings(
+        &self,
+        project_id: Uuid,
+        settings: &ForgeProjectSettings,
+        source: SettingsSource,
+    ) -> Result<()> {
+        self.write_settings(SettingsScope::Project, Some(project_id), settings, source)
+            .await
     }
 
     pub async fn get_global_settings(&self) -> Result<ForgeProjectSettings> {
+        let Some(value) = self.get_global_config_value().await? else {
+            return Ok(ForgeProjectSettings::default());
+        };
+
+        parse_settings(value).context("Stored global settings are invalid")
+    }
+
+    /// Raw global settings with their secrets decrypted.
+    async fn get_global_config_value(&self) -> Result<Option<Value>> {
+        let secrets = self.secrets.read().await;
         // Read from forge_global_settings table
         let row: Option<(String,)> =
             sqlx::query_as("SELECT forge_config FROM forge_global_settings WHERE id = 1")
                 .fetch_optional(&self.pool)
                 .await?;
 
-        if let Some((config_str,)) = row
-            && let Ok(settings) = serde_json::from_str::<ForgeProjectSettings>(&config_str)
-        {
-            return Ok(settings);
-        }
+        decrypt_json(secrets.as_ref(), row.map(|(config_str,)| config_str))
+    }
+
+    pub async fn set_global_settings(
+        &self,
+        settings: &ForgeProjectSettings,
+        source: SettingsSource,
+    ) -> Result<()> {
+        self.write_settings(SettingsScope::Global, None, settings, source)
+            .await
+    }
 
-        Ok(ForgeProjectSettings::default())
+    /// Settings for a task that override its project's, if any were saved.
+    pub async fn get_task_settings(&self, task_id: Uuid) -> Result<Option<ForgeProjectSettings>> {
+        self.get_task_config_value(task_id)
+            .await?
+            .map(|value| {
+                parse_settings(value)
+                    .with_context(|| format!("Stored settings for task {task_id} are invalid"))
+            })
+            .transpose()
     }
 
-    pub async fn set_global_settings(&self, settings: &ForgeProjectSettings) -> Result<()> {
-        // Write to forge_global_settings table
-        let config_json = serde_json::to_string(settings)?;
+    /// Raw task settings with their secrets decrypted.
+    async fn get_task_config_value(&self, task_id: Uuid) -> Result<Option<Value>> {
+        let secrets = self.secrets.read().await;
+        let raw: Option<Option<String>> =
+            sqlx::query_scalar("SELECT forge_config FROM forge_task_settings WHERE task_id = ?")
+                .bind(task_id)
+                .fetch_optional(&self.pool)
+                .await?;
 
-        sqlx::query(
-            "INSERT INTO forge_global_settings (id, forge_config) VALUES (1, ?)
-             ON CONFLICT(id) DO UPDATE SET forge_config = excluded.forge_config",
-        )
-        .bind(config_json)
-        .execute(&self.pool)
-        .await?;
+        decrypt_json(secrets.as_ref(), raw.flatten())
+    }
 
+    pub async fn set_task_settings(
+        &self,
+        task_id: Uuid,
+        settings: &ForgeProjectSettings,
+        source: SettingsSource,
+    ) -> Result<()> {
+        self.write_settings(SettingsScope::Task, Some(task_id), settings, source)
+            .await
+    }
+
+    /// Store the settings of one level and record the write in the history, in one
+    /// transaction. Redacted secrets are restored from the stored value first.
+    async fn write_settings(
+        &self,
+        scope: SettingsScope,
+        scope_id: Option<Uuid>,
+        settings: &ForgeProjectSettings,
+        source: SettingsSource,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        self.write_settings_in(&mut tx, scope, scope_id, settings, source)
+            .await?;
+        tx.commit().await?;
         Ok(())
     }
 
-    pub async fn effective_omni_config(&self, project_id: Option<Uuid>) -> Result<OmniConfig> {
-        let global_settings = self.get_global_settings().await?;
-        let mut config = global_settings.omni_config.clone().unwrap_or_default();
-        config.enabled = global_settings.omni_enabled;
+    /// Like the setters, as part of the caller's transaction on `conn`, so the
+    /// settings are only kept when everything written with them is.
+    pub async fn write_settings_in(
+        &self,
+        conn: &mut SqliteConnection,
+        scope: SettingsScope,
+        scope_id: Option<Uuid>,
+        settings: &ForgeProjectSettings,
+        source: SettingsSource,
+    ) -> Result<()> {
+        let mut value = serde_json::to_value(settings)?;
+        stamp_version(&mut value);
+
+        let secrets = self.secrets.read().await;
+        let previous = stored_settings(conn, scope, scope_id, secrets.as_ref()).await?;
+        restore_redacted(&mut value, previous.as_ref())?;
+        history::record(conn, scope, scope_id, previous.as_ref(), &value, source).await?;
 
+        if let Some(cipher) = secrets.as_ref() {
+            cipher.encrypt_secrets(&mut value)?;
+        }
+        let sql = match scope {
+            SettingsScope::Global => {
+                "INSERT INTO forge_global_settings (id, forge_config) VALUES (1, ?)
+                 ON CONFLICT(id) DO UPDATE SET forge_config = excluded.forge_config"
+            }
+            SettingsScope::Project => {
+                "INSERT INTO forge_project_settings (forge_config, project_id) VALUES (?, ?)
+                 ON CONFLICT(project_id) DO UPDATE SET forge_config = excluded.forge_config"
+            }
+            SettingsScope::Task => {
+                "INSERT INTO forge_task_settings (forge_config, task_id) VALUES (?, ?)
+                 ON CONFLICT(task_id) DO UPDATE SET forge_config = excluded.forge_config"
+            }
+        };
+        let mut query = sqlx::query(sql).bind(value.to_string());
+        if let Some(scope_id) = scope_id {
+            query = query.bind(scope_id);
+        }
+        query.execute(conn).await?;
+        Ok(())
+    }
+
+    /// Settings writes, newest first; for one level when `scope` is given.
+    pub async fn settings_history(
+        &self,
+        scope: Option<(SettingsScope, Option<Uuid>)>,
+        limit: i64,
+    ) -> Result<Vec<SettingsHistoryEntry>> {
+        let mut conn = self.pool.acquire().await?;
+        history::list(&mut conn, scope, limit).await
+    }
+
+    /// Store the settings recorded by a history entry again, as a new write from
+    /// `source`. Secrets are redacted in the history, so the ones stored now are
+    /// kept. Returns the restored entry, or `None` when there is no such entry.
+    pub async fn restore_settings(
+        &self,
+        id: i64,
+        source: SettingsSource,
+    ) -> Result<Option<SettingsHistoryEntry>> {
+        let mut conn = self.pool.acquire().await?;
+        let Some(entry) = history::get(&mut conn, id).await? else {
+            return Ok(None);
+        };
+        drop(conn);
+
+        // Recorded before the current rules, so checked like a new write
+        let settings = parse_settings(entry.new_value.clone())
+            .and_then(|settings| settings.validate().map(|()| settings))
+            .map_err(|e| {
+                InvalidSettings(format!(
+                    "Settings recorded by history entry {id} are invalid: {e:#}"
+                ))
+            })?;
+        self.write_settings(entry.scope, entry.scope_id, &settings, source)
+            .await?;
+        Ok(Some(entry))
+    }
+
+    /// Settings merged field by field over the global, project and task levels.
+    pub async fn effective_settings(
+        &self,
+        project_id: Option<Uuid>,
+        task_id: Option<Uuid>,
+    ) -> Result<EffectiveSettings> {
+        let mut levels = Vec::new();
+        if let Some(value) = self.get_global_config_value().await? {
+            levels.push((ConfigOrigin::Global, value));
+        }
         if let Some(project_id) = project_id
             && let Some(project_config) = self.get_project_config(project_id).await?
-            && let Some(value) = project_config.forge_config.clone()
-            && let Ok(project_settings) = serde_json::from_value::<ForgeProjectSettings>(value)
+            && let Some(value) = project_config.forge_config
         {
-            let mut project_omni = project_settings
-                .omni_config
-                .unwrap_or_else(|| config.clone());
-            project_omni.enabled = project_settings.omni_enabled;
-            config = project_omni;
+            levels.push((ConfigOrigin::Project, value));
+        }
+        if let Some(task_id) = task_id
+            && let Some(value) = self.get_task_config_value(task_id).await?
+        {
+            levels.push((ConfigOrigin::Task, value));
+        }
+
+        EffectiveSettings::resolve(levels)
+    }
+
+    /// Omni settings for a project, its fields inherited from the global settings
+    /// where the project leaves them unset.
+    pub async fn effective_omni_config(&self, project_id: Option<Uuid>) -> Result<OmniConfig> {
+        Ok(self
+            .effective_settings(project_id, None)
+            .await?
+            .omni_config())
+    }
+
+    /// Channels that should receive notifications for a project. A project with its
+    /// own channel list replaces the global list; with no channels configured anywhere
+    /// the legacy Omni-only behaviour applies.
+    pub async fn effective_notification_channels(
+        &self,
+        project_id: Option<Uuid>,
+    ) -> Result<Vec<NotificationChannelConfig>> {
+        Ok(self
+            .effective_settings(project_id, None)
+            .await?
+            .notification_channels())
+    }
+
+    /// Rules filtering which events are delivered. A project with its own rules
+    /// replaces the global rules.
+    pub async fn effective_notification_rules(
+        &self,
+        project_id: Option<Uuid>,
+    ) -> Result<Vec<NotificationRule>> {
+        Ok(self
+            .effective_settings(project_id, None)
+            .await?
+            .notification_rules())
+    }
+
+    /// Events that queue notifications for a project. A project with its own list
+    /// replaces the global list; with none configured only execution exits are sent.
+    pub async fn effective_notification_types(
+        &self,
+        project_id: Option<Uuid>,
+    ) -> Result<Vec<NotificationType>> {
+        Ok(self
+            .effective_settings(project_id, None)
+            .await?
+            .notification_types())
+    }
+
+    /// Notification template for an event in a project; see
+    /// [`EffectiveSettings::notification_template`].
+    pub async fn effective_notification_template(
+        &self,
+        project_id: Option<Uuid>,
+        notification_type: NotificationType,
+    ) -> Result<Option<String>> {
+        Ok(self
+            .effective_settings(project_id, None)
+            .await?
+            .notification_template(notification_type))
+    }
+
+    /// Upgrade settings stored with an older schema version, and custom executors
+    /// stored in the free-form layout, and check that every row still parses.
+    /// Rows that do not are left untouched and reported.
+    pub async fn upgrade_stored_settings(&self) -> Result<SettingsCheck> {
+        let _secrets = self.secrets.read().await;
+        let mut tx = self.pool.begin().await?;
+        let mut check = SettingsCheck::default();
+
+        // The upgraded JSON when the row is outdated; secrets stay encrypted since
+        // their fields are plain strings to the schema
+        let upgrade = |raw: &str| -> Result<Option<String>> {
+            let value =
+                serde_json::from_str::<Value>(raw).context("Settings are not valid JSON")?;
+            let outdated = schema_version(&value) < SETTINGS_SCHEMA_VERSION;
+            let mut upgraded = upgrade_settings(value)?;
+            serde_json::from_value::<ForgeProjectSettings>(upgraded.clone())
+                .context("Settings do not match the expected schema")?;
+            stamp_version(&mut upgraded);
+            Ok(outdated.then(|| upgraded.to_string()))
+        };
+
+        let global: Option<String> =
+            sqlx::query_scalar("SELECT forge_config FROM forge_global_settings WHERE id = 1")
+                .fetch_optional(&mut *tx)
+                .await?;
+        if let Some(raw) = global {
+            match upgrade(&raw) {
+                Ok(Some(updated)) => {
+                    sqlx::query("UPDATE forge_global_settings SET forge_config = ? WHERE id = 1")
+                        .bind(updated)
+                        .execute(&mut *tx)
+                        .await?;
+                    check.upgraded += 1;
+                }
+                Ok(None) => {}
+                Err(err) => check
+                    .invalid
+                    .push(format!("Stored global settings are invalid: {err:#}")),
+            }
         }
 
-        Ok(config)
+        let projects: Vec<(Uuid, String)> = sqlx::query_as(
+            "SELECT project_id, forge_config FROM forge_project_settings WHERE forge_config IS NOT NULL",
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for (project_id, raw) in projects {
+            match upgrade(&raw) {
+                Ok(Some(updated)) => {
+                    sqlx::query(
+                        "UPDATE forge_project_settings SET forge_config = ? WHERE project_id = ?",
+                    )
+                    .bind(updated)
+                    .bind(project_id)
+                    .execute(&mut *tx)
+                    .await?;
+                    check.upgraded += 1;
+                }
+                Ok(None) => {}
+                Err(err) => check.invalid.push(format!(
+                    "Stored settings for project {project_id} are invalid: {err:#}"
+                )),
+            }
+        }
+
+        let tasks: Vec<(Uuid, String)> = sqlx::query_as(
+            "SELECT task_id, forge_config FROM forge_task_settings WHERE forge_config IS NOT NULL",
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for (task_id, raw) in tasks {
+            match upgrade(&raw) {
+                Ok(Some(updated)) => {
+                    sqlx::query(
+                        "UPDATE forge_task_settings SET forge_config = ? WHERE task_id = ?",
+                    )
+                    .bind(updated)
+                    .bind(task_id)
+                    .execute(&mut *tx)
+                    .await?;
+                    check.upgraded += 1;
+                }
+                Ok(None) => {}
+                Err(err) => check.invalid.push(format!(
+                    "Stored settings for task {task_id} are invalid: {err:#}"
+                )),
+            }
+        }
+
+        let executors: Vec<(Uuid, String)> = sqlx::query_as(
+            "SELECT project_id, custom_executors FROM forge_project_settings WHERE custom_executors IS NOT NULL",
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for (project_id, raw) in executors {
+            match parse_custom_executors(&raw)
+                .and_then(|custom_executors| Ok(serde_json::to_string(&custom_executors)?))
+            {
+                Ok(updated) if updated != raw => {
+                    sqlx::query(
+                        "UPDATE forge_project_settings SET custom_executors = ? WHERE project_id = ?",
+                    )
+                    .bind(updated)
+                    .bind(project_id)
+                    .execute(&mut *tx)
+                    .await?;
+                    check.upgraded += 1;
+                }
+                Ok(_) => {}
+                Err(err) => check.invalid.push(format!(
+                    "Stored custom executors for project {project_id} are invalid: {err:#}"
+                )),
+            }
+        }
+
+        tx.commit().await?;
+        Ok(check)
+    }
+
+    /// Encrypt secrets that were stored before a key was configured. Returns the
+    /// number of settings rows rewritten.
+    pub async fn encrypt_stored_secrets(&self) -> Result<u64> {
+        let secrets = self.secrets.read().await;
+        let Some(cipher) = secrets.as_ref() else {
+            return Ok(0);
+        };
+        self.rewrite_secrets(None, cipher).await
     }
+
+    /// Re-encrypt every stored secret with `new_cipher` and use it from now on.
+    /// Returns the number of settings rows rewritten.
+    pub async fn rotate_secret_key(&self, new_cipher: SecretCipher) -> Result<u64> {
+        let mut secrets = self.secrets.write().await;
+        let rows = self.rewrite_secrets(secrets.as_ref(), &new_cipher).await?;
+        *secrets = Some(new_cipher);
+        Ok(rows)
+    }
+
+    /// Rewrite the secrets of all settings rows in one transaction. With `current`,
+    /// stored ciphertext is decrypted with that key first; otherwise only plaintext
+    /// secrets are encrypted.
+    async fn rewrite_secrets(
+        &self,
+        current: Option<&SecretCipher>,
+        cipher: &SecretCipher,
+    ) -> Result<u64> {
+        let mut tx = self.pool.begin().await?;
+        let mut rewritten = 0;
+
+        let rewrite = |raw: &str| -> Result<Option<String>> {
+            let Ok(original) = serde_json::from_str::<Value>(raw) else {
+                return Ok(None);
+            };
+            let mut value = original.clone();
+            if let Some(current) = current {
+                current.decrypt_secrets(&mut value)?;
+            }
+            cipher.encrypt_secrets(&mut value)?;
+            Ok((value != original).then(|| value.to_string()))
+        };
+
+        let global: Option<String> =
+            sqlx::query_scalar("SELECT forge_config FROM forge_global_settings WHERE id = 1")
+                .fetch_optional(&mut *tx)
+                .await?;
+        if let Some(updated) = global.as_deref().map(rewrite).transpose()?.flatten() {
+            sqlx::query("UPDATE forge_global_settings SET forge_config = ? WHERE id = 1")
+                .bind(updated)
+                .execute(&mut *tx)
+                .await?;
+            rewritten += 1;
+        }
+
+        let projects: Vec<(Uuid, String)> = sqlx::query_as(
+            "SELECT project_id, forge_config FROM forge_project_settings WHERE forge_config IS NOT NULL",
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for (project_id, raw) in projects {
+            if let Some(updated) = rewrite(&raw)? {
+                sqlx::query(
+                    "UPDATE forge_project_settings SET forge_config = ? WHERE project_id = ?",
+                )
+                .bind(updated)
+                .bind(project_id)
+                .execute(&mut *tx)
+                .await?;
+                rewritten += 1;
+            }
+        }
+
+        let tasks: Vec<(Uuid, String)> = sqlx::query_as(
+            "SELECT task_id, forge_config FROM forge_task_settings WHERE forge_config IS NOT NULL",
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        for (task_id, raw) in tasks {
+            if let Some(updated) = rewrite(&raw)? {
+                sqlx::query("UPDATE forge_task_settings SET forge_config = ? WHERE task_id = ?")
+                    .bind(updated)
+                    .bind(task_id)
+                    .execute(&mut *tx)
+                    .await?;
+                rewritten += 1;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(rewritten)
+    }
+}
+
+fn decrypt_value(cipher: Option<&SecretCipher>, value: &mut Value) -> Result<()> {
+    match cipher {
+        Some(cipher) => cipher.decrypt_secrets(value),
+        None => ensure_no_encrypted_secrets(value),
+    }
+}
+
+/// Decrypted settings of one level, read for an update. A value that is no longer
+/// valid JSON counts as missing, so writing new settings replaces it.
+async fn stored_settings(
+    conn: &mut SqliteConnection,
+    scope: SettingsScope,
+    scope_id: Option<Uuid>,
+    cipher: Option<&SecretCipher>,
+) -> Result<Option<Value>> {
+    let raw: Option<Option<String>> = match scope {
+        SettingsScope::Global => {
+            sqlx::query_scalar("SELECT forge_config FROM forge_global_settings WHERE id = 1")
+                .fetch_optional(conn)
+                .await?
+        }
+        SettingsScope::Project => {
+            sqlx::query_scalar(
+                "SELECT forge_config FROM forge_project_settings WHERE project_id = ?",
+            )
+            .bind(scope_id)
+            .fetch_optional(conn)
+            .await?
+        }
+        SettingsScope::Task => {
+            sqlx::query_scalar("SELECT forge_config FROM forge_task_settings WHERE task_id = ?")
+                .bind(scope_id)
+                .fetch_optional(conn)
+                .await?
+        }
+    };
+
+    let Some(mut value) = raw
+        .flatten()
+        .and_then(|raw| serde_json::from_str::<Value>(&raw).ok())
+    else {
+        return Ok(None);
+    };
+    decrypt_value(cipher, &mut value)?;
+    Ok(Some(value))
+}
+
+/// Parse stored settings JSON and decrypt its secrets.
+fn decrypt_json(cipher: Option<&SecretCipher>, raw: Option<String>) -> Result<Option<Value>> {
+    let Some(raw) = raw else {
+        return Ok(None);
+    };
+    let mut value: Value =
+        serde_json::from_str(&raw).context("Stored settings are not valid JSON")?;
+    decrypt_value(cipher, &mut value)?;
+    Ok(Some(value))
+}
+
+/// Stored custom executors, upgraded from the free-form layout when needed.
+fn parse_custom_executors(raw: &str) -> Result<CustomExecutors> {
+    let value = serde_json::from_str(raw).context("Custom executors are not valid JSON")?;
+    upgrade_custom_executors(value)
+}
+
+/// Outcome of [`ForgeConfigService::upgrade_stored_settings`].
+#[derive(Debug, Default)]
+pub struct SettingsCheck {
+    /// Rows rewritten with the current schema version
+    pub upgraded: u64,
+    /// One message per row that does not parse
+    pub invalid: Vec<String>,
 }
 
 // Helper struct for database queries
@@ -162,8 +744,12 @@ struct ProjectConfigRow {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
+
     use super::*;
-    use forge_omni::{OmniConfig, RecipientType};
+    use crate::secrets::REDACTED;
+    use forge_notifications::{CommandChannelConfig, WebhookChannelConfig};
+    use forge_omni::{OmniConfig, OmniRecipient, RecipientType};
 
     async fn setup_pool() -> SqlitePool {
         let pool = SqlitePool::connect("sqlite::memory:")
@@ -201,6 +787,31 @@ mod tests {
         .await
         .expect("failed to create forge_project_settings table for tests");
 
+        sqlx::query(
+            r#"CREATE TABLE forge_task_settings (
+                    task_id TEXT PRIMARY KEY,
+                    forge_config TEXT
+                )"#,
+        )
+        .execute(&pool)
+        .await
+        .expect("failed to create forge_task_settings table for tests");
+
+        sqlx::query(
+            r#"CREATE TABLE forge_settings_history (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    scope TEXT NOT NULL,
+                    scope_id BLOB,
+                    old_value TEXT,
+                    new_value TEXT NOT NULL,
+                    source TEXT NOT NULL,
+                    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+                )"#,
+        )
+        .execute(&pool)
+        .await
+        .expect("failed to create forge_settings_history table for tests");
+
         pool
     }
 
@@ -214,10 +825,10 @@ mod tests {
             .get_global_settings()
             .await
             .expect("default settings should load");
-        assert!(!settings.omni_enabled);
+        assert_eq!(settings.omni_enabled, None);
         assert!(settings.omni_config.is_none());
 
-        settings.omni_enabled = true;
+        settings.omni_enabled = Some(true);
         settings.omni_config = Some(OmniConfig {
             enabled: true,
             host: Some("https://omni.test".into()),
@@ -225,10 +836,11 @@ mod tests {
             instance: Some("forge".into()),
             recipient: Some("+14155552671".into()),
             recipient_type: Some(RecipientType::PhoneNumber),
+            ..Default::default()
         });
 
         service
-            .set_global_settings(&settings)
+            .set_global_settings(&settings, SettingsSource::Api)
             .await
             .expect("should persist global settings");
 
@@ -237,7 +849,7 @@ mod tests {
             .await
             .expect("should load stored global settings");
 
-        assert!(fetched.omni_enabled);
+        assert_eq!(fetched.omni_enabled, Some(true));
         assert_eq!(
             fetched.omni_config.unwrap().instance.as_deref(),
             Some("forge")
@@ -252,7 +864,7 @@ mod tests {
         let project_id = Uuid::new_v4();
 
         let global = ForgeProjectSettings {
-            omni_enabled: true,
+            omni_enabled: Some(true),
             omni_config: Some(OmniConfig {
                 enabled: true,
                 host: Some("https://global.omni".into()),
@@ -260,15 +872,17 @@ mod tests {
                 instance: Some("global".into()),
                 recipient: Some("global-recipient".into()),
                 recipient_type: Some(RecipientType::PhoneNumber),
+                ..Default::default()
             }),
+            ..Default::default()
         };
         service
-            .set_global_settings(&global)
+            .set_global_settings(&global, SettingsSource::Api)
             .await
             .expect("global settings should persist");
 
         let project = ForgeProjectSettings {
-            omni_enabled: true,
+            omni_enabled: Some(true),
             omni_config: Some(OmniConfig {
                 enabled: true,
                 host: Some("https://project.omni".into()),
@@ -276,10 +890,12 @@ mod tests {
                 instance: Some("project".into()),
                 recipient: Some("project-recipient".into()),
                 recipient_type: Some(RecipientType::UserId),
+                ..Default::default()
             }),
+            ..Default::default()
         };
         service
-            .set_forge_settings(project_id, &project)
+            .set_forge_settings(project_id, &project, SettingsSource::Api)
             .await
             .expect("project settings should persist");
 
@@ -295,6 +911,315 @@ mod tests {
         assert!(matches!(config.recipient_type, Some(RecipientType::UserId)));
     }
 
+    #[tokio::test]
+    async fn project_and_task_inherit_unset_omni_fields() {
+        let pool = setup_pool().await;
+        let service = ForgeConfigService::new(pool);
+        let project_id = Uuid::new_v4();
+        let task_id = Uuid::new_v4();
+
+        service
+            .set_global_settings(
+                &ForgeProjectSettings {
+                    omni_enabled: Some(true),
+                    omni_config: Some(OmniConfig {
+                        host: Some("https://global.omni".into()),
+                        api_key: Some("global-key".into()),
+                        instance: Some("global".into()),
+                        recipient: Some("global-recipient".into()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+                SettingsSource::Api,
+            )
+            .await
+            .unwrap();
+        service
+            .set_forge_settings(
+                project_id,
+                &ForgeProjectSettings {
+                    omni_config: Some(OmniConfig {
+                        recipient: Some("project-recipient".into()),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+                SettingsSource::Api,
+            )
+            .await
+            .unwrap();
+        service
+            .set_task_settings(
+                task_id,
+                &ForgeProjectSettings {
+                    notification_types: vec![NotificationType::PrMerged],
+                    ..Default::default()
+                },
+                SettingsSource::Api,
+            )
+            .await
+            .unwrap();
+
+        let config = service
+            .effective_omni_config(Some(project_id))
+            .await
+            .unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.host.as_deref(), Some("https://global.omni"));
+        assert_eq!(config.api_key.as_deref(), Some("global-key"));
+        assert_eq!(config.recipient.as_deref(), Some("project-recipient"));
+
+        let effective = service
+            .effective_settings(Some(project_id), Some(task_id))
+            .await
+            .unwrap();
+        assert_eq!(
+            effective.notification_types(),
+            vec![NotificationType::PrMerged]
+        );
+        // Levels that leave Omni switched neither on nor off inherit the global switch
+        assert!(effective.omni_config().enabled);
+        assert_eq!(
+            effective.origins.get("omni_enabled"),
+            Some(&ConfigOrigin::Global)
+        );
+        assert_eq!(
+            service
+                .get_task_settings(task_id)
+                .await
+                .unwrap()
+                .unwrap()
+                .omni_enabled,
+            None
+        );
+        assert_eq!(
+            effective.origins.get("omni_config.host"),
+            Some(&ConfigOrigin::Global)
+        );
+        assert_eq!(
+            effective.origins.get("omni_config.recipient"),
+            Some(&ConfigOrigin::Project)
+        );
+        assert_eq!(
+            effective.origins.get("notification_types"),
+            Some(&ConfigOrigin::Task)
+        );
+        assert_eq!(
+            effective.origins.get("notification_rules"),
+            Some(&ConfigOrigin::Default)
+        );
+    }
+
+    #[tokio::test]
+    async fn project_channels_replace_global_channels() {
+        let pool = setup_pool().await;
+        let service = ForgeConfigService::new(pool);
+        let project_id = Uuid::new_v4();
+        let other_project_id = Uuid::new_v4();
+
+        let channels = service
+            .effective_notification_channels(Some(project_id))
+            .await
+            .expect("channels should resolve without settings");
+        assert!(matches!(
+            channels.as_slice(),
+            [NotificationChannelConfig::Omni]
+        ));
+
+        let project = ForgeProjectSettings {
+            notification_channels: vec![NotificationChannelConfig::Command(CommandChannelConfig {
+                command: "notify-send".into(),
+                args: vec![],
+                timeout_secs: None,
+            })],
+            ..Default::default()
+        };
+        service
+            .set_forge_settings(project_id, &project, SettingsSource::Api)
+            .await
+            .expect("project settings should persist");
+
+        let channels = service
+            .effective_notification_channels(Some(project_id))
+            .await
+            .expect("project channels should resolve");
+        assert!(matches!(
+            channels.as_slice(),
+            [NotificationChannelConfig::Command(_)]
+        ));
+
+        let channels = service
+            .effective_notification_channels(Some(other_project_id))
+            .await
+            .expect("other project falls back to defaults");
+        assert!(matches!(
+            channels.as_slice(),
+            [NotificationChannelConfig::Omni]
+        ));
+    }
+
+    #[tokio::test]
+    async fn project_template_overrides_global_template() {
+        let pool = setup_pool().await;
+        let service = ForgeConfigService::new(pool);
+        let project_id = Uuid::new_v4();
+
+        service
+            .set_global_settings(
+                &ForgeProjectSettings {
+                    notification_template: Some("{{ title }}: {{ status }}".into()),
+                    ..Default::default()
+                },
+                SettingsSource::Api,
+            )
+            .await
+            .expect("global settings should persist");
+
+        let template = service
+            .effective_notification_template(Some(project_id), NotificationType::ExecutionCompleted)
+            .await
+            .expect("template should resolve");
+        assert_eq!(template.as_deref(), Some("{{ title }}: {{ status }}"));
+
+        service
+            .set_forge_settings(
+                project_id,
+                &ForgeProjectSettings {
+                    notification_template: Some("Tarefa {{ title }} finalizada".into()),
+                    ..Default::default()
+                },
+                SettingsSource::Api,
+            )
+            .await
+            .expect("project settings should persist");
+
+        let template = service
+            .effective_notification_template(Some(project_id), NotificationType::ExecutionCompleted)
+            .await
+            .expect("template should resolve");
+        assert_eq!(template.as_deref(), Some("Tarefa {{ title }} finalizada"));
+    }
+
+    #[tokio::test]
+    async fn event_templates_and_types_resolve_per_project() {
+        let pool = setup_pool().await;
+        let service = ForgeConfigService::new(pool);
+        let project_id = Uuid::new_v4();
+
+        let types = service
+            .effective_notification_types(Some(project_id))
+            .await
+            .expect("types should resolve");
+        assert_eq!(types, vec![NotificationType::ExecutionCompleted]);
+
+        service
+            .set_global_settings(
+                &ForgeProjectSettings {
+                    notification_template: Some("{{ title }}: {{ status }}".into()),
+                    notification_types: vec![
+                        NotificationType::ExecutionCompleted,
+                        NotificationType::PrMerged,
+                    ],
+                    notification_templates: BTreeMap::from([(
+                        NotificationType::PrMerged,
+                        "Merged PR #{{ pr_number }}".into(),
+                    )]),
+                    ..Default::default()
+                },
+                SettingsSource::Api,
+            )
+            .await
+            .expect("global settings should persist");
+        service
+            .set_forge_settings(
+                project_id,
+                &ForgeProjectSettings {
+                    notification_types: vec![NotificationType::MergeConflict],
+                    notification_templates: BTreeMap::from([(
+                        NotificationType::MergeConflict,
+                        "Conflict on {{ branch }}".into(),
+                    )]),
+                    ..Default::default()
+                },
+                SettingsSource::Api,
+            )
+            .await
+            .expect("project settings should persist");
+
+        let types = service
+            .effective_notification_types(Some(project_id))
+            .await
+            .expect("types should resolve");
+        assert_eq!(types, vec![NotificationType::MergeConflict]);
+
+        let template = |notification_type| {
+            let service = &service;
+            async move {
+                service
+                    .effective_notification_template(Some(project_id), notification_type)
+                    .await
+                    .expect("template should resolve")
+            }
+        };
+        assert_eq!(
+            template(NotificationType::MergeConflict).await.as_deref(),
+            Some("Conflict on {{ branch }}")
+        );
+        assert_eq!(
+            template(NotificationType::PrMerged).await.as_deref(),
+            Some("Merged PR #{{ pr_number }}")
+        );
+        // The generic template only covers execution exits
+        assert_eq!(
+            template(NotificationType::ExecutionCompleted)
+                .await
+                .as_deref(),
+            Some("{{ title }}: {{ status }}")
+        );
+        assert_eq!(template(NotificationType::TaskStatusChanged).await, None);
+
+        let invalid = ForgeProjectSettings {
+            notification_templates: BTreeMap::from([(
+                NotificationType::PrCreated,
+                "{% if %}".into(),
+            )]),
+            ..Default::default()
+        };
+        assert!(invalid.validate().is_err());
+
+        // Command channels run programs on the host; settings cannot enable them
+        let command = ForgeProjectSettings {
+            notification_channels: vec![NotificationChannelConfig::Command(CommandChannelConfig {
+                command: "sh".into(),
+                args: vec![],
+                timeout_secs: None,
+            })],
+            ..Default::default()
+        };
+        assert!(command.validate().is_err());
+
+        // Two recipients under one name would share a delivery record
+        let recipient = |recipient: &str, label: &str| OmniRecipient {
+            recipient: recipient.into(),
+            label: Some(label.into()),
+            ..Default::default()
+        };
+        let mut duplicate = ForgeProjectSettings {
+            omni_config: Some(OmniConfig {
+                recipients: vec![
+                    recipient("+15550001111", "oncall"),
+                    recipient("+15550002222", "oncall"),
+                ],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert!(duplicate.validate().is_err());
+        duplicate.omni_config.as_mut().unwrap().recipients[1].label = Some("backup".into());
+        assert!(duplicate.validate().is_ok());
+    }
+
     #[tokio::test]
     async fn forge_global_settings_singleton_constraint() {
         let pool = setup_pool().await;
@@ -331,4 +1256,430 @@ mod tests {
 
         assert_eq!(id, 1, "Global settings row should have ID 1");
     }
+
+    #[tokio::test]
+    async fn stored_settings_are_upgraded_or_reported() {
+        let pool = setup_pool().await;
+        let service = ForgeConfigService::new(pool.clone());
+        let project_id = Uuid::new_v4();
+        let task_id = Uuid::new_v4();
+
+        // Written before settings were versioned: no `enabled`, null lists
+        sqlx::query("UPDATE forge_global_settings SET forge_config = ? WHERE id = 1")
+            .bind(r#"{"omni_enabled":true,"omni_config":{"host":"https://omni.local"},"notification_rules":null}"#)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO forge_project_settings (project_id, forge_config) VALUES (?, ?)")
+            .bind(project_id)
+            .bind(r#"{"omni_enabled":"yes"}"#)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO forge_task_settings (task_id, forge_config) VALUES (?, ?)")
+            .bind(task_id)
+            .bind(r#"{"schema_version":99}"#)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let check = service.upgrade_stored_settings().await.unwrap();
+        assert_eq!(check.upgraded, 1);
+        assert_eq!(check.invalid.len(), 2);
+        assert!(check.invalid[0].contains(&project_id.to_string()));
+        assert!(check.invalid[1].contains("newer than the supported"));
+
+        let stored: Value = serde_json::from_str(&stored_global_json(&pool).await).unwrap();
+        assert_eq!(stored["schema_version"], SETTINGS_SCHEMA_VERSION);
+        assert_eq!(stored["omni_config"]["enabled"], true);
+        let global = service.get_global_settings().await.unwrap();
+        assert_eq!(
+            global.omni_config.unwrap().host.as_deref(),
+            Some("https://omni.local")
+        );
+
+        // Broken rows are reported to readers instead of read as defaults
+        let error = service.get_forge_settings(project_id).await.unwrap_err();
+        assert!(format!("{error:#}").contains("invalid type"));
+        assert!(service.get_task_settings(task_id).await.is_err());
+        assert!(
+            service
+                .effective_settings(Some(project_id), None)
+                .await
+                .is_err()
+        );
+        assert_eq!(service.upgrade_stored_settings().await.unwrap().upgraded, 0);
+    }
+
+    #[tokio::test]
+    async fn stored_custom_executors_are_upgraded_or_ignored() {
+        let pool = setup_pool().await;
+        let service = ForgeConfigService::new(pool.clone());
+        let legacy_id = Uuid::new_v4();
+        let broken_id = Uuid::new_v4();
+
+        sqlx::query(
+            "INSERT INTO forge_project_settings (project_id, custom_executors) VALUES (?, ?)",
+        )
+        .bind(legacy_id)
+        .bind(r#"{"CLAUDE_CODE":{"FAST":{"CLAUDE_CODE":{"model":"sonnet"}}}}"#)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO forge_project_settings (project_id, custom_executors, forge_config) VALUES (?, ?, ?)",
+        )
+        .bind(broken_id)
+        .bind(r#"{"NOT_AN_AGENT":{}}"#)
+        .bind(r#"{"schema_version":2,"omni_enabled":true}"#)
+        .execute(&pool)
+        .await
+        .unwrap();
+        // Leave only the custom executors outdated
+        sqlx::query("UPDATE forge_global_settings SET forge_config = '{\"schema_version\":2}'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // Reads work before the upgrade; broken overlays are ignored
+        let legacy = service.get_custom_executors(legacy_id).await.unwrap();
+        assert_eq!(
+            legacy.0["CLAUDE_CODE"].variants["FAST"]
+                .overrides
+                .model
+                .as_deref(),
+            Some("sonnet")
+        );
+        assert!(
+            service
+                .get_custom_executors(broken_id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            service
+                .get_forge_settings(broken_id)
+                .await
+                .unwrap()
+                .omni_enabled
+                == Some(true)
+        );
+
+        let check = service.upgrade_stored_settings().await.unwrap();
+        assert_eq!(check.upgraded, 1);
+        assert_eq!(check.invalid.len(), 1);
+        assert!(check.invalid[0].contains(&broken_id.to_string()));
+        assert_eq!(service.upgrade_stored_settings().await.unwrap().upgraded, 0);
+        assert_eq!(
+            service.get_custom_executors(legacy_id).await.unwrap(),
+            legacy
+        );
+    }
+
+    #[tokio::test]
+    async fn writes_are_recorded_and_restorable() {
+        let pool = setup_pool().await;
+        let service = ForgeConfigService::new(pool);
+        let project_id = Uuid::new_v4();
+
+        service
+            .set_global_settings(&settings_with_key("first-key"), SettingsSource::Api)
+            .await
+            .unwrap();
+        let mut edited = settings_with_key("second-key");
+        edited.notification_template = Some("{{ title }}".into());
+        service
+            .set_global_settings(&edited, SettingsSource::Mcp)
+            .await
+            .unwrap();
+        service
+            .set_forge_settings(
+                project_id,
+                &ForgeProjectSettings::default(),
+                SettingsSource::Import,
+            )
+            .await
+            .unwrap();
+
+        let all = service.settings_history(None, 10).await.unwrap();
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].scope, SettingsScope::Project);
+        assert_eq!(all[0].scope_id, Some(project_id));
+        assert_eq!(all[0].source, SettingsSource::Import);
+        assert!(all[0].old_value.is_none());
+
+        let global = service
+            .settings_history(Some((SettingsScope::Global, None)), 10)
+            .await
+            .unwrap();
+        assert_eq!(global.len(), 2);
+        assert_eq!(global[0].source, SettingsSource::Mcp);
+        // Secrets never reach the history
+        assert_eq!(global[0].new_value["omni_config"]["api_key"], REDACTED);
+        assert_eq!(
+            global[0].old_value.as_ref().unwrap()["omni_config"]["api_key"],
+            REDACTED
+        );
+
+        let restored = service
+            .restore_settings(global[1].id, SettingsSource::Api)
+            .await
+            .unwrap()
+            .expect("entry should exist");
+        assert_eq!(restored.id, global[1].id);
+        let current = service.get_global_settings().await.unwrap();
+        assert_eq!(current.notification_template, None);
+        // The redacted key in the entry keeps the one stored now
+        assert_eq!(
+            current.omni_config.unwrap().api_key.as_deref(),
+            Some("second-key")
+        );
+        assert_eq!(service.settings_history(None, 10).await.unwrap().len(), 4);
+        assert!(
+            service
+                .restore_settings(999, SettingsSource::Api)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn restore_rejects_entries_that_no_longer_validate() {
+        let pool = setup_pool().await;
+        let service = ForgeConfigService::new(pool.clone());
+        let settings = ForgeProjectSettings {
+            notification_template: Some("{{ title }}".into()),
+            ..Default::default()
+        };
+        service
+            .set_global_settings(&settings, SettingsSource::Api)
+            .await
+            .unwrap();
+        // An entry written before its template was checked
+        sqlx::query(
+            "UPDATE forge_settings_history SET new_value = json_set(new_value, '$.notification_template', '{{ title')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let history = service.settings_history(None, 10).await.unwrap();
+
+        let err = service
+            .restore_settings(history[0].id, SettingsSource::Api)
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<InvalidSettings>().is_some());
+        let current = service.get_global_settings().await.unwrap();
+        assert_eq!(
+            current.notification_template.as_deref(),
+            Some("{{ title }}")
+        );
+        assert_eq!(service.settings_history(None, 10).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn restored_channels_keep_their_own_secrets() {
+        let pool = setup_pool().await;
+        let service = ForgeConfigService::new(pool);
+        let webhook = |url: &str, secret: &str| {
+            NotificationChannelConfig::Webhook(WebhookChannelConfig {
+                url: url.into(),
+                secret: secret.into(),
+            })
+        };
+        let with_channels = |channels| ForgeProjectSettings {
+            notification_channels: channels,
+            ..Default::default()
+        };
+
+        for channels in [
+            vec![
+                webhook("https://a", "secret-a"),
+                webhook("https://b", "secret-b"),
+            ],
+            vec![webhook("https://b", REDACTED)],
+            vec![
+                webhook("https://c", "secret-c"),
+                webhook("https://b", REDACTED),
+            ],
+        ] {
+            service
+                .set_global_settings(&with_channels(channels), SettingsSource::Api)
+                .await
+                .unwrap();
+        }
+        let history = service
+            .settings_history(Some((SettingsScope::Global, None)), 10)
+            .await
+            .unwrap();
+
+        // The first entry's channel `a` was deleted since; its secret is gone
+        let err = service
+            .restore_settings(history[2].id, SettingsSource::Api)
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<InvalidSettings>().is_some());
+
+        // `b` moved to the front and keeps its secret, not the one of `c`
+        service
+            .restore_settings(history[1].id, SettingsSource::Api)
+            .await
+            .unwrap();
+        let current = service.get_global_settings().await.unwrap();
+        match current.notification_channels.as_slice() {
+            [NotificationChannelConfig::Webhook(channel)] => {
+                assert_eq!(channel.url, "https://b");
+                assert_eq!(channel.secret, "secret-b");
+            }
+            other => panic!("expected the webhook of b, got {other:?}"),
+        }
+    }
+
+    fn settings_with_key(api_key: &str) -> ForgeProjectSettings {
+        ForgeProjectSettings {
+            omni_enabled: Some(true),
+            omni_config: Some(OmniConfig {
+                enabled: true,
+                host: Some("https://omni.local".into()),
+                api_key: Some(api_key.into()),
+                instance: Some("forge".into()),
+                recipient: Some("+5511999999999".into()),
+                recipient_type: Some(RecipientType::PhoneNumber),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    async fn stored_global_json(pool: &SqlitePool) -> String {
+        sqlx::query_scalar("SELECT forge_config FROM forge_global_settings WHERE id = 1")
+            .fetch_one(pool)
+            .await
+            .expect("should read stored settings")
+    }
+
+    #[tokio::test]
+    async fn secrets_are_encrypted_at_rest() {
+        let pool = setup_pool().await;
+        let service =
+            ForgeConfigService::with_secret_cipher(pool.clone(), SecretCipher::generate());
+        let project_id = Uuid::new_v4();
+
+        service
+            .set_global_settings(&settings_with_key("global-key"), SettingsSource::Api)
+            .await
+            .unwrap();
+        service
+            .set_forge_settings(
+                project_id,
+                &settings_with_key("project-key"),
+                SettingsSource::Api,
+            )
+            .await
+            .unwrap();
+
+        let stored = stored_global_json(&pool).await;
+        assert!(!stored.contains("global-key"));
+        assert!(stored.contains("enc:v1:"));
+        assert!(stored.contains("https://omni.local"));
+        let stored_project: String =
+            sqlx::query_scalar("SELECT forge_config FROM forge_project_settings")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(!stored_project.contains("project-key"));
+
+        let global = service.effective_omni_config(None).await.unwrap();
+        assert_eq!(global.api_key.as_deref(), Some("global-key"));
+        let project = service
+            .effective_omni_config(Some(project_id))
+            .await
+            .unwrap();
+        assert_eq!(project.api_key.as_deref(), Some("project-key"));
+
+        // Another key cannot read the stored secrets
+        let other = ForgeConfigService::with_secret_cipher(pool.clone(), SecretCipher::generate());
+        assert!(other.get_global_settings().await.is_err());
+        let keyless = ForgeConfigService::new(pool);
+        assert!(keyless.get_global_settings().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn redacted_secret_keeps_stored_value() {
+        let pool = setup_pool().await;
+        let service = ForgeConfigService::with_secret_cipher(pool, SecretCipher::generate());
+
+        service
+            .set_global_settings(&settings_with_key("global-key"), SettingsSource::Api)
+            .await
+            .unwrap();
+
+        let mut edited = service.get_global_settings().await.unwrap().redacted();
+        assert_eq!(
+            edited.omni_config.as_ref().unwrap().api_key.as_deref(),
+            Some(REDACTED)
+        );
+        edited.omni_config.as_mut().unwrap().instance = Some("renamed".into());
+        service
+            .set_global_settings(&edited, SettingsSource::Api)
+            .await
+            .unwrap();
+
+        let fetched = service.effective_omni_config(None).await.unwrap();
+        assert_eq!(fetched.api_key.as_deref(), Some("global-key"));
+        assert_eq!(fetched.instance.as_deref(), Some("renamed"));
+    }
+
+    #[tokio::test]
+    async fn rotating_the_key_reencrypts_stored_rows() {
+        let pool = setup_pool().await;
+
+        // Plaintext saved before encryption was enabled
+        ForgeConfigService::new(pool.clone())
+            .set_global_settings(&settings_with_key("global-key"), SettingsSource::Api)
+            .await
+            .unwrap();
+        assert!(stored_global_json(&pool).await.contains("global-key"));
+
+        let service =
+            ForgeConfigService::with_secret_cipher(pool.clone(), SecretCipher::generate());
+        service
+            .set_forge_settings(
+                Uuid::new_v4(),
+                &settings_with_key("project-key"),
+                SettingsSource::Api,
+            )
+            .await
+            .unwrap();
+        assert_eq!(service.encrypt_stored_secrets().await.unwrap(), 1);
+        assert_eq!(service.encrypt_stored_secrets().await.unwrap(), 0);
+        assert!(!stored_global_json(&pool).await.contains("global-key"));
+
+        let new_key = SecretCipher::generate();
+        assert_eq!(service.rotate_secret_key(new_key.clone()).await.unwrap(), 2);
+        assert_eq!(
+            service
+                .effective_omni_config(None)
+                .await
+                .unwrap()
+                .api_key
+                .as_deref(),
+            Some("global-key")
+        );
+
+        let reloaded = ForgeConfigService::with_secret_cipher(pool, new_key);
+        assert_eq!(
+            reloaded
+                .get_global_settings()
+                .await
+                .unwrap()
+                .omni_config
+                .unwrap()
+                .api_key
+                .as_deref(),
+            Some("global-key")
+        );
+    }
 }
@@ -1,22 +1,123 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use forge_notifications::{NotificationChannelConfig, NotificationType};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 use uuid::Uuid;
 
+/// Settings rejected before anything was stored, e.g. a redacted secret with no
+/// stored value to keep. The API answers these with 400.
+#[derive(Debug)]
+pub struct InvalidSettings(pub String);
+
+impl fmt::Display for InvalidSettings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for InvalidSettings {}
+
 /// Project-level configuration stored in auxiliary tables
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct ProjectConfig {
     pub project_id: Uuid,
-    #[ts(type = "JsonValue | null")]
-    pub custom_executors: Option<serde_json::Value>,
+    pub custom_executors: Option<crate::executors::CustomExecutors>,
     #[ts(type = "JsonValue | null")]
     pub forge_config: Option<serde_json::Value>,
 }
 
-/// Configuration for forge-specific project settings
+/// Configuration for forge-specific project settings. Unknown keys are rejected,
+/// so a misspelled setting is reported instead of silently dropped.
 #[derive(Debug, Clone, Serialize, Deserialize, TS, Default)]
+#[serde(deny_unknown_fields)]
 pub struct ForgeProjectSettings {
+    /// Unset inherits the level below; off when no level sets it
     #[serde(default)]
-    pub omni_enabled: bool,
+    pub omni_enabled: Option<bool>,
     #[serde(default)]
     pub omni_config: Option<forge_omni::OmniConfig>,
+    /// Channels that receive task notifications. Empty means Omni only.
+    #[serde(default)]
+    pub notification_channels: Vec<forge_notifications::NotificationChannelConfig>,
+    /// Jinja template for the notification text; see `forge_notifications::TemplateContext`
+    /// for the available placeholders. Unset keeps the default message.
+    #[serde(default)]
+    pub notification_template: Option<String>,
+    /// Events that queue notifications. Empty means execution exits only.
+    #[serde(default)]
+    pub notification_types: Vec<NotificationType>,
+    /// Template per event; `notification_template` still applies to execution exits
+    /// without one of their own
+    #[serde(default)]
+    pub notification_templates: BTreeMap<NotificationType, String>,
+    /// Filters an execution event must pass before it is delivered
+    #[serde(default)]
+    pub notification_rules: Vec<forge_notifications::NotificationRule>,
+}
+
+impl ForgeProjectSettings {
+    /// Reject settings that would only fail later, when a notification is sent.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let Some(template) = &self.notification_template {
+            forge_notifications::validate_template(template)?;
+        }
+        for (notification_type, template) in &self.notification_templates {
+            forge_notifications::validate_template(template)
+                .map_err(|err| anyhow::anyhow!("{} template: {err}", notification_type.as_str()))?;
+        }
+        for rule in &self.notification_rules {
+            rule.validate()?;
+        }
+        if self
+            .notification_channels
+            .iter()
+            .any(|channel| matches!(channel, NotificationChannelConfig::Command(_)))
+        {
+            forge_notifications::ensure_command_channels_enabled()?;
+        }
+        if let Some(omni_config) = &self.omni_config {
+            if let Some(quiet_hours) = &omni_config.quiet_hours {
+                quiet_hours.validate()?;
+            }
+            if let Some(rate_limit) = &omni_config.rate_limit
+                && (rate_limit.max_messages == 0 || rate_limit.period_secs == 0)
+            {
+                anyhow::bail!("Omni rate limit needs a positive message count and period");
+            }
+            if let Some(inbound) = &omni_config.inbound
+                && inbound.enabled
+                && inbound.secret.trim().is_empty()
+            {
+                anyhow::bail!("Omni inbound commands need a shared secret");
+            }
+            if let Some(http) = &omni_config.http {
+                http.validate()?;
+            }
+            // Deliveries are recorded per recipient name, so names must be unique
+            for (index, recipient) in omni_config.recipients.iter().enumerate() {
+                let name = recipient.display_name();
+                if omni_config.recipients[..index]
+                    .iter()
+                    .any(|earlier| earlier.display_name() == name)
+                {
+                    anyhow::bail!(
+                        "Omni recipient '{name}' is listed twice; give each recipient its own label"
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy with every stored secret replaced by [`crate::secrets::REDACTED`], as
+    /// returned by the API.
+    pub fn redacted(&self) -> Self {
+        let Ok(mut value) = serde_json::to_value(self) else {
+            return Self::default();
+        };
+        crate::secrets::redact_secrets(&mut value);
+        serde_json::from_value(value).unwrap_or_default()
+    }
 }
@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use forge_omni::OmniConfig;
+
+use crate::command::{CommandChannel, ensure_command_channels_enabled};
+use crate::email::EmailChannel;
+use crate::omni::OmniChannel;
+use crate::slack::SlackChannel;
+use crate::types::{NotificationChannelConfig, NotificationMessage};
+use crate::webhook::WebhookChannel;
+
+/// An outbound destination for task notifications.
+#[async_trait]
+pub trait NotificationChannel: Send + Sync {
+    /// Channel kind, e.g. `omni` or `slack`.
+    fn kind(&self) -> &'static str;
+
+    async fn send(&self, message: &NotificationMessage) -> Result<()>;
+}
+
+/// Seconds an HTTP channel waits for a connection.
+pub const HTTP_CONNECT_TIMEOUT_SECS: u64 = 10;
+/// Seconds an HTTP channel request may take, including the response. The worker
+/// delivers one row at a time, so a hung endpoint must not hold it for long.
+pub const HTTP_REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// Client for the HTTP channels, with connect and request timeouts.
+pub(crate) fn http_client() -> Result<reqwest::Client> {
+    Ok(reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(HTTP_CONNECT_TIMEOUT_SECS))
+        .timeout(Duration::from_secs(HTTP_REQUEST_TIMEOUT_SECS))
+        .user_agent(forge_omni::http::DEFAULT_USER_AGENT)
+        .build()?)
+}
+
+/// Build a channel from its settings. `omni_config` is the effective Omni
+/// configuration used by [`NotificationChannelConfig::Omni`].
+pub fn build_channel(
+    config: &NotificationChannelConfig,
+    omni_config: &OmniConfig,
+) -> Result<Box<dyn NotificationChannel>> {
+    Ok(match config {
+        NotificationChannelConfig::Omni => Box::new(OmniChannel::new(omni_config.clone())?),
+        NotificationChannelConfig::Slack(slack) => Box::new(SlackChannel::new(slack.clone())?),
+        NotificationChannelConfig::Email(email) => Box::new(EmailChannel::new(email.clone())?),
+        NotificationChannelConfig::Command(command) => {
+            ensure_command_channels_enabled()?;
+            Box::new(CommandChannel::new(command.clone()))
+        }
+        NotificationChannelConfig::Webhook(webhook) => {
+            Box::new(WebhookChannel::new(webhook.clone())?)
+        }
+    })
+}
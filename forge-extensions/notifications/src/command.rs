@@ -0,0 +1,153 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use tokio::process::Command;
+
+use crate::channel::NotificationChannel;
+use crate::types::{CommandChannelConfig, NotificationMessage};
+
+const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Set to `1` or `true` on the server to allow `command` channels. They run a
+/// program on the host, so only the operator can turn them on, not settings
+/// saved through the API or an imported bundle.
+pub const COMMAND_CHANNELS_ENV_VAR: &str = "FORGE_ENABLE_COMMAND_CHANNELS";
+
+pub fn command_channels_enabled() -> bool {
+    std::env::var(COMMAND_CHANNELS_ENV_VAR)
+        .is_ok_and(|value| matches!(value.trim().to_ascii_lowercase().as_str(), "1" | "true"))
+}
+
+/// Error for a `command` channel while [`COMMAND_CHANNELS_ENV_VAR`] is off.
+pub fn ensure_command_channels_enabled() -> Result<()> {
+    if !command_channels_enabled() {
+        return Err(anyhow!(
+            "Command channels are disabled; set {COMMAND_CHANNELS_ENV_VAR}=1 on the server to allow them"
+        ));
+    }
+    Ok(())
+}
+
+/// Runs a local command for each notification, e.g. a desktop notifier.
+pub struct CommandChannel {
+    config: CommandChannelConfig,
+}
+
+impl CommandChannel {
+    pub fn new(config: CommandChannelConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for CommandChannel {
+    fn kind(&self) -> &'static str {
+        "command"
+    }
+
+    async fn send(&self, message: &NotificationMessage) -> Result<()> {
+        let timeout = self
+            .config
+            .timeout_secs
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_COMMAND_TIMEOUT);
+
+        let mut command = Command::new(&self.config.command);
+        command
+            .args(&self.config.args)
+            .env(
+                "FORGE_NOTIFICATION_TYPE",
+                message.notification_type.as_str(),
+            )
+            .env("FORGE_NOTIFICATION_TITLE", &message.title)
+            .env("FORGE_NOTIFICATION_SUMMARY", &message.summary)
+            .env(
+                "FORGE_NOTIFICATION_URL",
+                message.url.as_deref().unwrap_or_default(),
+            )
+            .env("FORGE_NOTIFICATION_TEXT", message.to_text())
+            .env("FORGE_NOTIFICATION_METADATA", message.metadata.to_string())
+            .kill_on_drop(true);
+
+        let output = tokio::time::timeout(timeout, command.output())
+            .await
+            .map_err(|_| anyhow!("Notification command timed out after {timeout:?}"))?
+            .with_context(|| {
+                format!("failed to run notification command {}", self.config.command)
+            })?;
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Notification command exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn message() -> NotificationMessage {
+        NotificationMessage {
+            id: "notif-1".into(),
+            title: "Fix login".into(),
+            summary: "❌ Execution failed".into(),
+            url: None,
+            metadata: serde_json::json!({ "status": "failed" }),
+            notification_type: Default::default(),
+            body: None,
+            diff_stats: None,
+            attachments: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn exposes_notification_through_environment() {
+        let channel = CommandChannel::new(CommandChannelConfig {
+            command: "sh".into(),
+            args: vec![
+                "-c".into(),
+                r#"test "$FORGE_NOTIFICATION_TITLE" = "Fix login""#.into(),
+            ],
+            timeout_secs: None,
+        });
+
+        channel
+            .send(&message())
+            .await
+            .expect("command should succeed");
+    }
+
+    #[test]
+    fn disabled_without_the_operator_flag() {
+        // The test environment never sets the flag
+        assert!(!command_channels_enabled());
+        assert!(
+            ensure_command_channels_enabled()
+                .unwrap_err()
+                .to_string()
+                .contains(COMMAND_CHANNELS_ENV_VAR)
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_non_zero_exit() {
+        let channel = CommandChannel::new(CommandChannelConfig {
+            command: "sh".into(),
+            args: vec!["-c".into(), "echo boom >&2; exit 3".into()],
+            timeout_secs: None,
+        });
+
+        let err = channel
+            .send(&message())
+            .await
+            .expect_err("command should fail");
+        assert!(err.to_string().contains("boom"));
+    }
+}
@@ -0,0 +1,98 @@
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use crate::channel::NotificationChannel;
+use crate::types::{EmailChannelConfig, NotificationMessage};
+
+/// Sends notifications as plain-text email over SMTP.
+pub struct EmailChannel {
+    from: Mailbox,
+    to: Vec<Mailbox>,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl EmailChannel {
+    pub fn new(config: EmailChannelConfig) -> Result<Self> {
+        let from: Mailbox = config
+            .from
+            .parse()
+            .with_context(|| format!("invalid sender address: {}", config.from))?;
+        let to = config
+            .to
+            .iter()
+            .map(|address| {
+                address
+                    .parse::<Mailbox>()
+                    .with_context(|| format!("invalid recipient address: {address}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if to.is_empty() {
+            return Err(anyhow!("Email channel has no recipients"));
+        }
+
+        let mut builder = if config.starttls {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)?
+        } else {
+            AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host)?
+        };
+        if let Some(port) = config.smtp_port {
+            builder = builder.port(port);
+        }
+        if let (Some(username), Some(password)) = (&config.username, &config.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        Ok(Self {
+            from,
+            to,
+            transport: builder.build(),
+        })
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for EmailChannel {
+    fn kind(&self) -> &'static str {
+        "email"
+    }
+
+    async fn send(&self, message: &NotificationMessage) -> Result<()> {
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .subject(message.headline());
+        for recipient in &self.to {
+            builder = builder.to(recipient.clone());
+        }
+
+        let email = builder.body(message.to_text())?;
+        self.transport.send(email).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_invalid_addresses() {
+        let config = EmailChannelConfig {
+            smtp_host: "smtp.example.com".into(),
+            smtp_port: None,
+            username: None,
+            password: None,
+            from: "forge@example.com".into(),
+            to: vec!["not an address".into()],
+            starttls: true,
+        };
+
+        let err = EmailChannel::new(config)
+            .err()
+            .expect("address should be rejected");
+        assert!(err.to_string().contains("invalid recipient address"));
+    }
+}
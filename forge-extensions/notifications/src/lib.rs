@@ -0,0 +1,29 @@
+//! Forge Notifications Extension
+//!
+//! Channel abstraction for outbound task notifications. Omni is one channel among
+//! Slack-compatible webhooks, signed generic webhooks, SMTP email and local command
+//! hooks; the forge-app worker fans a queued notification out to every channel a
+//! project selects.
+
+pub mod channel;
+pub mod command;
+pub mod email;
+pub mod omni;
+pub mod rules;
+pub mod slack;
+pub mod template;
+pub mod types;
+pub mod webhook;
+
+pub use channel::{NotificationChannel, build_channel};
+pub use command::{
+    COMMAND_CHANNELS_ENV_VAR, CommandChannel, command_channels_enabled,
+    ensure_command_channels_enabled,
+};
+pub use email::EmailChannel;
+pub use omni::OmniChannel;
+pub use rules::{NotificationEvent, NotificationRule, rejecting_rule};
+pub use slack::SlackChannel;
+pub use template::{DiffStats, TemplateContext, render_template, validate_template};
+pub use types::*;
+pub use webhook::WebhookChannel;
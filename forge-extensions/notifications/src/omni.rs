@@ -0,0 +1,82 @@
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use forge_omni::{OmniAttachment, OmniAttachmentKind, OmniAttachmentMode, OmniConfig, OmniService};
+
+use crate::channel::NotificationChannel;
+use crate::types::{NotificationMessage, NotificationType};
+
+/// Delivers notifications through Omni's `send-text` endpoint.
+///
+/// A rendered project template is sent verbatim; otherwise Omni's default task message
+/// (the shared text for events other than execution exits) is used, with the diff
+/// summary when `message.diff_summary` is set. Attachments follow as documents when
+/// `message.attachment` asks for them.
+pub struct OmniChannel {
+    service: OmniService,
+}
+
+impl OmniChannel {
+    pub fn new(config: OmniConfig) -> Result<Self> {
+        let host = config
+            .host
+            .as_deref()
+            .ok_or_else(|| anyhow!("Omni host not configured"))?;
+        if host.is_empty() {
+            return Err(anyhow!("Omni host configuration empty"));
+        }
+
+        Ok(Self {
+            service: OmniService::new(config),
+        })
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for OmniChannel {
+    fn kind(&self) -> &'static str {
+        "omni"
+    }
+
+    async fn send(&self, message: &NotificationMessage) -> Result<()> {
+        let options = self.service.config().message.clone().unwrap_or_default();
+
+        let text = match &message.body {
+            Some(body) => body.clone(),
+            None => {
+                let mut text = match message.notification_type {
+                    NotificationType::ExecutionCompleted => OmniService::task_message(
+                        &message.title,
+                        &message.summary,
+                        message.url.as_deref(),
+                    ),
+                    _ => message.to_text(),
+                };
+                if options.diff_summary
+                    && let Some(stats) = message.diff_stats
+                {
+                    text = format!("{}\n📊 {}", text.trim_end(), stats.summary());
+                }
+                text
+            }
+        };
+
+        let attachments: Vec<OmniAttachment> = match options.attachment {
+            OmniAttachmentMode::None => vec![],
+            OmniAttachmentMode::Patch => message
+                .attachments
+                .iter()
+                .map(|attachment| OmniAttachment {
+                    kind: OmniAttachmentKind::Document,
+                    filename: attachment.filename.clone(),
+                    mime_type: attachment.mime_type.clone(),
+                    content: attachment.content.clone(),
+                    caption: Some(message.title.clone()),
+                })
+                .collect(),
+        };
+
+        self.service
+            .send_notification_with_attachments(&text, &attachments)
+            .await
+    }
+}
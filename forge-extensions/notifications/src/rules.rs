@@ -0,0 +1,252 @@
+//! Notification rules.
+//!
+//! Every execution process that finishes queues a notification, including setup
+//! scripts and dev servers. Rules narrow that down: an event is delivered only if it
+//! passes every rule, and the first rule it fails is recorded as the skip reason.
+//! Run reasons and statuses describe execution exits, so other events (task status
+//! changes, pull requests, conflicts) are only filtered by executor and branch.
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::types::NotificationType;
+
+const RUN_REASONS: &[&str] = &["setupscript", "cleanupscript", "codingagent", "devserver"];
+const STATUSES: &[&str] = &["completed", "failed", "killed"];
+
+/// Conditions an execution event must meet to be delivered. Empty lists and a
+/// missing pattern match everything.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS)]
+pub struct NotificationRule {
+    pub name: String,
+    /// Execution run reasons, e.g. `codingagent`
+    #[serde(default)]
+    pub run_reasons: Vec<String>,
+    /// Terminal statuses: `completed`, `failed`, `killed`
+    #[serde(default)]
+    pub statuses: Vec<String>,
+    /// Executor names, compared case-insensitively
+    #[serde(default)]
+    pub executors: Vec<String>,
+    /// Glob matched against the attempt branch; `*` and `?` are supported
+    #[serde(default)]
+    pub branch_pattern: Option<String>,
+}
+
+/// The fields of a queued notification that rules inspect.
+#[derive(Clone, Copy, Debug)]
+pub struct NotificationEvent<'a> {
+    pub notification_type: NotificationType,
+    /// Unknown for rows queued before run reasons were recorded; such rows are
+    /// not filtered by run reason.
+    pub run_reason: Option<&'a str>,
+    pub status: &'a str,
+    pub executor: &'a str,
+    pub branch: &'a str,
+}
+
+impl NotificationRule {
+    pub fn matches(&self, event: &NotificationEvent<'_>) -> bool {
+        let run_reason_ok = match event.run_reason {
+            Some(run_reason) => {
+                self.run_reasons.is_empty()
+                    || self.run_reasons.iter().any(|allowed| allowed == run_reason)
+            }
+            None => true,
+        };
+        let status_ok = event.notification_type != NotificationType::ExecutionCompleted
+            || self.statuses.is_empty()
+            || self.statuses.iter().any(|allowed| allowed == event.status);
+        let executor_ok = self.executors.is_empty()
+            || self
+                .executors
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(event.executor));
+        let branch_ok = self
+            .branch_pattern
+            .as_deref()
+            .is_none_or(|pattern| glob_match(pattern, event.branch));
+
+        run_reason_ok && status_ok && executor_ok && branch_ok
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() {
+            return Err(anyhow!("notification rule name must not be empty"));
+        }
+        if let Some(reason) = self
+            .run_reasons
+            .iter()
+            .find(|reason| !RUN_REASONS.contains(&reason.as_str()))
+        {
+            return Err(anyhow!(
+                "notification rule '{}': unknown run reason '{}'",
+                self.name,
+                reason
+            ));
+        }
+        if let Some(status) = self
+            .statuses
+            .iter()
+            .find(|status| !STATUSES.contains(&status.as_str()))
+        {
+            return Err(anyhow!(
+                "notification rule '{}': unknown status '{}'",
+                self.name,
+                status
+            ));
+        }
+        if self
+            .branch_pattern
+            .as_deref()
+            .is_some_and(|pattern| pattern.trim().is_empty())
+        {
+            return Err(anyhow!(
+                "notification rule '{}': branch pattern must not be empty",
+                self.name
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The first rule the event fails, if any.
+pub fn rejecting_rule<'a>(
+    rules: &'a [NotificationRule],
+    event: &NotificationEvent<'_>,
+) -> Option<&'a NotificationRule> {
+    rules.iter().find(|rule| !rule.matches(event))
+}
+
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event<'a>(run_reason: Option<&'a str>, status: &'a str) -> NotificationEvent<'a> {
+        NotificationEvent {
+            notification_type: NotificationType::ExecutionCompleted,
+            run_reason,
+            status,
+            executor: "CLAUDE_CODE",
+            branch: "forge/fix-login",
+        }
+    }
+
+    #[test]
+    fn first_failing_rule_rejects_event() {
+        let rules = vec![
+            NotificationRule {
+                name: "coding agent only".into(),
+                run_reasons: vec!["codingagent".into()],
+                ..Default::default()
+            },
+            NotificationRule {
+                name: "failures only".into(),
+                statuses: vec!["failed".into(), "killed".into()],
+                ..Default::default()
+            },
+        ];
+
+        let setup = event(Some("setupscript"), "failed");
+        assert_eq!(
+            rejecting_rule(&rules, &setup).map(|rule| rule.name.as_str()),
+            Some("coding agent only")
+        );
+
+        let completed = event(Some("codingagent"), "completed");
+        assert_eq!(
+            rejecting_rule(&rules, &completed).map(|rule| rule.name.as_str()),
+            Some("failures only")
+        );
+
+        assert!(rejecting_rule(&rules, &event(Some("codingagent"), "failed")).is_none());
+        assert!(rejecting_rule(&rules, &event(None, "failed")).is_none());
+
+        let merged = NotificationEvent {
+            notification_type: NotificationType::PrMerged,
+            ..event(None, "merged")
+        };
+        assert!(rejecting_rule(&rules, &merged).is_none());
+    }
+
+    #[test]
+    fn matches_executor_and_branch_pattern() {
+        let rule = NotificationRule {
+            name: "claude on forge branches".into(),
+            executors: vec!["claude_code".into()],
+            branch_pattern: Some("forge/*".into()),
+            ..Default::default()
+        };
+
+        assert!(rule.matches(&event(Some("codingagent"), "completed")));
+        assert!(!rule.matches(&NotificationEvent {
+            branch: "main",
+            ..event(Some("codingagent"), "completed")
+        }));
+        assert!(!rule.matches(&NotificationEvent {
+            executor: "CODEX",
+            ..event(Some("codingagent"), "completed")
+        }));
+    }
+
+    #[test]
+    fn glob_supports_wildcards() {
+        assert!(glob_match("forge/*", "forge/abc-123"));
+        assert!(glob_match("*-hotfix", "forge/login-hotfix"));
+        assert!(glob_match("forge/?bc*", "forge/abc-1"));
+        assert!(!glob_match("forge/*", "feature/forge"));
+        assert!(glob_match("*", ""));
+    }
+
+    #[test]
+    fn validation_rejects_unknown_values() {
+        let rule = NotificationRule {
+            name: "typo".into(),
+            run_reasons: vec!["coding_agent".into()],
+            ..Default::default()
+        };
+        assert!(rule.validate().is_err());
+
+        let unnamed = NotificationRule::default();
+        assert!(unnamed.validate().is_err());
+
+        let ok = NotificationRule {
+            name: "failures".into(),
+            statuses: vec!["failed".into()],
+            branch_pattern: Some("forge/*".into()),
+            ..Default::default()
+        };
+        assert!(ok.validate().is_ok());
+    }
+}
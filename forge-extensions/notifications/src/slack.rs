@@ -0,0 +1,130 @@
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use serde_json::json;
+
+use crate::channel::{NotificationChannel, http_client};
+use crate::types::{NotificationMessage, SlackChannelConfig};
+
+/// Posts to a Slack-compatible incoming webhook.
+pub struct SlackChannel {
+    config: SlackChannelConfig,
+    client: reqwest::Client,
+}
+
+impl SlackChannel {
+    pub fn new(config: SlackChannelConfig) -> Result<Self> {
+        Ok(Self {
+            config,
+            client: http_client()?,
+        })
+    }
+
+    fn payload(&self, message: &NotificationMessage) -> serde_json::Value {
+        let text = match (&message.body, &message.url) {
+            (Some(body), _) => body.clone(),
+            (None, Some(url)) => format!("*<{}|{}>*\n{}", url, message.headline(), message.summary),
+            (None, None) => format!("*{}*\n{}", message.headline(), message.summary),
+        };
+
+        let mut payload = json!({ "text": text });
+        if let Some(username) = &self.config.username {
+            payload["username"] = json!(username);
+        }
+        if let Some(channel) = &self.config.channel {
+            payload["channel"] = json!(channel);
+        }
+        payload
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for SlackChannel {
+    fn kind(&self) -> &'static str {
+        "slack"
+    }
+
+    async fn send(&self, message: &NotificationMessage) -> Result<()> {
+        let response = self
+            .client
+            .post(&self.config.webhook_url)
+            .json(&self.payload(message))
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow!("Slack webhook returned {}: {}", status, text));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+
+    #[tokio::test]
+    async fn posts_text_payload_to_webhook() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/services/T000/B000")
+                .json_body_partial(r##"{"channel": "#forge"}"##);
+            then.status(200).body("ok");
+        });
+
+        let channel = SlackChannel::new(SlackChannelConfig {
+            webhook_url: server.url("/services/T000/B000"),
+            username: None,
+            channel: Some("#forge".into()),
+        })
+        .unwrap();
+
+        channel
+            .send(&NotificationMessage {
+                id: "notif-1".into(),
+                title: "Fix login".into(),
+                summary: "✅ Execution completed".into(),
+                url: Some("http://forge.example/projects/1/tasks/2".into()),
+                metadata: serde_json::Value::Null,
+                notification_type: Default::default(),
+                body: None,
+                diff_stats: None,
+                attachments: vec![],
+            })
+            .await
+            .expect("slack webhook should accept the payload");
+
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn rendered_body_replaces_default_text() {
+        let channel = SlackChannel::new(SlackChannelConfig {
+            webhook_url: "https://hooks.slack.test/T000/B000".into(),
+            username: None,
+            channel: None,
+        })
+        .unwrap();
+
+        let payload = channel.payload(&NotificationMessage {
+            id: "notif-1".into(),
+            title: "Fix login".into(),
+            summary: "✅ Execution completed".into(),
+            url: None,
+            metadata: serde_json::Value::Null,
+            notification_type: Default::default(),
+            body: Some("Fix login is done".into()),
+            diff_stats: None,
+            attachments: vec![],
+        });
+
+        assert_eq!(payload["text"], "Fix login is done");
+    }
+}
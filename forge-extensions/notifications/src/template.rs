@@ -0,0 +1,217 @@
+//! Project-defined notification templates.
+//!
+//! Templates use Jinja syntax rendered by minijinja with no loader attached, so a
+//! template can only read the values in [`TemplateContext`]; it cannot include
+//! files or call out of the sandbox.
+
+use anyhow::{Result, anyhow};
+use minijinja::{Environment, UndefinedBehavior};
+use serde::{Deserialize, Serialize};
+
+/// Upper bound on stored template size.
+pub const MAX_TEMPLATE_LEN: usize = 4096;
+
+/// Placeholders available to notification templates.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct TemplateContext {
+    /// Notification type, e.g. `execution_completed` or `pr_merged`
+    pub event: String,
+    pub title: String,
+    /// Execution status (`completed`, `failed`, `killed`), new task status
+    /// (`inreview`, `done`), pull request status (`open`, `merged`) or `conflict`
+    pub status: String,
+    /// Task status before a `task_status_changed` event
+    pub previous_status: Option<String>,
+    /// Default status block, for templates that only add a prefix or footer
+    pub summary: String,
+    pub executor: String,
+    pub branch: String,
+    pub exit_code: Option<i64>,
+    /// Human-readable run time, e.g. `4m 07s`
+    pub duration: Option<String>,
+    pub duration_secs: Option<i64>,
+    pub task_url: Option<String>,
+    /// Shortstat line, e.g. `3 files changed, +42 -7`
+    pub diff_stats: Option<String>,
+    pub files_changed: Option<u64>,
+    pub insertions: Option<u64>,
+    pub deletions: Option<u64>,
+    pub pr_number: Option<i64>,
+    pub pr_url: Option<String>,
+    /// Operation that hit conflicts: `merge` or `rebase`
+    pub conflict_op: Option<String>,
+}
+
+/// Line counts of an attempt branch against its base branch.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffStats {
+    pub files_changed: u64,
+    pub insertions: u64,
+    pub deletions: u64,
+}
+
+impl DiffStats {
+    pub fn summary(&self) -> String {
+        let files = if self.files_changed == 1 {
+            "file"
+        } else {
+            "files"
+        };
+        format!(
+            "{} {} changed, +{} -{}",
+            self.files_changed, files, self.insertions, self.deletions
+        )
+    }
+}
+
+impl TemplateContext {
+    pub fn with_duration_secs(mut self, secs: Option<i64>) -> Self {
+        self.duration_secs = secs;
+        self.duration = secs.map(format_duration);
+        self
+    }
+
+    pub fn with_diff_stats(mut self, stats: Option<DiffStats>) -> Self {
+        self.diff_stats = stats.map(|stats| stats.summary());
+        self.files_changed = stats.map(|stats| stats.files_changed);
+        self.insertions = stats.map(|stats| stats.insertions);
+        self.deletions = stats.map(|stats| stats.deletions);
+        self
+    }
+
+    /// Context with every placeholder filled, used to validate templates on save.
+    fn sample() -> Self {
+        TemplateContext {
+            event: "execution_completed".into(),
+            title: "Sample task".into(),
+            status: "completed".into(),
+            previous_status: Some("inprogress".into()),
+            summary: "✅ Execution completed".into(),
+            executor: "CLAUDE_CODE".into(),
+            branch: "forge/sample".into(),
+            exit_code: Some(0),
+            task_url: Some("http://127.0.0.1:8887/projects/sample/tasks/sample".into()),
+            pr_number: Some(42),
+            pr_url: Some("https://github.com/sample/sample/pull/42".into()),
+            conflict_op: Some("rebase".into()),
+            ..Default::default()
+        }
+        .with_duration_secs(Some(247))
+        .with_diff_stats(Some(DiffStats {
+            files_changed: 3,
+            insertions: 42,
+            deletions: 7,
+        }))
+    }
+}
+
+/// Render a template. Missing values render as empty strings.
+pub fn render_template(template: &str, context: &TemplateContext) -> Result<String> {
+    render(environment(UndefinedBehavior::Lenient), template, context)
+}
+
+/// Check a template before it is stored: it must parse, stay within
+/// [`MAX_TEMPLATE_LEN`] and only reference known placeholders.
+pub fn validate_template(template: &str) -> Result<()> {
+    if template.len() > MAX_TEMPLATE_LEN {
+        return Err(anyhow!(
+            "notification template exceeds {MAX_TEMPLATE_LEN} bytes"
+        ));
+    }
+
+    let env = environment(UndefinedBehavior::Strict);
+    let rendered = render(env, template, &TemplateContext::sample())?;
+    if rendered.is_empty() {
+        return Err(anyhow!("notification template renders an empty message"));
+    }
+    Ok(())
+}
+
+fn environment(undefined: UndefinedBehavior) -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_undefined_behavior(undefined);
+    // Unknown values (no exit code, no diff) print nothing rather than `none`
+    env.set_formatter(|out, state, value| {
+        if value.is_none() {
+            Ok(())
+        } else {
+            minijinja::escape_formatter(out, state, value)
+        }
+    });
+    env
+}
+
+fn render(env: Environment<'_>, template: &str, context: &TemplateContext) -> Result<String> {
+    let rendered = env
+        .render_str(template, context)
+        .map_err(|err| anyhow!("invalid notification template: {err}"))?;
+    Ok(rendered.trim().to_string())
+}
+
+fn format_duration(secs: i64) -> String {
+    let secs = secs.max(0);
+    match (secs / 3600, secs % 3600 / 60, secs % 60) {
+        (0, 0, s) => format!("{s}s"),
+        (0, m, s) => format!("{m}m {s:02}s"),
+        (h, m, _) => format!("{h}h {m:02}m"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_placeholders() {
+        let context = TemplateContext {
+            title: "Fix login".into(),
+            status: "failed".into(),
+            branch: "forge/fix-login".into(),
+            exit_code: Some(1),
+            ..Default::default()
+        }
+        .with_duration_secs(Some(65))
+        .with_diff_stats(Some(DiffStats {
+            files_changed: 1,
+            insertions: 3,
+            deletions: 0,
+        }));
+
+        let rendered = render_template(
+            "{% if status == 'failed' %}Falhou{% else %}Concluída{% endif %}: {{ title }}\n\
+             {{ branch }} ({{ duration }}, exit {{ exit_code }})\n{{ diff_stats }}",
+            &context,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rendered,
+            "Falhou: Fix login\nforge/fix-login (1m 05s, exit 1)\n1 file changed, +3 -0"
+        );
+    }
+
+    #[test]
+    fn missing_values_render_empty() {
+        let rendered =
+            render_template("{{ title }} {{ diff_stats }}", &TemplateContext::default()).unwrap();
+        assert_eq!(rendered, "");
+    }
+
+    #[test]
+    fn validation_rejects_bad_templates() {
+        assert!(validate_template("{{ title }} — {{ status }}").is_ok());
+        assert!(validate_template("PR #{{ pr_number }} {{ pr_url }}").is_ok());
+        assert!(validate_template("{{ title").is_err());
+        assert!(validate_template("{{ titel }}").is_err());
+        assert!(validate_template("{% include 'secrets.txt' %}").is_err());
+        assert!(validate_template("   ").is_err());
+        assert!(validate_template(&"x".repeat(MAX_TEMPLATE_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(9), "9s");
+        assert_eq!(format_duration(247), "4m 07s");
+        assert_eq!(format_duration(3 * 3600 + 125), "3h 02m");
+    }
+}
@@ -0,0 +1,254 @@
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::template::DiffStats;
+
+/// Channel-agnostic notification content.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NotificationMessage {
+    /// Queue row id; stable across retries so receivers can deduplicate
+    pub id: String,
+    /// Task title
+    pub title: String,
+    pub summary: String,
+    pub url: Option<String>,
+    /// Raw event metadata (attempt id, status, executor, branch, ...)
+    pub metadata: serde_json::Value,
+    /// Event that queued the notification
+    #[serde(default)]
+    pub notification_type: NotificationType,
+    /// Rendered project template; replaces the default text when set
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Line counts of the attempt branch, when a channel asked for them
+    #[serde(default)]
+    pub diff_stats: Option<DiffStats>,
+    /// Files for channels that can deliver them; not part of the serialized message
+    #[serde(skip)]
+    pub attachments: Vec<NotificationAttachment>,
+}
+
+/// A file sent along with a notification, e.g. the attempt's patch.
+#[derive(Clone, Debug)]
+pub struct NotificationAttachment {
+    pub filename: String,
+    pub mime_type: String,
+    pub content: Vec<u8>,
+}
+
+/// Event that queued a notification, stored as `notification_type` in
+/// `forge_omni_notifications`.
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, TS,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationType {
+    /// An execution process finished
+    #[default]
+    ExecutionCompleted,
+    /// A task moved to `inreview` or `done`
+    TaskStatusChanged,
+    PrCreated,
+    PrMerged,
+    /// A merge or rebase stopped on conflicts
+    MergeConflict,
+}
+
+impl NotificationType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationType::ExecutionCompleted => "execution_completed",
+            NotificationType::TaskStatusChanged => "task_status_changed",
+            NotificationType::PrCreated => "pr_created",
+            NotificationType::PrMerged => "pr_merged",
+            NotificationType::MergeConflict => "merge_conflict",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        serde_json::from_value(serde_json::Value::String(value.to_string())).ok()
+    }
+
+    fn headline(&self) -> &'static str {
+        match self {
+            NotificationType::ExecutionCompleted => "🎯 Task Complete",
+            NotificationType::TaskStatusChanged => "📋 Task Update",
+            NotificationType::PrCreated => "🔀 Pull Request Opened",
+            NotificationType::PrMerged => "🎉 Pull Request Merged",
+            NotificationType::MergeConflict => "⚠️ Merge Conflict",
+        }
+    }
+}
+
+impl NotificationMessage {
+    /// Headline shared by all channels, e.g. as email subject.
+    pub fn headline(&self) -> String {
+        format!("{}: {}", self.notification_type.headline(), self.title)
+    }
+
+    /// Plain-text rendering shared by text-only channels.
+    pub fn to_text(&self) -> String {
+        if let Some(body) = &self.body {
+            return body.clone();
+        }
+        match &self.url {
+            Some(url) => format!("{}\n\n{}\nURL: {}", self.headline(), self.summary, url),
+            None => format!("{}\n\n{}", self.headline(), self.summary),
+        }
+    }
+}
+
+/// A notification channel selected in forge project settings.
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotificationChannelConfig {
+    /// Uses the effective Omni configuration of the project.
+    Omni,
+    Slack(SlackChannelConfig),
+    Email(EmailChannelConfig),
+    Command(CommandChannelConfig),
+    Webhook(WebhookChannelConfig),
+}
+
+impl NotificationChannelConfig {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            NotificationChannelConfig::Omni => "omni",
+            NotificationChannelConfig::Slack(_) => "slack",
+            NotificationChannelConfig::Email(_) => "email",
+            NotificationChannelConfig::Command(_) => "command",
+            NotificationChannelConfig::Webhook(_) => "webhook",
+        }
+    }
+}
+
+/// Stable labels used to record per-channel delivery results: the channel kind,
+/// suffixed with its position when a kind is configured more than once.
+pub fn channel_labels(channels: &[NotificationChannelConfig]) -> Vec<String> {
+    channels
+        .iter()
+        .enumerate()
+        .map(|(index, channel)| {
+            let earlier = channels[..index]
+                .iter()
+                .filter(|other| other.kind() == channel.kind())
+                .count();
+            if earlier == 0 {
+                channel.kind().to_string()
+            } else {
+                format!("{}#{}", channel.kind(), earlier + 1)
+            }
+        })
+        .collect()
+}
+
+/// Slack-compatible incoming webhook (also works with Mattermost and Rocket.Chat).
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct SlackChannelConfig {
+    pub webhook_url: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub channel: Option<String>,
+}
+
+/// SMTP email delivery.
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct EmailChannelConfig {
+    pub smtp_host: String,
+    #[serde(default)]
+    pub smtp_port: Option<u16>,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+    /// Upgrade the connection with STARTTLS instead of connecting over implicit TLS.
+    #[serde(default)]
+    pub starttls: bool,
+}
+
+/// Local command hook, e.g. `notify-send` or a custom script.
+///
+/// The notification is exposed through `FORGE_NOTIFICATION_*` environment variables.
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct CommandChannelConfig {
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+}
+
+/// Generic JSON webhook signed with HMAC-SHA256.
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct WebhookChannelConfig {
+    pub url: String,
+    /// Shared secret used to sign the request body (`X-Forge-Signature` header).
+    pub secret: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_config_uses_type_tag() {
+        let omni: NotificationChannelConfig = serde_json::from_str(r#"{"type":"omni"}"#).unwrap();
+        assert!(matches!(omni, NotificationChannelConfig::Omni));
+
+        let slack: NotificationChannelConfig = serde_json::from_str(
+            r#"{"type":"slack","webhook_url":"https://hooks.slack.test/T000/B000"}"#,
+        )
+        .unwrap();
+        match slack {
+            NotificationChannelConfig::Slack(config) => {
+                assert_eq!(config.webhook_url, "https://hooks.slack.test/T000/B000");
+                assert!(config.channel.is_none());
+            }
+            other => panic!("expected slack channel, got {other:?}"),
+        }
+
+        let json =
+            serde_json::to_string(&NotificationChannelConfig::Command(CommandChannelConfig {
+                command: "notify-send".into(),
+                args: vec![],
+                timeout_secs: None,
+            }))
+            .unwrap();
+        assert!(json.contains(r#""type":"command""#));
+    }
+
+    #[test]
+    fn notification_types_round_trip_as_stored() {
+        assert_eq!(
+            NotificationType::parse("pr_merged"),
+            Some(NotificationType::PrMerged)
+        );
+        assert_eq!(NotificationType::parse("deployed"), None);
+        assert_eq!(
+            serde_json::to_value(NotificationType::MergeConflict).unwrap(),
+            NotificationType::MergeConflict.as_str()
+        );
+    }
+
+    #[test]
+    fn channel_labels_disambiguate_repeated_kinds() {
+        let slack = |url: &str| {
+            NotificationChannelConfig::Slack(SlackChannelConfig {
+                webhook_url: url.into(),
+                username: None,
+                channel: None,
+            })
+        };
+
+        let labels = channel_labels(&[
+            NotificationChannelConfig::Omni,
+            slack("https://a"),
+            slack("https://b"),
+        ]);
+
+        assert_eq!(labels, vec!["omni", "slack", "slack#2"]);
+    }
+}
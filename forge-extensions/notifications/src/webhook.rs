@@ -0,0 +1,175 @@
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use serde_json::json;
+use sha2::Sha256;
+
+use crate::channel::{NotificationChannel, http_client};
+use crate::types::{NotificationMessage, NotificationType, WebhookChannelConfig};
+
+pub const SIGNATURE_HEADER: &str = "X-Forge-Signature";
+pub const DELIVERY_HEADER: &str = "X-Forge-Delivery";
+pub const EVENT_HEADER: &str = "X-Forge-Event";
+
+/// POSTs a signed JSON payload describing the event.
+///
+/// Receivers verify `X-Forge-Signature: sha256=<hex>` by computing
+/// HMAC-SHA256 over the raw request body with the endpoint secret.
+pub struct WebhookChannel {
+    config: WebhookChannelConfig,
+    client: reqwest::Client,
+}
+
+impl WebhookChannel {
+    pub fn new(config: WebhookChannelConfig) -> Result<Self> {
+        if config.url.trim().is_empty() {
+            return Err(anyhow!("Webhook URL not configured"));
+        }
+        if config.secret.is_empty() {
+            return Err(anyhow!("Webhook secret not configured"));
+        }
+
+        Ok(Self {
+            config,
+            client: http_client()?,
+        })
+    }
+
+    fn event_name(message: &NotificationMessage) -> String {
+        let status = message
+            .metadata
+            .get("status")
+            .and_then(|status| status.as_str())
+            .unwrap_or("unknown");
+        match message.notification_type {
+            NotificationType::ExecutionCompleted => format!("execution.{status}"),
+            NotificationType::TaskStatusChanged => format!("task.{status}"),
+            NotificationType::PrCreated => "pull_request.opened".into(),
+            NotificationType::PrMerged => "pull_request.merged".into(),
+            NotificationType::MergeConflict => "merge.conflict".into(),
+        }
+    }
+}
+
+/// Hex-encoded HMAC-SHA256 of `body` keyed with `secret`.
+pub fn sign_payload(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[async_trait]
+impl NotificationChannel for WebhookChannel {
+    fn kind(&self) -> &'static str {
+        "webhook"
+    }
+
+    async fn send(&self, message: &NotificationMessage) -> Result<()> {
+        let event = Self::event_name(message);
+        let body = serde_json::to_vec(&json!({
+            "delivery_id": message.id,
+            "event": event,
+            "sent_at": chrono::Utc::now().to_rfc3339(),
+            "task": {
+                "title": message.title,
+                "url": message.url,
+            },
+            "text": message.to_text(),
+            "execution": message.metadata,
+        }))?;
+        let signature = sign_payload(&self.config.secret, &body);
+
+        let response = self
+            .client
+            .post(&self.config.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, format!("sha256={signature}"))
+            .header(DELIVERY_HEADER, &message.id)
+            .header(EVENT_HEADER, &event)
+            .body(body)
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(anyhow!("Webhook returned {}: {}", status, text));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::prelude::*;
+
+    #[test]
+    fn signs_with_hmac_sha256() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign_payload("Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[tokio::test]
+    async fn posts_signed_payload_with_delivery_id() {
+        let server = MockServer::start_async().await;
+        let mock = server.mock(|when, then| {
+            when.method(POST)
+                .path("/hooks/forge")
+                .header(DELIVERY_HEADER, "notif-42")
+                .header(EVENT_HEADER, "execution.failed")
+                .header_exists(SIGNATURE_HEADER)
+                .json_body_partial(
+                    r#"{"delivery_id": "notif-42", "execution": {"exit_code": 1, "branch": "forge/abc"}}"#,
+                );
+            then.status(204);
+        });
+
+        let channel = WebhookChannel::new(WebhookChannelConfig {
+            url: server.url("/hooks/forge"),
+            secret: "s3cret".into(),
+        })
+        .unwrap();
+
+        channel
+            .send(&NotificationMessage {
+                id: "notif-42".into(),
+                title: "Fix login".into(),
+                summary: "❌ Execution failed".into(),
+                url: None,
+                metadata: json!({
+                    "task_attempt_id": "0f3c",
+                    "status": "failed",
+                    "executor": "CLAUDE_CODE",
+                    "branch": "forge/abc",
+                    "project_id": "9ac5",
+                    "exit_code": 1,
+                }),
+                notification_type: Default::default(),
+                body: None,
+                diff_stats: None,
+                attachments: vec![],
+            })
+            .await
+            .expect("webhook should accept the payload");
+
+        mock.assert_async().await;
+    }
+
+    #[test]
+    fn requires_secret() {
+        let result = WebhookChannel::new(WebhookChannelConfig {
+            url: "https://ci.example/hooks".into(),
+            secret: String::new(),
+        });
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,73 @@
+//! Run the Omni stand-in server, so notifications can be followed locally without a
+//! WhatsApp instance:
+//!
+//! ```text
+//! cargo run -p forge-omni --features fake-server --bin fake-omni -- --port 8882
+//! ```
+//!
+//! Then set the Omni host to `http://127.0.0.1:8882` and the instance to `forge`.
+//! Received messages are logged.
+
+use std::{net::SocketAddr, time::Duration};
+
+use anyhow::{Context, Result, bail};
+use forge_omni::fake::{DEFAULT_INSTANCE, FakeOmni};
+use tracing_subscriber::EnvFilter;
+
+const DEFAULT_PORT: u16 = 8882;
+
+const USAGE: &str = "Usage: fake-omni [--port <port>] [--instance <name>]... \
+                     [--api-key <key>] [--latency-ms <ms>]";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+        )
+        .init();
+
+    let mut port = DEFAULT_PORT;
+    let mut instances = Vec::new();
+    let mut api_key = None;
+    let mut latency = Duration::ZERO;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().with_context(|| format!("{arg} needs a value"));
+        match arg.as_str() {
+            "--port" => port = value()?.parse().context("invalid --port")?,
+            "--instance" => instances.push(value()?),
+            "--api-key" => api_key = Some(value()?),
+            "--latency-ms" => {
+                latency = Duration::from_millis(value()?.parse().context("invalid --latency-ms")?)
+            }
+            "--help" | "-h" => {
+                println!("{USAGE}");
+                return Ok(());
+            }
+            other => bail!("Unknown argument '{other}'\n{USAGE}"),
+        }
+    }
+    if instances.is_empty() {
+        instances.push(DEFAULT_INSTANCE.to_string());
+    }
+
+    let server = FakeOmni::bind(SocketAddr::from(([127, 0, 0, 1], port))).await?;
+    for instance in &instances {
+        server.add_instance(instance);
+    }
+    if let Some(key) = &api_key {
+        server.require_api_key(key);
+    }
+    server.set_latency(latency);
+
+    tracing::info!(
+        "Fake Omni listening on {} with instances: {}",
+        server.base_url(),
+        instances.join(", ")
+    );
+
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
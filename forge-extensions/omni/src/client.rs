@@ -1,5 +1,11 @@
-use super::types::{InstancesResponse, OmniInstance, SendTextRequest, SendTextResponse};
+use super::http::OmniHttpConfig;
+use super::types::{
+    InstancesResponse, OmniInstance, SendDocumentRequest, SendMediaRequest, SendTextRequest,
+    SendTextResponse,
+};
 use anyhow::Result;
+use serde::Serialize;
+use std::fmt::Debug;
 
 pub struct OmniClient {
     base_url: String,
@@ -8,14 +14,31 @@ pub struct OmniClient {
 }
 
 impl OmniClient {
+    /// Client with the default timeouts and user agent.
     pub fn new(base_url: String, api_key: Option<String>) -> Self {
+        let client = OmniHttpConfig::default()
+            .build_client()
+            .unwrap_or_else(|_| reqwest::Client::new());
         Self {
             base_url,
             api_key,
-            client: reqwest::Client::new(),
+            client,
         }
     }
 
+    /// Client for `options`, which apply to every request it makes.
+    pub fn with_options(
+        base_url: String,
+        api_key: Option<String>,
+        options: &OmniHttpConfig,
+    ) -> Result<Self> {
+        Ok(Self {
+            base_url,
+            api_key,
+            client: options.build_client()?,
+        })
+    }
+
     pub async fn list_instances(&self) -> Result<Vec<OmniInstance>> {
         let mut request = self
             .client
@@ -41,11 +64,41 @@ impl OmniClient {
         instance: &str,
         req: SendTextRequest,
     ) -> Result<SendTextResponse> {
-        let url = format!("{}/api/v1/instance/{}/send-text", self.base_url, instance);
+        self.post_message(instance, "send-text", &req).await
+    }
+
+    /// Send an image through Omni's `send-media` endpoint.
+    pub async fn send_media(
+        &self,
+        instance: &str,
+        req: SendMediaRequest,
+    ) -> Result<SendTextResponse> {
+        self.post_message(instance, "send-media", &req).await
+    }
+
+    /// Send a file through Omni's `send-document` endpoint.
+    pub async fn send_document(
+        &self,
+        instance: &str,
+        req: SendDocumentRequest,
+    ) -> Result<SendTextResponse> {
+        self.post_message(instance, "send-document", &req).await
+    }
+
+    async fn post_message<T: Serialize + Debug>(
+        &self,
+        instance: &str,
+        endpoint: &str,
+        req: &T,
+    ) -> Result<SendTextResponse> {
+        let url = format!(
+            "{}/api/v1/instance/{}/{}",
+            self.base_url, instance, endpoint
+        );
 
         tracing::info!("Sending Omni request to: {} with payload: {:?}", url, req);
 
-        let mut request = self.client.post(&url).json(&req);
+        let mut request = self.client.post(&url).json(req);
 
         if let Some(key) = &self.api_key {
             request = request.header("X-API-Key", key);
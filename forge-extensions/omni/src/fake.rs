@@ -0,0 +1,401 @@
+//! Omni stand-in server.
+//!
+//! Serves the endpoints forge calls — the instance list and the `send-text`,
+//! `send-media` and `send-document` routes — and records every message it
+//! receives. Tests start it on a free port and inject failures or latency; the
+//! `fake-omni` binary runs it for local development. Built with the `fake-server`
+//! feature.
+
+use std::{
+    net::SocketAddr,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use anyhow::Result;
+use axum::{
+    Json, Router,
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::{get, post},
+};
+use chrono::{DateTime, Utc};
+use serde_json::{Value, json};
+use tokio::{net::TcpListener, sync::oneshot, time::sleep};
+
+/// Instance created by [`FakeOmni::start`].
+pub const DEFAULT_INSTANCE: &str = "forge";
+
+const SEND_ENDPOINTS: &[&str] = &["send-text", "send-media", "send-document"];
+
+#[derive(Clone, Debug)]
+pub struct FakeInstance {
+    pub name: String,
+    pub channel_type: String,
+    pub status: String,
+    pub is_healthy: bool,
+}
+
+impl FakeInstance {
+    fn healthy(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            channel_type: "whatsapp".to_string(),
+            status: "connected".to_string(),
+            is_healthy: true,
+        }
+    }
+}
+
+/// A message accepted by one of the send endpoints.
+#[derive(Clone, Debug)]
+pub struct ReceivedMessage {
+    pub instance: String,
+    /// `send-text`, `send-media` or `send-document`
+    pub endpoint: String,
+    /// `phone_number` or `user_id` of the request
+    pub recipient: Option<String>,
+    /// Message text, or the caption of media and documents
+    pub text: Option<String>,
+    pub body: Value,
+    pub message_id: String,
+    pub received_at: DateTime<Utc>,
+}
+
+#[derive(Default)]
+struct FakeState {
+    instances: Vec<FakeInstance>,
+    messages: Vec<ReceivedMessage>,
+    /// Remaining sends to reject and the status to reject them with
+    failures: Option<(usize, StatusCode)>,
+    latency: Duration,
+    api_key: Option<String>,
+}
+
+type SharedState = Arc<Mutex<FakeState>>;
+type ApiError = (StatusCode, Json<Value>);
+
+/// A running stand-in server; it stops when dropped.
+pub struct FakeOmni {
+    addr: SocketAddr,
+    state: SharedState,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl FakeOmni {
+    /// Start on a free local port with one healthy instance, [`DEFAULT_INSTANCE`].
+    pub async fn start() -> Result<Self> {
+        let server = Self::bind(SocketAddr::from(([127, 0, 0, 1], 0))).await?;
+        server.add_instance(DEFAULT_INSTANCE);
+        Ok(server)
+    }
+
+    /// Start on `addr` without any instance.
+    pub async fn bind(addr: SocketAddr) -> Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let state = SharedState::default();
+        let app = router(state.clone());
+
+        let (shutdown, stopped) = oneshot::channel::<()>();
+        tokio::spawn(async move {
+            let server = axum::serve(listener, app).with_graceful_shutdown(async {
+                stopped.await.ok();
+            });
+            if let Err(err) = server.await {
+                tracing::error!("Fake Omni server stopped: {}", err);
+            }
+        });
+
+        Ok(Self {
+            addr,
+            state,
+            shutdown: Some(shutdown),
+        })
+    }
+
+    /// Value for `omni_config.host`.
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Add a healthy, connected instance.
+    pub fn add_instance(&self, name: &str) {
+        let mut state = self.state();
+        state.instances.retain(|instance| instance.name != name);
+        state.instances.push(FakeInstance::healthy(name));
+    }
+
+    /// Change how an instance is listed. Sends to an unhealthy instance are
+    /// rejected with `503`.
+    pub fn set_instance_health(&self, name: &str, is_healthy: bool, status: &str) {
+        for instance in self
+            .state()
+            .instances
+            .iter_mut()
+            .filter(|instance| instance.name == name)
+        {
+            instance.is_healthy = is_healthy;
+            instance.status = status.to_string();
+        }
+    }
+
+    /// Reject requests without this `X-API-Key` with `401`.
+    pub fn require_api_key(&self, key: &str) {
+        self.state().api_key = Some(key.to_string());
+    }
+
+    /// Delay every response by `latency`.
+    pub fn set_latency(&self, latency: Duration) {
+        self.state().latency = latency;
+    }
+
+    /// Reject the next `count` sends with `status`.
+    pub fn fail_next(&self, count: usize, status: u16) {
+        let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        self.state().failures = (count > 0).then_some((count, status));
+    }
+
+    /// Messages accepted so far, oldest first.
+    pub fn messages(&self) -> Vec<ReceivedMessage> {
+        self.state().messages.clone()
+    }
+
+    pub fn clear_messages(&self) {
+        self.state().messages.clear();
+    }
+
+    fn state(&self) -> MutexGuard<'_, FakeState> {
+        lock(&self.state)
+    }
+}
+
+impl Drop for FakeOmni {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+// A test that panicked while holding the lock must not hide the recorded messages
+fn lock(state: &SharedState) -> MutexGuard<'_, FakeState> {
+    state
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn router(state: SharedState) -> Router {
+    Router::new()
+        .route("/api/v1/instances/", get(list_instances))
+        .route("/api/v1/instance/{name}/{endpoint}", post(send_message))
+        .with_state(state)
+}
+
+fn error(status: StatusCode, detail: impl Into<String>) -> ApiError {
+    (status, Json(json!({ "detail": detail.into() })))
+}
+
+/// Check the API key and wait out the configured latency.
+async fn accept(state: &SharedState, headers: &HeaderMap) -> Result<(), ApiError> {
+    let latency = {
+        let state = lock(state);
+        if let Some(expected) = &state.api_key {
+            let provided = headers
+                .get("X-API-Key")
+                .and_then(|value| value.to_str().ok());
+            if provided != Some(expected.as_str()) {
+                return Err(error(StatusCode::UNAUTHORIZED, "Invalid API key"));
+            }
+        }
+        state.latency
+    };
+    sleep(latency).await;
+    Ok(())
+}
+
+async fn list_instances(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, ApiError> {
+    accept(&state, &headers).await?;
+
+    let state = lock(&state);
+    let channels: Vec<Value> = state
+        .instances
+        .iter()
+        .map(|instance| {
+            json!({
+                "instance_name": instance.name,
+                "channel_type": instance.channel_type,
+                "display_name": instance.name,
+                "status": instance.status,
+                "is_healthy": instance.is_healthy,
+            })
+        })
+        .collect();
+    Ok(Json(json!({ "channels": channels })))
+}
+
+async fn send_message(
+    State(state): State<SharedState>,
+    Path((instance, endpoint)): Path<(String, String)>,
+    headers: HeaderMap,
+    Json(body): Json<Value>,
+) -> Result<Json<Value>, ApiError> {
+    if !SEND_ENDPOINTS.contains(&endpoint.as_str()) {
+        return Err(error(StatusCode::NOT_FOUND, "Not Found"));
+    }
+    // Latency comes first, so a client that times out leaves no message behind
+    accept(&state, &headers).await?;
+
+    let mut state = lock(&state);
+    let Some(target) = state.instances.iter().find(|i| i.name == instance) else {
+        return Err(error(
+            StatusCode::NOT_FOUND,
+            format!("Instance '{instance}' not found"),
+        ));
+    };
+    if !target.is_healthy {
+        return Err(error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!("Instance '{instance}' is {}", target.status),
+        ));
+    }
+    if let Some((remaining, status)) = state.failures.take() {
+        if remaining > 1 {
+            state.failures = Some((remaining - 1, status));
+        }
+        return Err(error(status, "Injected failure"));
+    }
+
+    let recipient = ["phone_number", "user_id"]
+        .iter()
+        .find_map(|key| body.get(*key).and_then(Value::as_str))
+        .map(str::to_string);
+    let text = ["text", "caption"]
+        .iter()
+        .find_map(|key| body.get(*key).and_then(Value::as_str))
+        .map(str::to_string);
+    let message_id = format!("fake-{}", state.messages.len() + 1);
+
+    tracing::info!(
+        instance = %instance,
+        endpoint = %endpoint,
+        recipient = recipient.as_deref().unwrap_or("-"),
+        "Received message: {}",
+        text.as_deref().unwrap_or("")
+    );
+
+    state.messages.push(ReceivedMessage {
+        instance,
+        endpoint,
+        recipient,
+        text,
+        body,
+        message_id: message_id.clone(),
+        received_at: Utc::now(),
+    });
+
+    Ok(Json(json!({
+        "success": true,
+        "message_id": message_id,
+        "status": "sent",
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OmniClient, OmniConfig, OmniService, RecipientType, SendTextRequest};
+
+    fn service(server: &FakeOmni) -> OmniService {
+        OmniService::new(OmniConfig {
+            enabled: true,
+            host: Some(server.base_url()),
+            api_key: Some("key".into()),
+            instance: Some(DEFAULT_INSTANCE.into()),
+            recipient: Some("+15550001111".into()),
+            recipient_type: Some(RecipientType::PhoneNumber),
+            ..Default::default()
+        })
+    }
+
+    fn text(message: &str) -> SendTextRequest {
+        SendTextRequest {
+            phone_number: Some("+15550001111".into()),
+            user_id: None,
+            text: message.into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn records_messages_and_injected_failures() {
+        let server = FakeOmni::start().await.unwrap();
+        server.require_api_key("key");
+        let omni = service(&server);
+
+        let instances = omni.list_instances().await.unwrap();
+        assert_eq!(instances.len(), 1);
+        assert!(instances[0].is_healthy);
+
+        omni.send_text_notification("first").await.unwrap();
+        let messages = server.messages();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].endpoint, "send-text");
+        assert_eq!(messages[0].recipient.as_deref(), Some("+15550001111"));
+        assert_eq!(messages[0].text.as_deref(), Some("first"));
+
+        server.fail_next(1, 502);
+        let error = omni.send_text_notification("second").await.unwrap_err();
+        assert!(error.to_string().contains("502"));
+        omni.send_text_notification("third").await.unwrap();
+
+        server.set_instance_health(DEFAULT_INSTANCE, false, "disconnected");
+        let error = omni.send_text_notification("fourth").await.unwrap_err();
+        assert!(error.to_string().contains("disconnected"));
+        assert!(!omni.list_instances().await.unwrap()[0].is_healthy);
+
+        let unauthenticated = OmniClient::new(server.base_url(), None);
+        assert!(
+            unauthenticated
+                .send_text(DEFAULT_INSTANCE, text("fifth"))
+                .await
+                .is_err()
+        );
+
+        let texts: Vec<_> = server
+            .messages()
+            .into_iter()
+            .filter_map(|message| message.text)
+            .collect();
+        assert_eq!(texts, vec!["first", "third"]);
+    }
+
+    #[tokio::test]
+    async fn latency_can_exceed_the_client_timeout() {
+        let server = FakeOmni::start().await.unwrap();
+        server.set_latency(Duration::from_secs(3));
+
+        let client = OmniClient::with_options(
+            server.base_url(),
+            None,
+            &crate::OmniHttpConfig {
+                request_timeout_secs: Some(1),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(
+            client
+                .send_text(DEFAULT_INSTANCE, text("slow"))
+                .await
+                .is_err()
+        );
+        assert!(server.messages().is_empty());
+    }
+}
@@ -0,0 +1,145 @@
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+pub const DEFAULT_CONNECT_TIMEOUT_SECS: u64 = 10;
+pub const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 30;
+pub const DEFAULT_USER_AGENT: &str = concat!("automagik-forge/", env!("CARGO_PKG_VERSION"));
+
+/// HTTP client settings for requests to Omni.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS)]
+pub struct OmniHttpConfig {
+    /// Seconds to wait for a connection; defaults to 10
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub connect_timeout_secs: Option<u64>,
+    /// Seconds a whole request may take, including the response; defaults to 30
+    #[serde(default)]
+    #[ts(type = "number | null")]
+    pub request_timeout_secs: Option<u64>,
+    /// HTTP(S) proxy for all Omni requests, e.g. `http://proxy.local:3128`.
+    /// Unset uses the `HTTPS_PROXY`/`HTTP_PROXY` environment variables.
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// PEM files with additional root certificates, e.g. a private CA
+    #[serde(default)]
+    pub root_certificates: Vec<String>,
+    /// Skip TLS certificate verification, for self-hosted instances with
+    /// self-signed certificates
+    #[serde(default)]
+    pub accept_invalid_certs: bool,
+    #[serde(default)]
+    pub user_agent: Option<String>,
+}
+
+impl OmniHttpConfig {
+    /// Reject a proxy URL or certificate file the client could not be built with.
+    pub fn validate(&self) -> Result<()> {
+        self.build_client().map(|_| ())
+    }
+
+    pub fn build_client(&self) -> Result<reqwest::Client> {
+        let connect_timeout = self
+            .connect_timeout_secs
+            .unwrap_or(DEFAULT_CONNECT_TIMEOUT_SECS);
+        let request_timeout = self
+            .request_timeout_secs
+            .unwrap_or(DEFAULT_REQUEST_TIMEOUT_SECS);
+        if connect_timeout == 0 || request_timeout == 0 {
+            anyhow::bail!("Omni HTTP timeouts must be at least one second");
+        }
+
+        let user_agent = self
+            .user_agent
+            .as_deref()
+            .filter(|agent| !agent.trim().is_empty())
+            .unwrap_or(DEFAULT_USER_AGENT);
+
+        let mut builder = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(connect_timeout))
+            .timeout(Duration::from_secs(request_timeout))
+            .user_agent(user_agent)
+            .danger_accept_invalid_certs(self.accept_invalid_certs);
+
+        if let Some(proxy) = self
+            .proxy
+            .as_deref()
+            .filter(|proxy| !proxy.trim().is_empty())
+        {
+            let proxy = reqwest::Proxy::all(proxy.trim())
+                .with_context(|| format!("Invalid Omni proxy URL '{proxy}'"))?;
+            builder = builder.proxy(proxy);
+        }
+
+        for path in &self.root_certificates {
+            let pem = std::fs::read(path)
+                .with_context(|| format!("Failed to read Omni root certificate {path}"))?;
+            let certificate = reqwest::Certificate::from_pem(&pem)
+                .with_context(|| format!("Invalid PEM certificate in {path}"))?;
+            builder = builder.add_root_certificate(certificate);
+        }
+
+        Ok(builder.build()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_settings_the_client_cannot_use() {
+        assert!(OmniHttpConfig::default().validate().is_ok());
+
+        let proxied = OmniHttpConfig {
+            proxy: Some("http://proxy.local:3128".into()),
+            user_agent: Some("forge-test".into()),
+            accept_invalid_certs: true,
+            ..Default::default()
+        };
+        assert!(proxied.validate().is_ok());
+
+        let bad_proxy = OmniHttpConfig {
+            proxy: Some("not a url".into()),
+            ..Default::default()
+        };
+        assert!(bad_proxy.validate().is_err());
+
+        let missing_certificate = OmniHttpConfig {
+            root_certificates: vec!["/nonexistent/forge-ca.pem".into()],
+            ..Default::default()
+        };
+        assert!(missing_certificate.validate().is_err());
+
+        let zero_timeout = OmniHttpConfig {
+            request_timeout_secs: Some(0),
+            ..Default::default()
+        };
+        assert!(zero_timeout.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn request_timeout_stops_a_hung_host() {
+        // Accepts connections but never answers
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!(
+            "http://{}/api/v1/instances/",
+            listener.local_addr().unwrap()
+        );
+
+        let client = OmniHttpConfig {
+            request_timeout_secs: Some(1),
+            ..Default::default()
+        }
+        .build_client()
+        .unwrap();
+
+        let started = std::time::Instant::now();
+        let error = client.get(&url).send().await.unwrap_err();
+        assert!(error.is_timeout());
+        assert!(started.elapsed() < Duration::from_secs(5));
+        drop(listener);
+    }
+}
@@ -0,0 +1,269 @@
+//! Inbound Omni messages.
+//!
+//! Omni forwards chat messages to `POST /api/forge/omni/inbound`. Forge accepts them
+//! only with the shared secret in [`SECRET_HEADER`] and from an allowed sender, then
+//! runs the command they contain and replies to the sender.
+
+use std::fmt;
+
+use anyhow::{Result, anyhow};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+use crate::types::{OmniConfig, OmniRecipient, REDACTED, RecipientType};
+
+pub const SECRET_HEADER: &str = "X-Omni-Secret";
+
+const WHATSAPP_SUFFIXES: &[&str] = &["@s.whatsapp.net", "@c.us"];
+
+/// Shortest id prefix accepted in commands; shorter ones match too many rows.
+pub const MIN_ID_PREFIX_LEN: usize = 4;
+
+pub const HELP_TEXT: &str = "Forge commands:\n\
+    • status — running agents\n\
+    • retry <task> — start a new attempt\n\
+    • stop <attempt> — stop a running attempt\n\
+    • follow-up <attempt> <text> — send a follow-up prompt";
+
+/// Settings for chat commands received from Omni.
+#[derive(Clone, Default, Serialize, Deserialize, TS)]
+pub struct OmniInboundConfig {
+    pub enabled: bool,
+    /// Shared secret Omni sends in the `X-Omni-Secret` header
+    pub secret: String,
+    /// Phone numbers or user ids allowed to send commands; empty means the
+    /// configured notification recipients
+    #[serde(default)]
+    pub allowed_senders: Vec<String>,
+}
+
+impl fmt::Debug for OmniInboundConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OmniInboundConfig")
+            .field("enabled", &self.enabled)
+            .field("secret", &REDACTED)
+            .field("allowed_senders", &self.allowed_senders)
+            .finish()
+    }
+}
+
+/// Message event posted by Omni.
+#[derive(Clone, Debug, Deserialize)]
+pub struct OmniInboundEvent {
+    #[serde(default, alias = "instance_name")]
+    pub instance: Option<String>,
+    #[serde(alias = "from", alias = "phone_number", alias = "user_id")]
+    pub sender: String,
+    #[serde(alias = "message", alias = "body")]
+    pub text: String,
+}
+
+/// A chat command, with task and attempt ids given as (prefixes of) UUIDs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InboundCommand {
+    Help,
+    Status,
+    Retry { task: String },
+    Stop { attempt: String },
+    FollowUp { attempt: String, prompt: String },
+}
+
+impl InboundCommand {
+    /// Parse a message such as `retry 1a2b3c4d`. The error message is meant to be
+    /// sent back to the sender.
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim().trim_start_matches('/');
+        let (command, rest) = text
+            .split_once(char::is_whitespace)
+            .map(|(command, rest)| (command, rest.trim()))
+            .unwrap_or((text, ""));
+
+        match command.to_ascii_lowercase().as_str() {
+            "help" | "" => Ok(InboundCommand::Help),
+            "status" => Ok(InboundCommand::Status),
+            "retry" => Ok(InboundCommand::Retry {
+                task: parse_id(rest, "retry <task>")?,
+            }),
+            "stop" => Ok(InboundCommand::Stop {
+                attempt: parse_id(rest, "stop <attempt>")?,
+            }),
+            "follow-up" | "followup" => {
+                let (attempt, prompt) = rest
+                    .split_once(char::is_whitespace)
+                    .map(|(attempt, prompt)| (attempt, prompt.trim()))
+                    .unwrap_or((rest, ""));
+                if prompt.is_empty() {
+                    return Err(anyhow!("Usage: follow-up <attempt> <text>"));
+                }
+                Ok(InboundCommand::FollowUp {
+                    attempt: parse_id(attempt, "follow-up <attempt> <text>")?,
+                    prompt: prompt.to_string(),
+                })
+            }
+            other => Err(anyhow!("Unknown command '{other}'.\n\n{HELP_TEXT}")),
+        }
+    }
+}
+
+/// Normalise an id argument to lowercase hex without dashes.
+fn parse_id(value: &str, usage: &str) -> Result<String> {
+    let id: String = value
+        .trim()
+        .chars()
+        .filter(|c| *c != '-')
+        .collect::<String>()
+        .to_ascii_lowercase();
+
+    if id.len() < MIN_ID_PREFIX_LEN || id.len() > 32 || !id.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(anyhow!(
+            "Usage: {usage} (id or its first {MIN_ID_PREFIX_LEN}+ characters)"
+        ));
+    }
+    Ok(id)
+}
+
+impl OmniInboundConfig {
+    /// Compare the provided secret without leaking where it differs.
+    pub fn verify_secret(&self, provided: &str) -> bool {
+        if self.secret.is_empty() || provided.len() != self.secret.len() {
+            return false;
+        }
+        provided
+            .bytes()
+            .zip(self.secret.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+    }
+
+    /// The recipient to reply to if `sender` may send commands.
+    pub fn allowed_sender(&self, config: &OmniConfig, sender: &str) -> Option<OmniRecipient> {
+        let sender_key = sender_key(sender);
+        if sender_key.is_empty() {
+            return None;
+        }
+
+        if self.allowed_senders.is_empty() {
+            return config
+                .targets()
+                .into_iter()
+                .find(|target| sender_key_matches(&target.recipient, &sender_key));
+        }
+
+        self.allowed_senders
+            .iter()
+            .any(|allowed| sender_key_matches(allowed, &sender_key))
+            .then(|| {
+                if sender_key.chars().all(|c| c.is_ascii_digit()) {
+                    OmniRecipient {
+                        recipient: format!("+{sender_key}"),
+                        recipient_type: Some(RecipientType::PhoneNumber),
+                        ..Default::default()
+                    }
+                } else {
+                    OmniRecipient {
+                        recipient: sender.to_string(),
+                        recipient_type: Some(RecipientType::UserId),
+                        ..Default::default()
+                    }
+                }
+            })
+    }
+}
+
+/// Omni reports WhatsApp senders as `5511999999999@s.whatsapp.net` while recipients
+/// are configured as `+5511999999999`, so phone numbers are compared by digits.
+fn sender_key(value: &str) -> String {
+    let value = value.trim();
+    let local = WHATSAPP_SUFFIXES
+        .iter()
+        .find_map(|suffix| value.strip_suffix(suffix))
+        .unwrap_or(value);
+    local
+        .chars()
+        .filter(|c| !matches!(c, '+' | ' ' | '-'))
+        .collect()
+}
+
+fn sender_key_matches(configured: &str, key: &str) -> bool {
+    !configured.trim().is_empty() && sender_key(configured) == key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_commands() {
+        assert_eq!(
+            InboundCommand::parse(" Status ").unwrap(),
+            InboundCommand::Status
+        );
+        assert_eq!(
+            InboundCommand::parse("/retry 1A2B-3C4D").unwrap(),
+            InboundCommand::Retry {
+                task: "1a2b3c4d".into()
+            }
+        );
+        assert_eq!(
+            InboundCommand::parse("follow-up 1a2b3c4d please add tests\nand docs").unwrap(),
+            InboundCommand::FollowUp {
+                attempt: "1a2b3c4d".into(),
+                prompt: "please add tests\nand docs".into()
+            }
+        );
+        assert_eq!(InboundCommand::parse("").unwrap(), InboundCommand::Help);
+
+        assert!(InboundCommand::parse("stop").is_err());
+        assert!(InboundCommand::parse("stop 1a2").is_err());
+        assert!(InboundCommand::parse("stop xyz12345").is_err());
+        assert!(InboundCommand::parse("follow-up 1a2b3c4d").is_err());
+        assert!(InboundCommand::parse("deploy prod").is_err());
+    }
+
+    #[test]
+    fn verifies_secret() {
+        let inbound = OmniInboundConfig {
+            enabled: true,
+            secret: "s3cret".into(),
+            allowed_senders: vec![],
+        };
+        assert!(inbound.verify_secret("s3cret"));
+        assert!(!inbound.verify_secret("s3creT"));
+        assert!(!inbound.verify_secret(""));
+
+        let unset = OmniInboundConfig::default();
+        assert!(!unset.verify_secret(""));
+    }
+
+    #[test]
+    fn senders_default_to_notification_recipients() {
+        let config = OmniConfig {
+            recipient: Some("+5511999999999".into()),
+            recipient_type: Some(RecipientType::PhoneNumber),
+            ..Default::default()
+        };
+        let inbound = OmniInboundConfig {
+            enabled: true,
+            secret: "s3cret".into(),
+            allowed_senders: vec![],
+        };
+
+        let reply_to = inbound
+            .allowed_sender(&config, "5511999999999@s.whatsapp.net")
+            .expect("recipient may send commands");
+        assert_eq!(reply_to.recipient, "+5511999999999");
+        assert!(inbound.allowed_sender(&config, "5511000000000").is_none());
+
+        let explicit = OmniInboundConfig {
+            allowed_senders: vec!["oncall@discord".into()],
+            ..inbound
+        };
+        assert!(explicit.allowed_sender(&config, "+5511999999999").is_none());
+        let reply_to = explicit.allowed_sender(&config, "oncall@discord").unwrap();
+        assert!(matches!(
+            reply_to.recipient_type,
+            Some(RecipientType::UserId)
+        ));
+        assert!(explicit.allowed_sender(&config, "oncall@slack").is_none());
+    }
+}
@@ -4,9 +4,17 @@
 //! Provides notification services for task completion and status updates.
 
 pub mod client;
+#[cfg(feature = "fake-server")]
+pub mod fake;
+pub mod http;
+pub mod inbound;
+pub mod quiet_hours;
 pub mod service;
 pub mod types;
 
 pub use client::OmniClient;
+pub use http::OmniHttpConfig;
+pub use inbound::{InboundCommand, OmniInboundConfig, OmniInboundEvent};
+pub use quiet_hours::{QuietHoursAction, QuietHoursConfig};
 pub use service::OmniService;
 pub use types::*;
@@ -0,0 +1,163 @@
+use anyhow::{Result, anyhow};
+use chrono::{DateTime, Duration, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// What happens to a notification that arrives during quiet hours.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum QuietHoursAction {
+    /// Keep it queued and deliver when the window ends
+    #[default]
+    Hold,
+    /// Drop it with status `skipped`
+    Skip,
+}
+
+/// Daily window without notifications, e.g. 22:00–07:00 in the recipient's time zone.
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct QuietHoursConfig {
+    pub enabled: bool,
+    /// Local start time, `HH:MM`
+    pub start: String,
+    /// Local end time, `HH:MM`; earlier than `start` for windows spanning midnight
+    pub end: String,
+    /// IANA time zone, e.g. `America/Sao_Paulo`
+    pub timezone: String,
+    #[serde(default)]
+    pub action: QuietHoursAction,
+}
+
+impl QuietHoursConfig {
+    pub fn validate(&self) -> Result<()> {
+        self.parse().map(|_| ())
+    }
+
+    /// End of the quiet window containing `now`, or `None` outside quiet hours.
+    pub fn window_end(&self, now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>> {
+        if !self.enabled {
+            return Ok(None);
+        }
+
+        let (start, end, tz) = self.parse()?;
+        if start == end {
+            return Ok(None);
+        }
+
+        let local = now.with_timezone(&tz);
+        let time = local.time();
+        let today = local.date_naive();
+        let end_date = if start < end {
+            if time < start || time >= end {
+                return Ok(None);
+            }
+            today
+        } else if time >= start {
+            today + Duration::days(1)
+        } else if time < end {
+            today
+        } else {
+            return Ok(None);
+        };
+
+        let naive_end = end_date.and_time(end);
+        // An end time skipped by a DST change resolves to the hour after the gap
+        let window_end = tz
+            .from_local_datetime(&naive_end)
+            .earliest()
+            .or_else(|| {
+                tz.from_local_datetime(&(naive_end + Duration::hours(1)))
+                    .earliest()
+            })
+            .ok_or_else(|| anyhow!("quiet hours end {} does not exist in {}", end, tz))?;
+
+        Ok(Some(window_end.with_timezone(&Utc)))
+    }
+
+    fn parse(&self) -> Result<(NaiveTime, NaiveTime, Tz)> {
+        let parse_time = |value: &str| {
+            NaiveTime::parse_from_str(value, "%H:%M")
+                .map_err(|_| anyhow!("invalid quiet hours time '{value}', expected HH:MM"))
+        };
+        let tz = self
+            .timezone
+            .parse::<Tz>()
+            .map_err(|_| anyhow!("unknown time zone '{}'", self.timezone))?;
+
+        Ok((parse_time(&self.start)?, parse_time(&self.end)?, tz))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quiet_hours(start: &str, end: &str) -> QuietHoursConfig {
+        QuietHoursConfig {
+            enabled: true,
+            start: start.into(),
+            end: end.into(),
+            timezone: "America/Sao_Paulo".into(),
+            action: QuietHoursAction::Hold,
+        }
+    }
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn overnight_window_ends_next_morning() {
+        let config = quiet_hours("22:00", "07:00");
+
+        // 23:30 in São Paulo (UTC-3)
+        let end = config.window_end(utc("2026-03-10T02:30:00Z")).unwrap();
+        assert_eq!(end, Some(utc("2026-03-10T10:00:00Z")));
+
+        // 06:00 local, same window
+        let end = config.window_end(utc("2026-03-10T09:00:00Z")).unwrap();
+        assert_eq!(end, Some(utc("2026-03-10T10:00:00Z")));
+
+        // 12:00 local is outside quiet hours
+        assert!(
+            config
+                .window_end(utc("2026-03-10T15:00:00Z"))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn same_day_window() {
+        let config = quiet_hours("12:00", "14:00");
+
+        let end = config.window_end(utc("2026-03-10T15:30:00Z")).unwrap();
+        assert_eq!(end, Some(utc("2026-03-10T17:00:00Z")));
+        assert!(
+            config
+                .window_end(utc("2026-03-10T17:00:00Z"))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn disabled_or_invalid_config() {
+        let mut config = quiet_hours("22:00", "07:00");
+        config.enabled = false;
+        assert!(
+            config
+                .window_end(utc("2026-03-10T02:30:00Z"))
+                .unwrap()
+                .is_none()
+        );
+
+        assert!(quiet_hours("25:00", "07:00").validate().is_err());
+        let mut bad_zone = quiet_hours("22:00", "07:00");
+        bad_zone.timezone = "Mars/Olympus_Mons".into();
+        assert!(bad_zone.validate().is_err());
+    }
+}
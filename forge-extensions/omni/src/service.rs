@@ -18,10 +18,14 @@ impl OmniService {
     }
 
     pub fn apply_config(&mut self, config: OmniConfig) {
-        self.client = OmniClient::new(
-            config.host.clone().unwrap_or_default(),
-            config.api_key.clone(),
-        );
+        let host = config.host.clone().unwrap_or_default();
+        let options = config.http.clone().unwrap_or_default();
+        self.client = OmniClient::with_options(host.clone(), config.api_key.clone(), &options)
+            .unwrap_or_else(|e| {
+                // Settings are validated on save, but a certificate file can disappear
+                tracing::error!("Invalid Omni HTTP settings, using defaults: {}", e);
+                OmniClient::new(host, config.api_key.clone())
+            });
         self.config = config;
     }
 
@@ -35,64 +39,157 @@ impl OmniService {
         task_status: &str,
         task_url: Option<&str>,
     ) -> Result<()> {
-        if !self.config.enabled {
-            tracing::debug!("Omni notifications disabled");
-            return Ok(());
-        }
-
-        let instance = self
-            .config
-            .instance
-            .as_ref()
-            .ok_or_else(|| anyhow::anyhow!("No Omni instance configured"))?;
-        let recipient = self
-            .config
-            .recipient
-            .as_ref()
-            .ok_or_else(|| anyhow::anyhow!("No recipient configured"))?;
-
-        tracing::info!(
-            "Sending Omni notification - Instance: {}, Recipient: {}, Title: {}",
-            instance,
-            recipient,
-            task_title
-        );
+        let message = Self::task_message(task_title, task_status, task_url);
+        self.send_text_notification(&message).await
+    }
 
-        let message = format!(
+    /// Default text of a task notification.
+    pub fn task_message(task_title: &str, task_status: &str, task_url: Option<&str>) -> String {
+        format!(
             "🎯 Task Complete: {}\n\n\
              Status: {}\n\
              {}",
             task_title,
             task_status,
             task_url.map(|u| format!("URL: {}", u)).unwrap_or_default()
+        )
+    }
+
+    /// Send preformatted text, e.g. a rendered project template, to every configured
+    /// recipient. Fails if any recipient could not be reached.
+    pub async fn send_text_notification(&self, message: &str) -> Result<()> {
+        self.send_notification_with_attachments(message, &[]).await
+    }
+
+    /// Like [`OmniService::send_text_notification`], then send `attachments` to each
+    /// recipient that received the text. Attachments are best effort: a failed
+    /// upload is logged but does not fail the delivery, so a retry never repeats a
+    /// text that already arrived.
+    pub async fn send_notification_with_attachments(
+        &self,
+        message: &str,
+        attachments: &[OmniAttachment],
+    ) -> Result<()> {
+        if !self.config.enabled {
+            tracing::debug!("Omni notifications disabled");
+            return Ok(());
+        }
+
+        let deliveries = self.send_to_recipients(message, attachments).await?;
+        let failures: Vec<String> = deliveries
+            .iter()
+            .filter_map(|delivery| {
+                delivery
+                    .error
+                    .as_ref()
+                    .map(|error| format!("{}: {}", delivery.recipient, error))
+            })
+            .collect();
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(failures.join("; ")))
+        }
+    }
+
+    /// Fan `message` out to every recipient and report each result separately.
+    pub async fn send_text_to_recipients(&self, message: &str) -> Result<Vec<OmniDelivery>> {
+        self.send_to_recipients(message, &[]).await
+    }
+
+    async fn send_to_recipients(
+        &self,
+        message: &str,
+        attachments: &[OmniAttachment],
+    ) -> Result<Vec<OmniDelivery>> {
+        let targets = self.config.targets();
+        if targets.is_empty() {
+            return Err(anyhow::anyhow!("No recipient configured"));
+        }
+
+        let mut deliveries = Vec::with_capacity(targets.len());
+        for target in targets {
+            let Some(instance) = target.instance.as_ref().or(self.config.instance.as_ref()) else {
+                deliveries.push(OmniDelivery {
+                    recipient: target.display_name().to_string(),
+                    instance: None,
+                    response: None,
+                    error: Some("No Omni instance configured".into()),
+                });
+                continue;
+            };
+
+            tracing::info!(
+                "Sending Omni notification - Instance: {}, Recipient: {}",
+                instance,
+                target.recipient
+            );
+
+            let request = target.send_text_request(message.to_string());
+            let result = self.client.send_text(instance, request).await;
+            let (response, error) = match result {
+                Ok(response) => {
+                    tracing::info!("Omni notification sent successfully: {:?}", response);
+                    self.send_attachments(instance, &target, attachments).await;
+                    (Some(response), None)
+                }
+                Err(e) => {
+                    tracing::error!("Failed to send Omni notification: {}", e);
+                    (None, Some(e.to_string()))
+                }
+            };
+
+            deliveries.push(OmniDelivery {
+                recipient: target.display_name().to_string(),
+                instance: Some(instance.clone()),
+                response,
+                error,
+            });
+        }
+
+        Ok(deliveries)
+    }
+
+    /// Send a clearly marked test message to every recipient, whether or not
+    /// notifications are enabled, so settings can be checked before saving them.
+    pub async fn send_test_message(&self) -> Result<Vec<OmniDelivery>> {
+        let message = format!(
+            "🧪 Forge test notification\n\n\
+             This message was sent from Forge settings at {} to check the Omni \
+             configuration. No action is needed.",
+            chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC")
         );
 
-        let request = match self.config.recipient_type {
-            Some(RecipientType::PhoneNumber) => SendTextRequest {
-                phone_number: Some(recipient.clone()),
-                user_id: None,
-                text: message,
-            },
-            Some(RecipientType::UserId) => SendTextRequest {
-                phone_number: None,
-                user_id: Some(recipient.clone()),
-                text: message,
-            },
-            None => SendTextRequest {
-                phone_number: Some(recipient.clone()),
-                user_id: None,
-                text: message,
-            },
-        };
+        self.send_text_to_recipients(&message).await
+    }
 
-        match self.client.send_text(instance, request).await {
-            Ok(response) => {
-                tracing::info!("Omni notification sent successfully: {:?}", response);
-                Ok(())
-            }
-            Err(e) => {
-                tracing::error!("Failed to send Omni notification: {}", e);
-                Err(e)
+    async fn send_attachments(
+        &self,
+        instance: &str,
+        target: &OmniRecipient,
+        attachments: &[OmniAttachment],
+    ) {
+        for attachment in attachments {
+            let result = match attachment.kind {
+                OmniAttachmentKind::Image => {
+                    self.client
+                        .send_media(instance, target.send_media_request(attachment))
+                        .await
+                }
+                OmniAttachmentKind::Document => {
+                    self.client
+                        .send_document(instance, target.send_document_request(attachment))
+                        .await
+                }
+            };
+            if let Err(e) = result {
+                tracing::warn!(
+                    "Failed to send Omni attachment '{}' to {}: {}",
+                    attachment.filename,
+                    target.display_name(),
+                    e
+                );
             }
         }
     }
@@ -1,6 +1,13 @@
+use std::fmt;
+
+use base64::{Engine, engine::general_purpose::STANDARD};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
+use crate::http::OmniHttpConfig;
+use crate::inbound::OmniInboundConfig;
+use crate::quiet_hours::QuietHoursConfig;
+
 /// Local Omni recipient type options.
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
 pub enum RecipientType {
@@ -9,7 +16,7 @@ pub enum RecipientType {
 }
 
 /// Forge-scoped Omni configuration payload.
-#[derive(Clone, Debug, Default, Serialize, Deserialize, TS)]
+#[derive(Clone, Default, Serialize, Deserialize, TS)]
 pub struct OmniConfig {
     pub enabled: bool,
     pub host: Option<String>,
@@ -17,6 +24,214 @@ pub struct OmniConfig {
     pub instance: Option<String>,
     pub recipient: Option<String>,
     pub recipient_type: Option<RecipientType>,
+    /// Additional recipients; when set, replaces `recipient`/`recipient_type`
+    #[serde(default)]
+    pub recipients: Vec<OmniRecipient>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub digest: Option<OmniDigestConfig>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub quiet_hours: Option<QuietHoursConfig>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub rate_limit: Option<OmniRateLimitConfig>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub inbound: Option<OmniInboundConfig>,
+    /// Timeouts, proxy and TLS settings for requests to Omni
+    #[serde(default)]
+    #[ts(optional)]
+    pub http: Option<OmniHttpConfig>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub message: Option<OmniMessageConfig>,
+}
+
+/// Configs end up in logs, so the API key is left out.
+impl fmt::Debug for OmniConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OmniConfig")
+            .field("enabled", &self.enabled)
+            .field("host", &self.host)
+            .field("api_key", &self.api_key.as_ref().map(|_| REDACTED))
+            .field("instance", &self.instance)
+            .field("recipient", &self.recipient)
+            .field("recipient_type", &self.recipient_type)
+            .field("recipients", &self.recipients)
+            .field("digest", &self.digest)
+            .field("quiet_hours", &self.quiet_hours)
+            .field("rate_limit", &self.rate_limit)
+            .field("inbound", &self.inbound)
+            .field("http", &self.http)
+            .field("message", &self.message)
+            .finish()
+    }
+}
+
+impl OmniConfig {
+    /// Recipients to notify: the `recipients` list, or the single legacy recipient.
+    pub fn targets(&self) -> Vec<OmniRecipient> {
+        if !self.recipients.is_empty() {
+            return self.recipients.clone();
+        }
+
+        self.recipient
+            .iter()
+            .filter(|recipient| !recipient.is_empty())
+            .map(|recipient| OmniRecipient {
+                recipient: recipient.clone(),
+                recipient_type: self.recipient_type.clone(),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    /// Copy of this configuration that only addresses `recipient`.
+    pub fn for_recipient(&self, recipient: &OmniRecipient) -> OmniConfig {
+        OmniConfig {
+            recipients: vec![recipient.clone()],
+            ..self.clone()
+        }
+    }
+}
+
+/// One Omni destination, e.g. a WhatsApp group or a Discord channel.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS)]
+pub struct OmniRecipient {
+    pub recipient: String,
+    #[serde(default)]
+    pub recipient_type: Option<RecipientType>,
+    /// Omni instance to send through; defaults to the configured `instance`
+    #[serde(default)]
+    pub instance: Option<String>,
+    /// Only notify for these execution statuses; empty means all
+    #[serde(default)]
+    pub statuses: Vec<String>,
+    /// Display name used in the notification log
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl OmniRecipient {
+    pub fn accepts_status(&self, status: &str) -> bool {
+        self.statuses.is_empty() || self.statuses.iter().any(|allowed| allowed == status)
+    }
+
+    pub fn display_name(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.recipient)
+    }
+
+    /// `(phone_number, user_id)` fields of a send request to this recipient.
+    fn address(&self) -> (Option<String>, Option<String>) {
+        match self.recipient_type {
+            Some(RecipientType::UserId) => (None, Some(self.recipient.clone())),
+            Some(RecipientType::PhoneNumber) | None => (Some(self.recipient.clone()), None),
+        }
+    }
+
+    pub(crate) fn send_text_request(&self, text: String) -> SendTextRequest {
+        let (phone_number, user_id) = self.address();
+        SendTextRequest {
+            phone_number,
+            user_id,
+            text,
+        }
+    }
+
+    pub(crate) fn send_media_request(&self, attachment: &OmniAttachment) -> SendMediaRequest {
+        let (phone_number, user_id) = self.address();
+        SendMediaRequest {
+            phone_number,
+            user_id,
+            media_type: OmniMediaType::Image,
+            media_base64: STANDARD.encode(&attachment.content),
+            mime_type: attachment.mime_type.clone(),
+            caption: attachment.caption.clone(),
+        }
+    }
+
+    pub(crate) fn send_document_request(&self, attachment: &OmniAttachment) -> SendDocumentRequest {
+        let (phone_number, user_id) = self.address();
+        SendDocumentRequest {
+            phone_number,
+            user_id,
+            document_base64: STANDARD.encode(&attachment.content),
+            mime_type: attachment.mime_type.clone(),
+            filename: attachment.filename.clone(),
+            caption: attachment.caption.clone(),
+        }
+    }
+}
+
+/// Extra content of task notifications.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS)]
+pub struct OmniMessageConfig {
+    /// Append files changed, insertions and deletions to the default message;
+    /// templates use their `diff_stats` placeholders instead
+    #[serde(default)]
+    pub diff_summary: bool,
+    /// File sent after the message
+    #[serde(default)]
+    pub attachment: OmniAttachmentMode,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum OmniAttachmentMode {
+    #[default]
+    None,
+    /// The attempt's full diff against its base branch as a `.patch` document
+    Patch,
+}
+
+/// File sent to a recipient after a notification's text.
+#[derive(Clone, Debug)]
+pub struct OmniAttachment {
+    pub kind: OmniAttachmentKind,
+    pub filename: String,
+    pub mime_type: String,
+    pub content: Vec<u8>,
+    pub caption: Option<String>,
+}
+
+/// Omni endpoint an attachment is sent through.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OmniAttachmentKind {
+    /// `send-media`, shown inline
+    Image,
+    /// `send-document`, delivered as a file
+    Document,
+}
+
+/// Groups queued notifications of a project into one summary message.
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct OmniDigestConfig {
+    pub enabled: bool,
+    /// How long the first queued notification waits for others to join the digest
+    #[ts(type = "number")]
+    pub window_secs: u64,
+    /// Send the digest early once this many notifications are waiting
+    pub max_items: u32,
+}
+
+/// Maximum number of messages a recipient receives per period. Messages over the
+/// limit stay queued until the period allows another one.
+#[derive(Clone, Debug, Serialize, Deserialize, TS)]
+pub struct OmniRateLimitConfig {
+    pub max_messages: u32,
+    #[ts(type = "number")]
+    pub period_secs: u64,
+}
+
+impl Default for OmniDigestConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_secs: 300,
+            max_items: 20,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -54,7 +269,7 @@ pub(crate) struct InstancesResponse {
     pub channels: Vec<RawOmniInstance>,
 }
 
-#[derive(Debug, Serialize, TS)]
+#[derive(Serialize, TS)]
 pub struct SendTextRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub phone_number: Option<String>,
@@ -63,7 +278,111 @@ pub struct SendTextRequest {
     pub text: String,
 }
 
-#[derive(Debug, Deserialize, TS)]
+/// Requests are logged on every send, so the recipient is masked and the message
+/// text is left out.
+impl fmt::Debug for SendTextRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendTextRequest")
+            .field("phone_number", &self.phone_number.as_deref().map(mask))
+            .field("user_id", &self.user_id.as_deref().map(mask))
+            .field(
+                "text",
+                &format_args!("<{} chars>", self.text.chars().count()),
+            )
+            .finish()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum OmniMediaType {
+    Image,
+    Video,
+}
+
+#[derive(Serialize, TS)]
+pub struct SendMediaRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone_number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    pub media_type: OmniMediaType,
+    /// File content, base64-encoded
+    pub media_base64: String,
+    pub mime_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+}
+
+impl fmt::Debug for SendMediaRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendMediaRequest")
+            .field("phone_number", &self.phone_number.as_deref().map(mask))
+            .field("user_id", &self.user_id.as_deref().map(mask))
+            .field("media_type", &self.media_type)
+            .field("mime_type", &self.mime_type)
+            .field(
+                "media_base64",
+                &format_args!("<{} bytes>", self.media_base64.len()),
+            )
+            .finish()
+    }
+}
+
+#[derive(Serialize, TS)]
+pub struct SendDocumentRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone_number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<String>,
+    /// File content, base64-encoded
+    pub document_base64: String,
+    pub mime_type: String,
+    pub filename: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub caption: Option<String>,
+}
+
+impl fmt::Debug for SendDocumentRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SendDocumentRequest")
+            .field("phone_number", &self.phone_number.as_deref().map(mask))
+            .field("user_id", &self.user_id.as_deref().map(mask))
+            .field("mime_type", &self.mime_type)
+            .field("filename", &self.filename)
+            .field(
+                "document_base64",
+                &format_args!("<{} bytes>", self.document_base64.len()),
+            )
+            .finish()
+    }
+}
+
+/// Shown in `Debug` output in place of a secret.
+pub(crate) const REDACTED: &str = "<redacted>";
+
+/// Keep only the last four characters of a recipient.
+fn mask(value: &str) -> String {
+    let count = value.chars().count();
+    let visible: String = value.chars().skip(count.saturating_sub(4)).collect();
+    if count <= 4 {
+        "***".to_string()
+    } else {
+        format!("***{visible}")
+    }
+}
+
+/// Result of sending to one recipient during a fan-out.
+#[derive(Debug, Serialize, TS)]
+pub struct OmniDelivery {
+    pub recipient: String,
+    pub instance: Option<String>,
+    /// Omni's answer, including the `message_id`, when the send succeeded
+    pub response: Option<SendTextResponse>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
 pub struct SendTextResponse {
     pub success: bool,
     pub message_id: Option<String>,
@@ -95,6 +414,13 @@ mod tests {
             instance: None,
             recipient: None,
             recipient_type: None,
+            recipients: vec![],
+            digest: None,
+            quiet_hours: None,
+            rate_limit: None,
+            inbound: None,
+            http: None,
+            message: None,
         };
 
         assert!(!config.enabled);
@@ -104,6 +430,69 @@ mod tests {
         assert!(config.recipient_type.is_none());
     }
 
+    #[test]
+    fn omni_config_debug_hides_secrets() {
+        let config = OmniConfig {
+            api_key: Some("omni-key-123".into()),
+            inbound: Some(OmniInboundConfig {
+                enabled: true,
+                secret: "inbound-secret".into(),
+                allowed_senders: vec![],
+            }),
+            ..Default::default()
+        };
+        let logged = format!("{config:?}");
+        assert!(!logged.contains("omni-key-123"));
+        assert!(!logged.contains("inbound-secret"));
+        assert!(logged.contains(REDACTED));
+    }
+
+    #[test]
+    fn send_text_request_debug_hides_recipient_and_text() {
+        let request = SendTextRequest {
+            phone_number: Some("+5511999991234".into()),
+            user_id: None,
+            text: "Task done, token abc123".into(),
+        };
+        let logged = format!("{request:?}");
+        assert!(logged.contains("***1234"));
+        assert!(!logged.contains("5511999"));
+        assert!(!logged.contains("abc123"));
+        assert!(logged.contains("<23 chars>"));
+    }
+
+    #[test]
+    fn targets_fall_back_to_legacy_recipient() {
+        let legacy = OmniConfig {
+            recipient: Some("+15550001111".into()),
+            recipient_type: Some(RecipientType::PhoneNumber),
+            ..Default::default()
+        };
+        let targets = legacy.targets();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].recipient, "+15550001111");
+
+        let group = OmniRecipient {
+            recipient: "120363000000000000@g.us".into(),
+            recipient_type: Some(RecipientType::UserId),
+            instance: Some("whatsapp".into()),
+            statuses: vec!["failed".into()],
+            label: Some("team".into()),
+        };
+        let config = OmniConfig {
+            recipients: vec![group.clone()],
+            ..legacy
+        };
+        let targets = config.targets();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].display_name(), "team");
+        assert!(!targets[0].accepts_status("completed"));
+
+        let request = group.send_text_request("hi".into());
+        assert_eq!(request.user_id.as_deref(), Some("120363000000000000@g.us"));
+        assert!(request.phone_number.is_none());
+    }
+
     #[test]
     fn test_send_text_request_serialization() {
         let req = SendTextRequest {
@@ -42,6 +42,7 @@ export function OmniCard({ value, onChange }: OmniCardProps) {
         instance: null,
         recipient: null,
         recipient_type: null,
+        recipients: [],
       },
     });
   };
@@ -61,7 +62,7 @@ export function OmniCard({ value, onChange }: OmniCardProps) {
         <div className="flex items-center space-x-2">
           <Checkbox
             id="omni-enabled"
-            checked={value.omni_enabled && isConfigured}
+            checked={!!value.omni_enabled && isConfigured}
             onCheckedChange={(checked: boolean) => {
               onChange({
                 ...value,
@@ -22,7 +22,7 @@ import { Loader2, MessageSquare } from 'lucide-react';
 import NiceModal, { useModal } from '@ebay/nice-modal-react';
 import { omniApi } from './api';
 import type { OmniInstance } from './types';
-import type { ForgeProjectSettings } from 'shared/forge-types';
+import type { ForgeProjectSettings, OmniConfig } from 'shared/forge-types';
 
 interface OmniModalProps {
   forgeSettings: ForgeProjectSettings;
@@ -33,7 +33,9 @@ const OmniModalImpl = ({ forgeSettings, onChange }: OmniModalProps) => {
   const modal = useModal();
   const [loading, setLoading] = useState(false);
   const [validating, setValidating] = useState(false);
+  const [testing, setTesting] = useState(false);
   const [error, setError] = useState<string | null>(null);
+  const [testResult, setTestResult] = useState<string | null>(null);
   const [instances, setInstances] = useState<OmniInstance[]>([]);
   
   const [formData, setFormData] = useState({
@@ -70,6 +72,51 @@ const OmniModalImpl = ({ forgeSettings, onChange }: OmniModalProps) => {
     }
   };
 
+  const buildOmniConfig = (): OmniConfig => ({
+    ...forgeSettings.omni_config,
+    enabled: true,
+    host: formData.host,
+    api_key: formData.api_key,
+    instance: formData.instance,
+    recipient: formData.recipient,
+    recipient_type: formData.recipient_type as any,
+    recipients: forgeSettings.omni_config?.recipients ?? [],
+  });
+
+  const sendTestNotification = async () => {
+    if (!formData.instance || !formData.recipient) {
+      setError('Please select an instance and enter a recipient');
+      return;
+    }
+
+    setTesting(true);
+    setError(null);
+    setTestResult(null);
+
+    try {
+      const result = await omniApi.testNotification(buildOmniConfig());
+      const failures = result.deliveries
+        .filter((delivery) => delivery.error)
+        .map((delivery) => `${delivery.recipient}: ${delivery.error}`);
+
+      if (result.error || failures.length > 0) {
+        setError(result.error || failures.join('; '));
+      } else {
+        const ids = result.deliveries
+          .map((delivery) => delivery.response?.message_id)
+          .filter(Boolean)
+          .join(', ');
+        setTestResult(
+          ids ? `Test message sent (message id: ${ids})` : 'Test message sent'
+        );
+      }
+    } catch (e: any) {
+      setError(e.message || 'Failed to send test notification');
+    } finally {
+      setTesting(false);
+    }
+  };
+
   const handleSave = async () => {
     if (!formData.instance || !formData.recipient) {
       setError('Please select an instance and enter a recipient');
@@ -83,14 +130,7 @@ const OmniModalImpl = ({ forgeSettings, onChange }: OmniModalProps) => {
       const updatedSettings: ForgeProjectSettings = {
         ...forgeSettings,
         omni_enabled: true,
-        omni_config: {
-          enabled: true,
-          host: formData.host,
-          api_key: formData.api_key,
-          instance: formData.instance,
-          recipient: formData.recipient,
-          recipient_type: formData.recipient_type as any,
-        },
+        omni_config: buildOmniConfig(),
       };
 
       // Persist to backend
@@ -224,12 +264,32 @@ const OmniModalImpl = ({ forgeSettings, onChange }: OmniModalProps) => {
               <AlertDescription>{error}</AlertDescription>
             </Alert>
           )}
+
+          {testResult && !error && (
+            <Alert>
+              <AlertDescription>{testResult}</AlertDescription>
+            </Alert>
+          )}
         </div>
         
         <DialogFooter>
           <Button variant="outline" onClick={() => modal.hide()}>
             Cancel
           </Button>
+          <Button
+            variant="outline"
+            onClick={sendTestNotification}
+            disabled={testing || instances.length === 0}
+          >
+            {testing ? (
+              <>
+                <Loader2 className="mr-2 h-4 w-4 animate-spin" />
+                Sending...
+              </>
+            ) : (
+              'Send Test'
+            )}
+          </Button>
           <Button 
             onClick={handleSave}
             disabled={loading || instances.length === 0}
@@ -1,4 +1,5 @@
 import { OmniInstance } from './types';
+import type { OmniConfig, OmniDelivery } from 'shared/forge-types';
 
 interface ApiEnvelope<T> {
   success: boolean;
@@ -65,9 +66,26 @@ export const omniApi = {
     return response.json();
   },
 
-  testNotification: async (): Promise<string> => {
-    return request<string>('/api/omni/test', {
+  testNotification: async (
+    config: OmniConfig
+  ): Promise<{
+    success: boolean;
+    deliveries: OmniDelivery[];
+    error?: string;
+  }> => {
+    // Forge endpoint, returns data directly like validateConfig
+    const response = await fetch('/api/forge/omni/test', {
       method: 'POST',
+      headers: {
+        'Content-Type': 'application/json',
+      },
+      body: JSON.stringify({ config }),
     });
+
+    if (!response.ok) {
+      throw new Error(`Request failed with ${response.status}`);
+    }
+
+    return response.json();
   },
 };
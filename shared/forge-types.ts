@@ -4,11 +4,247 @@
 
 type JsonValue = any;
 
-export type ForgeProjectSettings = { omni_enabled: boolean, omni_config: OmniConfig | null, };
+export type ForgeProjectSettings = { 
+/**
+ * Unset inherits the level below; off when no level sets it
+ */
+omni_enabled: boolean | null, omni_config: OmniConfig | null, 
+/**
+ * Channels that receive task notifications. Empty means Omni only.
+ */
+notification_channels: Array<NotificationChannelConfig>, 
+/**
+ * Jinja template for the notification text; see `forge_notifications::TemplateContext`
+ * for the available placeholders. Unset keeps the default message.
+ */
+notification_template: string | null, 
+/**
+ * Events that queue notifications. Empty means execution exits only.
+ */
+notification_types: Array<NotificationType>, 
+/**
+ * Template per event; `notification_template` still applies to execution exits
+ * without one of their own
+ */
+notification_templates: { [key in NotificationType]?: string }, 
+/**
+ * Filters an execution event must pass before it is delivered
+ */
+notification_rules: Array<NotificationRule>, };
 
-export type ProjectConfig = { project_id: string, custom_executors: JsonValue | null, forge_config: JsonValue | null, };
+export type ProjectConfig = { project_id: string, custom_executors: CustomExecutors | null, forge_config: JsonValue | null, };
 
-export type OmniConfig = { enabled: boolean, host: string | null, api_key: string | null, instance: string | null, recipient: string | null, recipient_type: RecipientType | null, };
+export type CustomExecutors = { [key in string]?: ExecutorOverlay };
+
+export type ExecutorOverlay = { 
+/**
+ * Variant used when an attempt names none; the upstream default when unset
+ */
+default_variant: string | null, 
+/**
+ * Variants of the project, by name
+ */
+variants: { [key in string]?: ExecutorVariant }, model: string | null, 
+/**
+ * Appended to the executor's command line
+ */
+additional_params: Array<string>, env: { [key in string]?: string }, };
+
+export type ExecutorVariant = { 
+/**
+ * Upstream variant this one builds on; the executor's default when unset
+ */
+base_variant: string | null, model: string | null, 
+/**
+ * Appended to the executor's command line
+ */
+additional_params: Array<string>, env: { [key in string]?: string }, };
+
+export type ExecutorOverrides = { model: string | null, 
+/**
+ * Appended to the executor's command line
+ */
+additional_params: Array<string>, env: { [key in string]?: string }, };
+
+export type EffectiveSettings = { settings: ForgeProjectSettings, 
+/**
+ * Origin by dotted field path, e.g. `omni_config.host`. A nested object
+ * nobody set is listed once, under its own name.
+ */
+origins: { [key in string]?: ConfigOrigin }, };
+
+export type ConfigOrigin = "default" | "global" | "project" | "task";
+
+export type SettingsHistoryEntry = { id: number, scope: SettingsScope, 
+/**
+ * Project or task id; `None` for the global settings
+ */
+scope_id: string | null, 
+/**
+ * Settings before the write, `None` when there were none
+ */
+old_value: Record<string, unknown> | null, new_value: Record<string, unknown>, source: SettingsSource, created_at: string, };
+
+export type SettingsScope = "global" | "project" | "task";
+
+export type SettingsSource = "api" | "mcp" | "import";
+
+export type ProjectBundle = { format_version: number, exported_at: string, 
+/**
+ * Whether secret fields of the settings were exported; otherwise they are empty
+ */
+includes_secrets: boolean, project: BundledProject, 
+/**
+ * Project settings, as stored
+ */
+settings: JsonValue | null, custom_executors: CustomExecutors | null, task_templates: Array<BundledTaskTemplate>, 
+/**
+ * Only filled when tasks were requested
+ */
+tasks: Array<BundledTask>, };
+
+export type BundledProject = { id: string, name: string, git_repo_path: string, setup_script: string | null, dev_script: string | null, cleanup_script: string | null, copy_files: string | null, };
+
+export type BundledTaskTemplate = { id: string, title: string, description: string | null, template_name: string, };
+
+export type BundledTask = { id: string, title: string, description: string | null, status: string, 
+/**
+ * Settings that override the project's for this task
+ */
+settings: JsonValue | null, };
+
+export type ProjectImportRequest = { bundle: ProjectBundle, 
+/**
+ * Name for the new project; the bundle's when unset
+ */
+name: string | null, 
+/**
+ * Where the repository is checked out on this machine; the bundle's path
+ * when unset
+ */
+git_repo_path: string | null, 
+/**
+ * Report what an import would do without writing anything
+ */
+dry_run: boolean, };
+
+export type ProjectImportReport = { 
+/**
+ * Whether anything was written; false for dry runs and blocked imports
+ */
+imported: boolean, 
+/**
+ * Id of the new project, or the one it would get
+ */
+project_id: string, 
+/**
+ * Bundle ids of the project, templates and tasks mapped to their new ids
+ */
+id_map: { [key in string]?: string }, task_templates: number, tasks: number, conflicts: Array<ImportConflict>, };
+
+export type ImportConflict = { kind: ImportConflictKind, message: string, };
+
+export type ImportConflictKind = "project_path_in_use" | "duplicate_template_name" | "secrets_not_included";
+
+export type OmniConfig = { enabled: boolean, host: string | null, api_key: string | null, instance: string | null, recipient: string | null, recipient_type: RecipientType | null, 
+/**
+ * Additional recipients; when set, replaces `recipient`/`recipient_type`
+ */
+recipients: Array<OmniRecipient>, digest?: OmniDigestConfig, quiet_hours?: QuietHoursConfig, rate_limit?: OmniRateLimitConfig, inbound?: OmniInboundConfig, 
+/**
+ * Timeouts, proxy and TLS settings for requests to Omni
+ */
+http?: OmniHttpConfig, message?: OmniMessageConfig, };
+
+export type OmniRecipient = { recipient: string, recipient_type: RecipientType | null, 
+/**
+ * Omni instance to send through; defaults to the configured `instance`
+ */
+instance: string | null, 
+/**
+ * Only notify for these execution statuses; empty means all
+ */
+statuses: Array<string>, 
+/**
+ * Display name used in the notification log
+ */
+label: string | null, };
+
+export type OmniDigestConfig = { enabled: boolean, 
+/**
+ * How long the first queued notification waits for others to join the digest
+ */
+window_secs: number, 
+/**
+ * Send the digest early once this many notifications are waiting
+ */
+max_items: number, };
+
+export type QuietHoursConfig = { enabled: boolean, 
+/**
+ * Local start time, `HH:MM`
+ */
+start: string, 
+/**
+ * Local end time, `HH:MM`; earlier than `start` for windows spanning midnight
+ */
+end: string, 
+/**
+ * IANA time zone, e.g. `America/Sao_Paulo`
+ */
+timezone: string, action: QuietHoursAction, };
+
+export type QuietHoursAction = "hold" | "skip";
+
+export type OmniRateLimitConfig = { max_messages: number, period_secs: number, };
+
+export type OmniInboundConfig = { enabled: boolean, 
+/**
+ * Shared secret Omni sends in the `X-Omni-Secret` header
+ */
+secret: string, 
+/**
+ * Phone numbers or user ids allowed to send commands; empty means the
+ * configured notification recipients
+ */
+allowed_senders: Array<string>, };
+
+export type OmniHttpConfig = { 
+/**
+ * Seconds to wait for a connection; defaults to 10
+ */
+connect_timeout_secs: number | null, 
+/**
+ * Seconds a whole request may take, including the response; defaults to 30
+ */
+request_timeout_secs: number | null, 
+/**
+ * HTTP(S) proxy for all Omni requests, e.g. `http://proxy.local:3128`.
+ * Unset uses the `HTTPS_PROXY`/`HTTP_PROXY` environment variables.
+ */
+proxy: string | null, 
+/**
+ * PEM files with additional root certificates, e.g. a private CA
+ */
+root_certificates: Array<string>, 
+/**
+ * Skip TLS certificate verification, for self-hosted instances with
+ * self-signed certificates
+ */
+accept_invalid_certs: boolean, user_agent: string | null, };
+
+export type OmniMessageConfig = { 
+/**
+ * Append files changed, insertions and deletions to the default message;
+ * templates use their `diff_stats` placeholders instead
+ */
+diff_summary: boolean, 
+/**
+ * File sent after the message
+ */
+attachment: OmniAttachmentMode, };
+
+export type OmniAttachmentMode = "none" | "patch";
 
 export type RecipientType = "PhoneNumber" | "UserId";
 
@@ -17,3 +253,61 @@ export type OmniInstance = { instance_name: string, channel_type: string, displa
 export type SendTextRequest = { phone_number: string | null, user_id: string | null, text: string, };
 
 export type SendTextResponse = { success: boolean, message_id: string | null, status: string, error: string | null, };
+
+export type OmniMediaType = "image" | "video";
+
+export type SendMediaRequest = { phone_number: string | null, user_id: string | null, media_type: OmniMediaType, 
+/**
+ * File content, base64-encoded
+ */
+media_base64: string, mime_type: string, caption: string | null, };
+
+export type SendDocumentRequest = { phone_number: string | null, user_id: string | null, 
+/**
+ * File content, base64-encoded
+ */
+document_base64: string, mime_type: string, filename: string, caption: string | null, };
+
+export type OmniDelivery = { recipient: string, instance: string | null, 
+/**
+ * Omni's answer, including the `message_id`, when the send succeeded
+ */
+response: SendTextResponse | null, error: string | null, };
+
+export type NotificationChannelConfig = { "type": "omni" } | { "type": "slack" } & SlackChannelConfig | { "type": "email" } & EmailChannelConfig | { "type": "command" } & CommandChannelConfig | { "type": "webhook" } & WebhookChannelConfig;
+
+export type SlackChannelConfig = { webhook_url: string, username: string | null, channel: string | null, };
+
+export type EmailChannelConfig = { smtp_host: string, smtp_port: number | null, username: string | null, password: string | null, from: string, to: Array<string>, 
+/**
+ * Upgrade the connection with STARTTLS instead of connecting over implicit TLS.
+ */
+starttls: boolean, };
+
+export type CommandChannelConfig = { command: string, args: Array<string>, timeout_secs: bigint | null, };
+
+export type WebhookChannelConfig = { url: string, 
+/**
+ * Shared secret used to sign the request body (`X-Forge-Signature` header).
+ */
+secret: string, };
+
+export type NotificationRule = { name: string, 
+/**
+ * Execution run reasons, e.g. `codingagent`
+ */
+run_reasons: Array<string>, 
+/**
+ * Terminal statuses: `completed`, `failed`, `killed`
+ */
+statuses: Array<string>, 
+/**
+ * Executor names, compared case-insensitively
+ */
+executors: Array<string>, 
+/**
+ * Glob matched against the attempt branch; `*` and `?` are supported
+ */
+branch_pattern: string | null, };
+
+export type NotificationType = "execution_completed" | "task_status_changed" | "pr_created" | "pr_merged" | "merge_conflict";